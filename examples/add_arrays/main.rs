@@ -2,9 +2,9 @@ use rand::Rng;
 
 // wgpu's default `max_workgroups_per_dimension`
 // can be changed using `hac::Limits` on Context creation
-const N: usize = 1 << 16 - 1;
+const N: usize = 1 << (16 - 1);
 
-const KERNEL_SOURCE: &'static str = r#"
+const KERNEL_SOURCE: &str = r#"
 struct ComputeInput {
     // wgsl builtin variables can be found in the following link
     // https://www.w3.org/TR/WGSL/#builtin-values
@@ -0,0 +1,76 @@
+use rand::Rng;
+
+const N: usize = 1 << 15;
+const BINS: usize = 16;
+
+const KERNEL_SOURCE: &str = r#"
+struct ComputeInput {
+    @builtin(global_invocation_id) id: vec3<u32>,
+}
+
+@group(0) @binding(0)
+var<storage, read> values: array<u32>;
+@group(0) @binding(1)
+var<storage, read_write> bins: array<atomic<u32>>;
+@group(0) @binding(2)
+var<storage, read_write> total: atomic<u32>;
+
+@compute @workgroup_size(64)
+fn main(input: ComputeInput) {
+    let i = input.id.x;
+    if (i >= arrayLength(&values)) {
+        return;
+    }
+
+    // many invocations update the same counters at the same time
+    atomicAdd(&bins[values[i] % arrayLength(&bins)], 1u);
+    atomicAdd(&total, 1u);
+}"#;
+
+fn main() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let mut rng = rand::thread_rng();
+
+    let mut values = vec![0u32; N];
+    rng.fill(&mut values[..]);
+
+    let buf_values = context.buffer_from_slice(&values);
+
+    // atomic buffers start zeroed, there's no need to upload the initial counts
+    let buf_bins = context.atomic_buffer::<u32>(BINS as u64);
+    let buf_total = context.atomic_buffer::<u32>(1);
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buf_values, hac::BufferAccess::ReadOnly) // @binding(0)
+        .push_buffer(&buf_bins, hac::BufferAccess::ReadWrite) // @binding(1)
+        .push_buffer(&buf_total, hac::BufferAccess::ReadWrite) // @binding(2)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(KERNEL_SOURCE);
+
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    kernel.dispatch(hac::Range::d1((N / 64) as u32));
+
+    // a single counter can be read without copying the whole buffer
+    let total = buf_total.read_element(0);
+    let bins = buf_bins.read_to_vec();
+
+    let mut expected = [0u32; BINS];
+    values
+        .iter()
+        .for_each(|&v| expected[v as usize % BINS] += 1);
+
+    assert_eq!(total as usize, N);
+    assert_eq!(bins, expected);
+    bins.iter()
+        .enumerate()
+        .for_each(|(i, count)| println!("bin {i:>2}: {count}"));
+}
@@ -20,14 +20,15 @@ fn main() {
     let input = include_bytes!("polar_bear.jpg");
     let image = image::load_from_memory(input).unwrap().to_rgba8();
 
-    let context = hac::Context::new(&hac::ContextInfo {
-        features: hac::Features::PUSH_CONSTANTS,
-        limits: hac::Limits {
-            max_push_constant_size: 8,
-            ..Default::default()
-        },
-        ..Default::default()
-    });
+    let context = hac::Context::new(
+        &hac::ContextInfo::builder()
+            .features(hac::Features::PUSH_CONSTANTS)
+            .limits(hac::Limits {
+                max_push_constant_size: 8,
+                ..Default::default()
+            })
+            .build(),
+    );
 
     let sampler = context.sampler(&hac::SamplerInfo {
         mag_filter: hac::FilterMode::Linear,
@@ -37,10 +38,10 @@ fn main() {
         ..Default::default()
     });
 
-    // the SamplerBindingType has to be filtering because `FilterMode::Linear` requires it
+    // the binding type is filtering because `FilterMode::Linear` requires it
     let sampler_bind_group = context
         .bind_group_descriptor()
-        .push_sampler(&sampler, hac::SamplerBindingType::Filtering)
+        .push_sampler_auto(&sampler)
         .into_bind_group();
 
     // the image to be sampled with a filtering sampler requires itself to be filterable
@@ -81,30 +82,22 @@ fn main() {
         program: &gaussian_program,
         entry_point: "gaussian_pass",
         bind_groups: &[&sampler_bind_group, &img_bind_group0, &gauss_bind_group],
-        push_constants_range: Some(0..8),
+        push_constants_range: None, // reflected from the program: 0..8
     });
 
-    let (width, height) = image.dimensions();
-    let global_workgroup = hac::Range::d2(width, height);
+    let global_workgroup = hac::Range::d2(im0.width(), im0.height());
 
     context
         .command_queue()
         .enqueue_set_kernel(&gaussian_kernel)
-        .enqueue_set_push_constants(0, hac::cast_slice(&[1i32, 0]))
+        .enqueue_set_push_constants_typed(&[1i32, 0])
         .enqueue_dispatch(global_workgroup)
         .enqueue_set_bind_group(1, &img_bind_group1)
-        .enqueue_set_push_constants(0, hac::cast_slice(&[0i32, 1]))
+        .enqueue_set_push_constants_typed(&[0i32, 1])
         .enqueue_dispatch(global_workgroup)
         .execute();
 
-    let output_bytes = im0.read_to_vec();
-
-    image::save_buffer(
-        "gaussian_blur_output.png",
-        &output_bytes,
-        width,
-        height,
-        image::ColorType::Rgba8,
-    )
-    .unwrap();
+    im0.to_rgba8_image()
+        .save("gaussian_blur_output.png")
+        .unwrap();
 }
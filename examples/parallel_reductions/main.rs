@@ -0,0 +1,104 @@
+use std::time::Instant;
+
+const N: u32 = 1 << 20;
+const WORKGROUP_SIZE: u32 = 256;
+
+// each workgroup sums a block of the input and adds it to the total
+const KERNEL_SOURCE: &str = r#"
+@group(0) @binding(0)
+var<storage, read> values: array<u32>;
+@group(0) @binding(1)
+var<storage, read_write> total: atomic<u32>;
+
+var<workgroup> partial: atomic<u32>;
+
+@compute @workgroup_size(256)
+fn main(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(local_invocation_index) local_index: u32,
+) {
+    // workgroup memory isn't zero-initialized on every backend
+    if (local_index == 0u) {
+        atomicStore(&partial, 0u);
+    }
+
+    workgroupBarrier();
+
+    if (global_id.x < arrayLength(&values)) {
+        atomicAdd(&partial, values[global_id.x]);
+    }
+
+    workgroupBarrier();
+
+    if (local_index == 0u) {
+        atomicAdd(&total, atomicLoad(&partial));
+    }
+}"#;
+
+struct Reduction {
+    total: hac::Buffer<u32>,
+    kernel: hac::Kernel,
+}
+
+impl Reduction {
+    fn new(context: &hac::Context, program: &hac::Program, values: &hac::Buffer<u32>) -> Self {
+        let total = context.atomic_buffer::<u32>(1);
+
+        let bind_group = context
+            .bind_group_descriptor()
+            .push_buffer(values, hac::BufferAccess::ReadOnly)
+            .push_buffer(&total, hac::BufferAccess::ReadWrite)
+            .into_bind_group();
+
+        let kernel = context.kernel(&hac::KernelInfo {
+            program,
+            entry_point: "main",
+            bind_groups: &[&bind_group],
+            push_constants_range: None,
+        });
+
+        Self { total, kernel }
+    }
+
+    fn queue<'a>(&'a self, context: &'a hac::Context) -> hac::CommandQueue<'a> {
+        context
+            .command_queue()
+            .enqueue_set_kernel(&self.kernel)
+            .enqueue_dispatch(N / WORKGROUP_SIZE)
+    }
+}
+
+fn main() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let program = context.program_from_wgsl(KERNEL_SOURCE);
+
+    // two independent inputs, the reductions don't share any resource
+    let ones = context.buffer_from_slice(&vec![1u32; N as usize]);
+    let twos = context.buffer_from_slice(&vec![2u32; N as usize]);
+
+    let a = Reduction::new(&context, &program, &ones);
+    let b = Reduction::new(&context, &program, &twos);
+
+    // warm up, the first submission pays for the lazy initialization of the device
+    context.command_queue_parallel([a.queue(&context), b.queue(&context)]);
+    context.wait_idle();
+
+    let start = Instant::now();
+    a.queue(&context).execute();
+    context.wait_idle();
+    b.queue(&context).execute();
+    context.wait_idle();
+    let sequential = start.elapsed();
+
+    let start = Instant::now();
+    context.command_queue_parallel([a.queue(&context), b.queue(&context)]);
+    context.wait_idle();
+    let parallel = start.elapsed();
+
+    // every submission adds to the totals: warm up, sequential and parallel
+    assert_eq!(a.total.read_element(0), 3 * N);
+    assert_eq!(b.total.read_element(0), 3 * 2 * N);
+
+    println!("sequential: {sequential:?}");
+    println!("parallel:   {parallel:?}");
+}
@@ -0,0 +1,76 @@
+// Runs in the browser with WebGPU, build it with:
+//
+// RUSTFLAGS=--cfg=web_sys_unstable_apis cargo build --example wasm_add_arrays --target wasm32-unknown-unknown
+//
+// and load it with wasm-bindgen, it also runs natively with `cargo run --example wasm_add_arrays`.
+
+const N: usize = 1024;
+
+const KERNEL_SOURCE: &str = r#"
+@group(0) @binding(0)
+var<storage, read> a: array<f32>;
+@group(0) @binding(1)
+var<storage, read> b: array<f32>;
+@group(0) @binding(2)
+var<storage, read_write> c: array<f32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    c[id.x] = a[id.x] + b[id.x];
+}"#;
+
+async fn run() {
+    // blocking isn't allowed in the browser, so the context is created asynchronously
+    let context = hac::Context::new_async(&hac::ContextInfo::default())
+        .await
+        .expect("failed to create the context");
+
+    let a: Vec<f32> = (0..N).map(|i| i as f32).collect();
+    let b: Vec<f32> = (0..N).map(|i| (N - i) as f32).collect();
+
+    let buf_a = context.buffer_from_slice(&a);
+    let buf_b = context.buffer_from_slice(&b);
+    let buf_c = context.buffer::<f32>(N as u64);
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buf_a, hac::BufferAccess::ReadOnly)
+        .push_buffer(&buf_b, hac::BufferAccess::ReadOnly)
+        .push_buffer(&buf_c, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(KERNEL_SOURCE);
+
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    kernel.dispatch(hac::Range::d1(N as u32));
+
+    // and so is the readback, `read_to_vec()` would block until the buffer is mapped
+    let c = buf_c.read_to_vec_async().await;
+
+    assert!(c.iter().all(|&c| c == N as f32));
+    log(&format!("{} sums of {N} are correct", c.len()));
+}
+
+#[cfg(target_arch = "wasm32")]
+fn log(message: &str) {
+    web_sys::console::log_1(&message.into());
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn log(message: &str) {
+    println!("{message}");
+}
+
+fn main() {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_futures::spawn_local(run());
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pollster::block_on(run());
+}
@@ -0,0 +1,143 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::{CommandQueue, Kernel, Range};
+
+/// Durations of the dispatches timed by `Kernel::bench()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchResult {
+    /// Duration of the fastest dispatch.
+    pub min: Duration,
+    /// Average duration of the dispatches.
+    pub mean: Duration,
+    /// Duration of the slowest dispatch.
+    pub max: Duration,
+    /// Number of timed dispatches.
+    pub iterations: u32,
+    /// Whether the dispatches were timed on the device with timestamp queries
+    /// or on the host with a wall clock.
+    pub device_timestamps: bool,
+}
+
+impl BenchResult {
+    fn from_durations(durations: &[Duration], device_timestamps: bool) -> Self {
+        let total: Duration = durations.iter().sum();
+
+        Self {
+            min: durations.iter().copied().min().unwrap_or_default(),
+            mean: total / durations.len() as u32,
+            max: durations.iter().copied().max().unwrap_or_default(),
+            iterations: durations.len() as u32,
+            device_timestamps,
+        }
+    }
+}
+
+/// Dispatches `kernel` `warmup` times and then times `iterations` dispatches of it.
+pub(crate) fn bench(
+    kernel: &Kernel,
+    workgroups: Range,
+    iterations: u32,
+    warmup: u32,
+) -> BenchResult {
+    assert!(iterations > 0, "at least one dispatch has to be timed");
+
+    let queue = || CommandQueue {
+        device: Arc::clone(&kernel.device),
+        cmd_queue: std::collections::VecDeque::new(),
+    };
+
+    (0..warmup).for_each(|_| {
+        queue()
+            .enqueue_set_kernel(kernel)
+            .enqueue_dispatch(workgroups)
+            .execute();
+    });
+    kernel.device.handle.poll(wgpu::Maintain::Wait);
+
+    let device = &kernel.device;
+    if device
+        .handle
+        .features()
+        .contains(wgpu::Features::TIMESTAMP_QUERY)
+    {
+        let durations = device_durations(device, iterations, |encoder| {
+            queue()
+                .enqueue_set_kernel(kernel)
+                .enqueue_dispatch(workgroups)
+                .encode(encoder);
+        });
+
+        return BenchResult::from_durations(&durations, true);
+    }
+
+    let durations: Vec<_> = (0..iterations)
+        .map(|_| {
+            let start = Instant::now();
+
+            queue()
+                .enqueue_set_kernel(kernel)
+                .enqueue_dispatch(workgroups)
+                .execute();
+            device.handle.poll(wgpu::Maintain::Wait);
+
+            start.elapsed()
+        })
+        .collect();
+
+    BenchResult::from_durations(&durations, false)
+}
+
+/// Times `iterations` recordings of `encode` with a pair of timestamps around each of them.
+fn device_durations(
+    device: &crate::Device,
+    iterations: u32,
+    encode: impl Fn(&mut wgpu::CommandEncoder),
+) -> Vec<Duration> {
+    let query_set = device.handle.create_query_set(&wgpu::QuerySetDescriptor {
+        label: Some("Bench query set"),
+        ty: wgpu::QueryType::Timestamp,
+        count: 2 * iterations,
+    });
+
+    let size = 2 * iterations as wgpu::BufferAddress * wgpu::QUERY_SIZE as wgpu::BufferAddress;
+    let staging = device.handle.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Bench staging buffer"),
+        size,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device
+        .handle
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Bench command encoder"),
+        });
+
+    for i in 0..iterations {
+        encoder.write_timestamp(&query_set, 2 * i);
+        encode(&mut encoder);
+        encoder.write_timestamp(&query_set, 2 * i + 1);
+    }
+
+    encoder.resolve_query_set(&query_set, 0..2 * iterations, &staging, 0);
+    device.queue.submit(std::iter::once(encoder.finish()));
+
+    let slice = staging.slice(..);
+    slice.map_async(wgpu::MapMode::Read, move |_| {});
+    device.handle.poll(wgpu::Maintain::Wait);
+
+    // the period is the number of nanoseconds per tick of the timestamps
+    let period = device.queue.get_timestamp_period() as f64;
+    let timestamps: Vec<u64> = bytemuck::cast_slice(&slice.get_mapped_range()).to_vec();
+
+    timestamps
+        .chunks_exact(2)
+        .map(|pair| {
+            let ticks = pair[1].saturating_sub(pair[0]);
+            Duration::from_nanos((ticks as f64 * period) as u64)
+        })
+        .collect()
+}
@@ -1,19 +1,67 @@
-use std::sync::Arc;
+use std::{
+    num::{NonZeroU32, NonZeroU64},
+    sync::Arc,
+};
+
+use bytemuck::Pod;
 
 use crate::{
-    Buffer, BufferAccess, Context, Image, ImageDimension, Sampler, SamplerBindingType,
-    StorageImageAccess,
+    Buffer, BufferAccess, Context, Image, ImageDimension, ImageFormat, Kernel, Sampler,
+    SamplerBindingType, StorageImageAccess,
 };
 
 /// Represents a [`Buffer`]
 #[derive(Debug)]
 struct BufferBinding<'a> {
     resource: wgpu::BindingResource<'a>,
+    buffer: Arc<wgpu::Buffer>,
     access: BufferAccess,
+    has_dynamic_offset: bool,
+}
+
+impl<'a> BufferBinding<'a> {
+    /// Region of the buffer that `BindGroup::update_buffer()` writes to, the whole buffer
+    /// for dynamic bindings since their window moves.
+    fn bound_region(&self) -> BoundBuffer {
+        let (offset, size) = match &self.resource {
+            wgpu::BindingResource::Buffer(binding) if !self.has_dynamic_offset => (
+                binding.offset,
+                binding
+                    .size
+                    .map_or(self.buffer.size() - binding.offset, NonZeroU64::get),
+            ),
+            _ => (0, self.buffer.size()),
+        };
+
+        BoundBuffer {
+            buffer: Arc::clone(&self.buffer),
+            offset,
+            size,
+        }
+    }
 }
 
 impl<'a> From<&BufferBinding<'a>> for wgpu::BindingType {
     fn from(binding: &BufferBinding<'a>) -> Self {
+        wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage {
+                read_only: binding.access == BufferAccess::ReadOnly,
+            },
+            has_dynamic_offset: binding.has_dynamic_offset,
+            min_binding_size: None,
+        }
+    }
+}
+
+/// Represents an array of [`Buffer`]s bound to a single binding.
+#[derive(Debug)]
+struct BufferArrayBinding<'a> {
+    buffers: Vec<wgpu::BufferBinding<'a>>,
+    access: BufferAccess,
+}
+
+impl<'a> From<&BufferArrayBinding<'a>> for wgpu::BindingType {
+    fn from(binding: &BufferArrayBinding<'a>) -> Self {
         wgpu::BindingType::Buffer {
             ty: wgpu::BufferBindingType::Storage {
                 read_only: binding.access == BufferAccess::ReadOnly,
@@ -30,6 +78,7 @@ struct TextureBinding<'a> {
     resource: wgpu::BindingResource<'a>,
     dimension: wgpu::TextureViewDimension,
     sample_type: wgpu::TextureSampleType,
+    multisampled: bool,
 }
 
 impl<'a> From<&TextureBinding<'a>> for wgpu::BindingType {
@@ -37,7 +86,7 @@ impl<'a> From<&TextureBinding<'a>> for wgpu::BindingType {
         wgpu::BindingType::Texture {
             sample_type: binding.sample_type,
             view_dimension: binding.dimension,
-            multisampled: false,
+            multisampled: binding.multisampled,
         }
     }
 }
@@ -78,18 +127,51 @@ impl<'a> From<&SamplerBinding<'a>> for wgpu::BindingType {
 #[derive(Debug)]
 enum Binding<'a> {
     Buffer(BufferBinding<'a>),
+    BufferArray(BufferArrayBinding<'a>),
     Sampler(SamplerBinding<'a>),
     Texture(TextureBinding<'a>),
     StorageTexture(StorageTextureBinding<'a>),
 }
 
 impl<'a> Binding<'a> {
-    fn into_resource(self) -> wgpu::BindingResource<'a> {
+    fn resource(&self) -> wgpu::BindingResource<'_> {
         match self {
-            Binding::Buffer(buffer_binding) => buffer_binding.resource,
-            Binding::Sampler(sampler_binding) => sampler_binding.resource,
-            Binding::Texture(texture_binding) => texture_binding.resource,
-            Binding::StorageTexture(storage_texture_binding) => storage_texture_binding.resource,
+            Binding::Buffer(buffer_binding) => buffer_binding.resource.clone(),
+            Binding::BufferArray(buffer_array_binding) => {
+                wgpu::BindingResource::BufferArray(&buffer_array_binding.buffers)
+            }
+            Binding::Sampler(sampler_binding) => sampler_binding.resource.clone(),
+            Binding::Texture(texture_binding) => texture_binding.resource.clone(),
+            Binding::StorageTexture(storage_texture_binding) => {
+                storage_texture_binding.resource.clone()
+            }
+        }
+    }
+
+    /// Buffers referenced by the binding and the access they are bound with.
+    fn buffers(&self) -> Vec<(&wgpu::Buffer, BufferAccess)> {
+        match self {
+            Binding::Buffer(BufferBinding {
+                resource: wgpu::BindingResource::Buffer(binding),
+                access,
+                ..
+            }) => vec![(binding.buffer, *access)],
+            Binding::BufferArray(buffer_array_binding) => buffer_array_binding
+                .buffers
+                .iter()
+                .map(|binding| (binding.buffer, buffer_array_binding.access))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Number of elements if the binding is an array.
+    fn count(&self) -> Option<NonZeroU32> {
+        match self {
+            Binding::BufferArray(buffer_array_binding) => {
+                NonZeroU32::new(buffer_array_binding.buffers.len() as u32)
+            }
+            _ => None,
         }
     }
 }
@@ -98,6 +180,7 @@ impl<'a> From<&Binding<'a>> for wgpu::BindingType {
     fn from(binding: &Binding<'a>) -> Self {
         match binding {
             Binding::Buffer(buffer_binding) => buffer_binding.into(),
+            Binding::BufferArray(buffer_array_binding) => buffer_array_binding.into(),
             Binding::Sampler(sampler_binding) => sampler_binding.into(),
             Binding::Texture(texture_binding) => texture_binding.into(),
             Binding::StorageTexture(storage_texture_binding) => storage_texture_binding.into(),
@@ -105,6 +188,81 @@ impl<'a> From<&Binding<'a>> for wgpu::BindingType {
     }
 }
 
+/// Resource that can be pushed to a [`BindGroupDescriptor`], useful to build
+/// bind groups from heterogeneous collections (i.e. `&[&dyn Bindable]`).
+///
+/// It's implemented by:
+/// - [`Buffer`]: bound with `BufferAccess::ReadWrite`.
+/// - `(&Buffer, BufferAccess)`: bound with the given access.
+/// - [`Image`]: bound for sampling.
+/// - `(&Image, StorageImageAccess)`: bound for storage with the given access.
+/// - [`Sampler`]: bound with the binding type required by its [`crate::SamplerInfo`].
+pub trait Bindable {
+    /// Pushes the resource as the last binding of `descriptor`.
+    fn push_to<'a>(&'a self, descriptor: BindGroupDescriptor<'a>) -> BindGroupDescriptor<'a>;
+}
+
+impl<T> Bindable for Buffer<T> {
+    fn push_to<'a>(&'a self, descriptor: BindGroupDescriptor<'a>) -> BindGroupDescriptor<'a> {
+        descriptor.push_buffer(self, BufferAccess::ReadWrite)
+    }
+}
+
+impl<T> Bindable for (&Buffer<T>, BufferAccess) {
+    fn push_to<'a>(&'a self, descriptor: BindGroupDescriptor<'a>) -> BindGroupDescriptor<'a> {
+        descriptor.push_buffer(self.0, self.1)
+    }
+}
+
+impl Bindable for Image {
+    fn push_to<'a>(&'a self, descriptor: BindGroupDescriptor<'a>) -> BindGroupDescriptor<'a> {
+        descriptor.push_image(self)
+    }
+}
+
+impl Bindable for (&Image, StorageImageAccess) {
+    fn push_to<'a>(&'a self, descriptor: BindGroupDescriptor<'a>) -> BindGroupDescriptor<'a> {
+        descriptor.push_storage_image(self.0, self.1)
+    }
+}
+
+impl Bindable for Sampler {
+    fn push_to<'a>(&'a self, descriptor: BindGroupDescriptor<'a>) -> BindGroupDescriptor<'a> {
+        descriptor.push_sampler_auto(self)
+    }
+}
+
+/// Buffer of a [`BindEntry`], created with `BindEntry::buffer()`.
+#[derive(Debug, Clone, Copy)]
+pub struct BufferEntry<'a> {
+    handle: &'a Arc<wgpu::Buffer>,
+    access: BufferAccess,
+}
+
+/// A resource and how it's bound, used to build bind groups from data with
+/// `BindGroupDescriptor::from_entries()` instead of chaining pushes.
+#[derive(Debug, Clone, Copy)]
+pub enum BindEntry<'a> {
+    /// A buffer bound like `BindGroupDescriptor::push_buffer()` does.
+    Buffer(BufferEntry<'a>),
+    /// An image bound for sampling like `BindGroupDescriptor::push_image()` does.
+    Image(&'a Image),
+    /// An image bound for storage like `BindGroupDescriptor::push_storage_image()` does.
+    StorageImage(&'a Image, StorageImageAccess),
+    /// A sampler bound with the binding type it requires (see `Sampler::binding_type()`).
+    Sampler(&'a Sampler),
+}
+
+impl<'a> BindEntry<'a> {
+    /// Entry of `buffer` bound with `access`.
+    pub fn buffer<T>(buffer: &'a Buffer<T>, access: BufferAccess) -> Self {
+        Self::Buffer(BufferEntry {
+            handle: &buffer.handle,
+            access,
+        })
+    }
+}
+
 /// Contains the information to create BindGroups.
 ///
 /// This may change in the future to be able to reutilize `wgpu::BindGroupLayout`s.
@@ -123,6 +281,53 @@ impl<'a> BindGroupDescriptor<'a> {
         }
     }
 
+    /// Creates a descriptor with a binding for every entry, in order.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let entries = inputs
+    ///     .iter()
+    ///     .map(|input| hac::BindEntry::buffer(input, hac::BufferAccess::ReadOnly))
+    ///     .chain([hac::BindEntry::buffer(&output, hac::BufferAccess::ReadWrite)]);
+    ///
+    /// let bind_group = hac::BindGroupDescriptor::from_entries(&context, entries).into_bind_group();
+    /// ```
+    pub fn from_entries(
+        context: &Context,
+        entries: impl IntoIterator<Item = BindEntry<'a>>,
+    ) -> Self {
+        entries
+            .into_iter()
+            .fold(Self::new(context), Self::push_entry)
+    }
+
+    /// Pushes `entry` as the last binding.
+    pub fn push_entry(self, entry: BindEntry<'a>) -> Self {
+        match entry {
+            BindEntry::Buffer(BufferEntry { handle, access }) => {
+                self.push_buffer_handle(handle, access)
+            }
+            BindEntry::Image(image) => self.push_image(image),
+            BindEntry::StorageImage(image, access) => self.push_storage_image(image, access),
+            BindEntry::Sampler(sampler) => self.push_sampler_auto(sampler),
+        }
+    }
+
+    /// Pushes any [`Bindable`] `resource` as the last binding.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let resources: [&dyn hac::Bindable; 3] = [&(&input, hac::BufferAccess::ReadOnly), &output, &sampler];
+    ///
+    /// let bind_group = resources
+    ///     .into_iter()
+    ///     .fold(context.bind_group_descriptor(), |descriptor, resource| descriptor.push(resource))
+    ///     .into_bind_group();
+    /// ```
+    pub fn push(self, resource: &'a (impl Bindable + ?Sized)) -> Self {
+        resource.push_to(self)
+    }
+
     /// Pushes `buffer` as the last binding with `accessor` access.
     ///
     /// # Example wgsl syntax
@@ -130,9 +335,171 @@ impl<'a> BindGroupDescriptor<'a> {
     /// @group(X) @binding(Y)
     /// var<storage, 'access'> buffer: array<'T'>; // T is the type of the buffer
     /// ```
-    pub fn push_buffer<T>(mut self, buffer: &'a Buffer<T>, access: BufferAccess) -> Self {
+    ///
+    /// # Note
+    ///
+    /// The same buffer can be pushed at several bindings (also in different bind groups
+    /// of a dispatch) as long as all of them have the same access. `ReadWrite` bindings
+    /// of the same buffer alias the same memory, so the kernel is responsible of avoiding
+    /// data races between them. Mixing `ReadOnly` and `ReadWrite` bindings of a buffer
+    /// (including slices of it) is not allowed.
+    pub fn push_buffer<T>(self, buffer: &'a Buffer<T>, access: BufferAccess) -> Self {
+        self.push_buffer_handle(&buffer.handle, access)
+    }
+
+    fn push_buffer_handle(mut self, handle: &'a Arc<wgpu::Buffer>, access: BufferAccess) -> Self {
+        let binding = Binding::Buffer(BufferBinding {
+            resource: handle.as_entire_binding(),
+            buffer: Arc::clone(handle),
+            access,
+            has_dynamic_offset: false,
+        });
+
+        self.bindings.push(binding);
+        self
+    }
+
+    /// Pushes `len` elements of `buffer` starting at the element `offset` as the last
+    /// binding with `access` access, the kernel sees them as an array of `len` elements.
+    ///
+    /// Useful to sub-allocate regions of a large buffer for different kernels.
+    ///
+    /// # Panics
+    ///
+    /// - if `len` is 0 or the range exceeds the length of the buffer.
+    /// - if the offset in bytes is not a multiple of the `min_storage_buffer_offset_alignment`
+    ///   limit (usually 256 bytes).
+    ///
+    /// # Example wgsl syntax
+    /// ```cpp,ignore
+    /// @group(X) @binding(Y)
+    /// var<storage, 'access'> region: array<'T'>; // arrayLength(&region) is `len`
+    /// ```
+    pub fn push_buffer_slice<T: Pod>(
+        mut self,
+        buffer: &'a Buffer<T>,
+        offset: wgpu::BufferAddress,
+        len: wgpu::BufferAddress,
+        access: BufferAccess,
+    ) -> Self {
+        assert!(
+            len > 0 && offset + len <= buffer.len(),
+            "the slice {offset}..{} is out of the bounds of a buffer with {} elements",
+            offset + len,
+            buffer.len(),
+        );
+
+        let element_size = std::mem::size_of::<T>() as wgpu::BufferAddress;
+        let alignment = self
+            .device
+            .handle
+            .limits()
+            .min_storage_buffer_offset_alignment;
+
+        assert!(
+            (offset * element_size) % alignment as wgpu::BufferAddress == 0,
+            "the offset of a buffer slice must be a multiple of {alignment} bytes"
+        );
+
+        let binding = Binding::Buffer(BufferBinding {
+            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                buffer: &buffer.handle,
+                offset: offset * element_size,
+                size: NonZeroU64::new(len * element_size),
+            }),
+            buffer: Arc::clone(&buffer.handle),
+            access,
+            has_dynamic_offset: false,
+        });
+
+        self.bindings.push(binding);
+        self
+    }
+
+    /// Pushes a window of `len` elements of `buffer` as the last binding with `access`
+    /// access, the start of the window is a dynamic offset chosen every time the bind
+    /// group is set (see `CommandQueue::enqueue_set_bind_group_with_offsets()`).
+    ///
+    /// Useful to run a kernel over many parameter sets stored in the same buffer
+    /// without creating a bind group for each one of them.
+    ///
+    /// # Note
+    ///
+    /// The window starts at the beginning of the buffer when the bind group is set without
+    /// offsets (i.e. when the kernel is set). Dynamic offsets are in bytes and must be
+    /// multiples of the `min_storage_buffer_offset_alignment` limit (usually 256 bytes).
+    ///
+    /// # Panics
+    ///
+    /// - if `len` is 0 or exceeds the length of the buffer.
+    ///
+    /// # Example wgsl syntax
+    /// ```cpp,ignore
+    /// @group(X) @binding(Y)
+    /// var<storage, 'access'> window: array<'T'>; // arrayLength(&window) is `len`
+    /// ```
+    pub fn push_dynamic_buffer<T: Pod>(
+        mut self,
+        buffer: &'a Buffer<T>,
+        len: wgpu::BufferAddress,
+        access: BufferAccess,
+    ) -> Self {
+        assert!(
+            len > 0 && len <= buffer.len(),
+            "a dynamic window of {len} elements doesn't fit a buffer with {} elements",
+            buffer.len(),
+        );
+
+        let element_size = std::mem::size_of::<T>() as wgpu::BufferAddress;
+
         let binding = Binding::Buffer(BufferBinding {
-            resource: buffer.handle.as_entire_binding(),
+            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                buffer: &buffer.handle,
+                offset: 0,
+                size: NonZeroU64::new(len * element_size),
+            }),
+            buffer: Arc::clone(&buffer.handle),
+            access,
+            has_dynamic_offset: true,
+        });
+
+        self.bindings.push(binding);
+        self
+    }
+
+    /// Pushes `buffers` as an array of buffers in the last binding with `access` access.
+    ///
+    /// Requires the `BUFFER_BINDING_ARRAY` and `STORAGE_RESOURCE_BINDING_ARRAY` features.
+    ///
+    /// # Note
+    ///
+    /// The wgsl frontend of the shader compiler used by wgpu 0.14 (naga 0.10) fails to
+    /// index binding arrays of storage buffers, so there's no wgsl syntax that works here.
+    /// The program that uses them has to be created with `Context::program_from_shader_source()`
+    /// from SPIR-V, which needs the `spirv` feature of `wgpu` enabled in the dependent crate.
+    ///
+    /// # Panics
+    ///
+    /// - if `buffers` is empty.
+    /// - if the features required for binding arrays of storage buffers aren't enabled.
+    pub fn push_buffer_array<T>(mut self, buffers: &[&'a Buffer<T>], access: BufferAccess) -> Self {
+        assert!(
+            !buffers.is_empty(),
+            "a buffer array must have at least one buffer"
+        );
+
+        let required_features =
+            wgpu::Features::BUFFER_BINDING_ARRAY | wgpu::Features::STORAGE_RESOURCE_BINDING_ARRAY;
+        assert!(
+            self.device.handle.features().contains(required_features),
+            "binding arrays of buffers require the {required_features:?} features"
+        );
+
+        let binding = Binding::BufferArray(BufferArrayBinding {
+            buffers: buffers
+                .iter()
+                .map(|buffer| buffer.handle.as_entire_buffer_binding())
+                .collect(),
             access,
         });
 
@@ -142,7 +509,8 @@ impl<'a> BindGroupDescriptor<'a> {
 
     /// Pushes `sampler` as the last binding with the spacified `binding_type`.
     ///
-    /// The `binding_type` should be filtering if it uses `FilterMode::Linear`.
+    /// The `binding_type` should be filtering if it uses `FilterMode::Linear`,
+    /// see `SamplerInfo::binding_type()`.
     ///
     /// # Example wgsl syntax
     /// ```cpp,ignore
@@ -165,6 +533,12 @@ impl<'a> BindGroupDescriptor<'a> {
         self
     }
 
+    /// Pushes `sampler` as the last binding with the binding type returned by
+    /// `Sampler::binding_type()`, which always matches the sampler.
+    pub fn push_sampler_auto(self, sampler: &'a Sampler) -> Self {
+        self.push_sampler(sampler, sampler.binding_type())
+    }
+
     /// Pushes `image` as the last binding.
     ///
     /// # Example wgsl syntax
@@ -175,6 +549,9 @@ impl<'a> BindGroupDescriptor<'a> {
     /// // - if it's format ends with Unorm => T is f32
     /// // - if it ends with Uint => T is u32
     /// // - if it ends with Sint => T is i32
+    ///
+    /// // multisampled images are declared as
+    /// var image: texture_multisampled_2d<'T'>;
     /// ```
     pub fn push_image(mut self, image: &'a Image) -> Self {
         let dimension = if image.dimension == ImageDimension::D2 {
@@ -183,11 +560,19 @@ impl<'a> BindGroupDescriptor<'a> {
             wgpu::TextureViewDimension::D3
         };
 
-        let sample_type = image.format.describe().sample_type;
+        let multisampled = image.sample_count > 1;
+        let sample_type = match image.format.describe().sample_type {
+            // multisampled images can't be filtered
+            wgpu::TextureSampleType::Float { .. } if multisampled => {
+                wgpu::TextureSampleType::Float { filterable: false }
+            }
+            sample_type => sample_type,
+        };
 
         let binding = Binding::Texture(TextureBinding {
             dimension,
             sample_type,
+            multisampled,
             resource: wgpu::BindingResource::TextureView(&image.view),
         });
 
@@ -195,14 +580,100 @@ impl<'a> BindGroupDescriptor<'a> {
         self
     }
 
+    /// Pushes a single mip `level` of `image` as the last binding.
+    ///
+    /// The level is seen as an image with a single mip level in the kernel,
+    /// see `BindGroupDescriptor::push_image()` for the wgsl syntax.
+    ///
+    /// # Panics
+    ///
+    /// - if `level` is not less than the mip level count of the image.
+    pub fn push_image_mip(mut self, image: &'a Image, level: u32) -> Self {
+        let dimension = if image.dimension == ImageDimension::D2 {
+            wgpu::TextureViewDimension::D2
+        } else {
+            wgpu::TextureViewDimension::D3
+        };
+
+        let sample_type = image.format.describe().sample_type;
+
+        let binding = Binding::Texture(TextureBinding {
+            dimension,
+            sample_type,
+            multisampled: false,
+            resource: wgpu::BindingResource::TextureView(&image.mip_views[level as usize]),
+        });
+
+        self.bindings.push(binding);
+        self
+    }
+
     /// Pushes an image for storage.
     ///
+    /// Only the first mip level of the image is bound, see
+    /// `BindGroupDescriptor::push_storage_image_mip()` to store into the other levels.
+    ///
     /// # Example wgsl syntax
     /// ```cpp,ignore
     /// @group(X) @binding(Y)
     /// var image: texture_storage_2d<rgba8unorm, write>;
     /// ```
-    pub fn push_storage_image(mut self, image: &'a Image, access: StorageImageAccess) -> Self {
+    ///
+    /// # Note
+    ///
+    /// `StorageImageAccess::ReadOnly` and `StorageImageAccess::ReadWrite` require the
+    /// `TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES` feature and a format that supports them
+    /// on the adapter, usually `R32Float`, `R32Uint` and `R32Sint` are supported on all
+    /// native backends while the rest depend on the adapter.
+    /// Use `Context::supports_read_write_storage()` to check a format.
+    ///
+    /// # Panics
+    ///
+    /// - if the format of the image can't be used for storage on the device (i.e. sRGB
+    ///   formats), see `Context::supports_storage()`.
+    /// - if `access` is not `StorageImageAccess::WriteOnly` and the format of the
+    ///   image doesn't support it.
+    pub fn push_storage_image(self, image: &'a Image, access: StorageImageAccess) -> Self {
+        self.push_storage_image_mip(image, 0, access)
+    }
+
+    /// Pushes a single mip `level` of `image` for storage.
+    ///
+    /// See `BindGroupDescriptor::push_storage_image()` for the wgsl syntax.
+    ///
+    /// # Panics
+    ///
+    /// - if `level` is not less than the mip level count of the image.
+    /// - if the format of the image can't be used for storage on the device (i.e. sRGB
+    ///   formats), see `Context::supports_storage()`.
+    /// - if `access` is not `StorageImageAccess::WriteOnly` and the format of the
+    ///   image doesn't support it.
+    pub fn push_storage_image_mip(
+        mut self,
+        image: &'a Image,
+        level: u32,
+        access: StorageImageAccess,
+    ) -> Self {
+        assert!(
+            Image::usages(&self.device, image.format)
+                .contains(wgpu::TextureUsages::STORAGE_BINDING),
+            "{:?} images can't be used for storage on this device",
+            image.format,
+        );
+
+        assert!(
+            access == StorageImageAccess::WriteOnly
+                || self
+                    .device
+                    .format_features(image.format)
+                    .flags
+                    .contains(wgpu::TextureFormatFeatureFlags::STORAGE_READ_WRITE),
+            "{access:?} storage access is not supported for {:?} images, it requires the \
+             TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES feature and an adapter that supports \
+             it for the format",
+            image.format,
+        );
+
         let dimension = if image.dimension == ImageDimension::D2 {
             wgpu::TextureViewDimension::D2
         } else {
@@ -212,7 +683,7 @@ impl<'a> BindGroupDescriptor<'a> {
         let binding = Binding::StorageTexture(StorageTextureBinding {
             access,
             dimension,
-            resource: wgpu::BindingResource::TextureView(&image.view),
+            resource: wgpu::BindingResource::TextureView(&image.mip_views[level as usize]),
             format: image.format,
         });
 
@@ -220,28 +691,75 @@ impl<'a> BindGroupDescriptor<'a> {
         self
     }
 
+    /// Pushes an image for storage declaring the binding with `format_override` instead
+    /// of the format of the image when given, meant for kernels that declare a specific
+    /// storage format and want the mismatch to be reported where the image is pushed.
+    ///
+    /// See `BindGroupDescriptor::push_storage_image()` for the wgsl syntax.
+    ///
+    /// # Note
+    ///
+    /// wgpu 0.14 doesn't support reinterpreting the format of a texture in its views, so
+    /// the only format compatible with an image is the one it was created with (even the
+    /// sRGB and linear variants of a format aren't). Use `Image::empty_with_format()` to
+    /// create an intermediate image with the format that the kernel expects.
+    ///
+    /// # Panics
+    ///
+    /// - if `format_override` is not compatible with the format of the image.
+    /// - for the same reasons as `BindGroupDescriptor::push_storage_image()`.
+    pub fn push_storage_image_with_format(
+        self,
+        image: &'a Image,
+        access: StorageImageAccess,
+        format_override: Option<ImageFormat>,
+    ) -> Self {
+        if let Some(format) = format_override {
+            assert!(
+                format == image.format,
+                "{:?} images can't be bound for storage as {format:?}, views must have the \
+                 format of the image",
+                image.format,
+            );
+        }
+
+        self.push_storage_image(image, access)
+    }
+
     /// Creates a bind group.
+    ///
+    /// # Panics
+    ///
+    /// - if a buffer is bound both as `ReadOnly` and `ReadWrite`.
     pub fn into_bind_group(self) -> BindGroup {
+        let buffers: Vec<_> = self.bindings.iter().flat_map(Binding::buffers).collect();
+        for (i, (buffer, access)) in buffers.iter().enumerate() {
+            assert!(
+                buffers[..i]
+                    .iter()
+                    .all(|(other, other_access)| !std::ptr::eq(*buffer, *other)
+                        || access == other_access),
+                "a buffer can't be bound as both read only and read write in the same bind group"
+            );
+        }
+
         let num_entries = self.bindings.len();
 
         let mut layout_entries = Vec::with_capacity(num_entries);
         let mut bind_group_entries = Vec::with_capacity(num_entries);
 
-        self.bindings
-            .into_iter()
-            .enumerate()
-            .for_each(|(i, binding)| {
-                layout_entries.push(wgpu::BindGroupLayoutEntry {
-                    binding: i as u32,
-                    visibility: wgpu::ShaderStages::COMPUTE,
-                    ty: wgpu::BindingType::from(&binding),
-                    count: None,
-                });
-                bind_group_entries.push(wgpu::BindGroupEntry {
-                    binding: i as u32,
-                    resource: binding.into_resource(),
-                })
+        self.bindings.iter().enumerate().for_each(|(i, binding)| {
+            layout_entries.push(wgpu::BindGroupLayoutEntry {
+                binding: i as u32,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::from(binding),
+                count: binding.count(),
             });
+            bind_group_entries.push(wgpu::BindGroupEntry {
+                binding: i as u32,
+                resource: binding.resource(),
+            })
+        });
 
         let layout =
             self.device
@@ -259,9 +777,36 @@ impl<'a> BindGroupDescriptor<'a> {
             },
         ));
 
+        let buffers = self
+            .bindings
+            .iter()
+            .map(|binding| match binding {
+                Binding::Buffer(buffer_binding) => Some(buffer_binding.bound_region()),
+                _ => None,
+            })
+            .collect();
+
+        let default_offsets = layout_entries
+            .iter()
+            .filter(|entry| {
+                matches!(
+                    entry.ty,
+                    wgpu::BindingType::Buffer {
+                        has_dynamic_offset: true,
+                        ..
+                    }
+                )
+            })
+            .map(|_| 0)
+            .collect();
+
         BindGroup {
-            layout,
+            device: Arc::clone(&self.device),
+            layout: Arc::new(layout),
+            layout_entries,
             handle: bind_group,
+            default_offsets,
+            buffers,
         }
     }
 }
@@ -271,6 +816,82 @@ impl<'a> BindGroupDescriptor<'a> {
 /// bind groups are created from [`BindGroupLayout`]s.
 #[derive(Debug)]
 pub struct BindGroup {
-    pub(crate) layout: wgpu::BindGroupLayout,
+    device: Arc<crate::Device>,
+    pub(crate) layout: Arc<wgpu::BindGroupLayout>,
+    pub(crate) layout_entries: Vec<wgpu::BindGroupLayoutEntry>,
     pub(crate) handle: Arc<wgpu::BindGroup>,
+    /// A zero offset for every binding with a dynamic offset.
+    pub(crate) default_offsets: Vec<u32>,
+    /// The region of the buffer bound at every binding that holds a single buffer.
+    buffers: Vec<Option<BoundBuffer>>,
+}
+
+/// Region of a buffer bound to a [`BindGroup`].
+#[derive(Debug)]
+struct BoundBuffer {
+    buffer: Arc<wgpu::Buffer>,
+    offset: wgpu::BufferAddress,
+    size: wgpu::BufferAddress,
+}
+
+impl BindGroup {
+    /// Writes `data` at the start of the buffer bound at `binding` (the start of the slice
+    /// for `BindGroupDescriptor::push_buffer_slice()` and of the whole buffer for
+    /// `BindGroupDescriptor::push_dynamic_buffer()`), it's the same as writing to the
+    /// buffer with `Buffer::write()`.
+    ///
+    /// # Note
+    ///
+    /// Writing keeps a single bind group for inputs that change between executions, but
+    /// the write is only seen by queues executed after it (see the ordering section of
+    /// [`crate::CommandQueue`]). Inputs that change between dispatches of the same queue need
+    /// a bind group for each one of them, set with `CommandQueue::enqueue_set_bind_group()`.
+    ///
+    /// # Panics
+    ///
+    /// - if `binding` doesn't hold a single buffer.
+    /// - if `data` overruns the bound region or its size isn't a multiple of
+    ///   `wgpu::COPY_BUFFER_ALIGNMENT` (4 bytes).
+    /// - if the buffer was created without `BufferUsage::copy_dst`.
+    pub fn update_buffer<T: Pod>(&self, binding: u32, data: &[T]) {
+        let bound = self
+            .buffers
+            .get(binding as usize)
+            .and_then(Option::as_ref)
+            .unwrap_or_else(|| panic!("binding {binding} doesn't hold a single buffer"));
+
+        let size = std::mem::size_of_val(data) as wgpu::BufferAddress;
+        assert!(
+            size <= bound.size,
+            "{size} bytes overrun the {} bytes bound at binding {binding}",
+            bound.size,
+        );
+        assert!(
+            size % wgpu::COPY_BUFFER_ALIGNMENT == 0,
+            "writes of {size} bytes must be aligned to {} bytes",
+            wgpu::COPY_BUFFER_ALIGNMENT,
+        );
+        assert!(
+            bound.buffer.usage().contains(wgpu::BufferUsages::COPY_DST),
+            "the buffer was created without copy_dst usage, it can't be written"
+        );
+
+        self.device
+            .queue
+            .write_buffer(&bound.buffer, bound.offset, bytemuck::cast_slice(data));
+    }
+
+    /// Returns `true` if the bind group can be set at `index` of `kernel`, that is, it was
+    /// created by the same context and has the layout of the bind group `kernel` was created
+    /// with at `index` (i.e. a parameters buffer shared at group 0 by several kernels).
+    ///
+    /// The bind groups of `KernelInfo` are already checked against the program when the
+    /// kernel is created, this checks a shared group against the kernels it's reused with
+    /// before setting it with `CommandQueue::enqueue_set_bind_group()`.
+    pub fn is_compatible_with(&self, kernel: &Kernel, index: u32) -> bool {
+        Arc::ptr_eq(&self.device, &kernel.device)
+            && kernel
+                .bind_group_layout(index)
+                .is_some_and(|entries| entries == self.layout_entries)
+    }
 }
@@ -1,9 +1,21 @@
-use std::{marker::PhantomData, mem, sync::Arc};
+use std::{
+    fmt,
+    future::Future,
+    marker::PhantomData,
+    mem,
+    ops::Deref,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context as TaskContext, Poll, Waker},
+};
 
 use bytemuck::Pod;
 use wgpu::util::DeviceExt as _;
 
-use crate::Context;
+use crate::{
+    memory::{Allocation, ResourceKind},
+    Context, StructLayout, StructLayoutMismatch,
+};
 
 /// Specifies the storage access of the buffer in the kernel.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -19,6 +31,138 @@ pub enum BufferAccess {
     ReadWrite,
 }
 
+/// Copy usages of a [`Buffer`], both are enabled by default.
+///
+/// Disabling them is meant for scratch buffers that are only used by kernels, since
+/// some backends may place them in faster memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferUsage {
+    /// The buffer can be read back and copied from (i.e. with `Buffer::read_to_vec()`).
+    pub copy_src: bool,
+
+    /// The buffer can be written from the host and copied to (i.e. with `Buffer::write()`).
+    pub copy_dst: bool,
+}
+
+impl BufferUsage {
+    /// Usage of a buffer only accessed by kernels.
+    pub const SCRATCH: Self = Self {
+        copy_src: false,
+        copy_dst: false,
+    };
+
+    fn to_wgpu(self) -> wgpu::BufferUsages {
+        let mut usages = wgpu::BufferUsages::STORAGE;
+        usages.set(wgpu::BufferUsages::COPY_SRC, self.copy_src);
+        usages.set(wgpu::BufferUsages::COPY_DST, self.copy_dst);
+        usages
+    }
+}
+
+impl Default for BufferUsage {
+    fn default() -> Self {
+        Self {
+            copy_src: true,
+            copy_dst: true,
+        }
+    }
+}
+
+/// Reason why `Buffer::try_write()` or `Image::try_write_region()` rejected a write.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriteError {
+    /// Writing `len` elements at `index` overruns a buffer of `capacity` elements.
+    BufferOverrun {
+        index: wgpu::BufferAddress,
+        len: wgpu::BufferAddress,
+        capacity: wgpu::BufferAddress,
+    },
+
+    /// The byte `offset` or `size` of a buffer write is not a multiple of
+    /// `wgpu::COPY_BUFFER_ALIGNMENT`.
+    Unaligned {
+        offset: wgpu::BufferAddress,
+        size: wgpu::BufferAddress,
+    },
+
+    /// The mip level doesn't exist in an image of `mip_level_count` levels.
+    MipLevel {
+        mip_level: u32,
+        mip_level_count: u32,
+    },
+
+    /// The region of `size` at `origin` exceeds the size of the mip level.
+    RegionOutOfBounds {
+        origin: crate::Origin3d,
+        size: crate::Extent3d,
+        level_size: crate::Extent3d,
+    },
+
+    /// The data layout can't describe the region, the message describes why.
+    DataLayout(String),
+
+    /// The data has `len` bytes but the layout and size of the write need `required`.
+    DataOverrun {
+        len: wgpu::BufferAddress,
+        required: wgpu::BufferAddress,
+    },
+
+    /// The buffer was created without `BufferUsage::copy_dst`.
+    NotWritable,
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WriteError::BufferOverrun {
+                index,
+                len,
+                capacity,
+            } => write!(
+                f,
+                "writing {len} elements at index {index} overruns a buffer of {capacity} elements"
+            ),
+            WriteError::Unaligned { offset, size } => write!(
+                f,
+                "writes of {size} bytes at offset {offset} must be aligned to {} bytes",
+                wgpu::COPY_BUFFER_ALIGNMENT,
+            ),
+            WriteError::MipLevel {
+                mip_level,
+                mip_level_count,
+            } => write!(
+                f,
+                "mip level {mip_level} is out of bounds, the image has {mip_level_count} levels"
+            ),
+            WriteError::RegionOutOfBounds {
+                origin,
+                size,
+                level_size,
+            } => write!(
+                f,
+                "region of size {size:?} at {origin:?} exceeds the size {level_size:?} of the mip level"
+            ),
+            WriteError::DataLayout(reason) => f.write_str(reason),
+            WriteError::DataOverrun { len, required } => write!(
+                f,
+                "data of {len} bytes is too small for the write, it needs {required} bytes"
+            ),
+            WriteError::NotWritable => {
+                f.write_str("the buffer was created without copy_dst usage, it can't be written")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WriteError {}
+
+/// Element types that support atomic operations in wgsl (`u32` and `i32`).
+pub trait AtomicElement: Pod {}
+
+impl AtomicElement for u32 {}
+
+impl AtomicElement for i32 {}
+
 /// Buffer on the GPU that stores homogeneous data.
 ///
 /// With multiple elements it acts as an `array<T>` in kernel code.
@@ -26,44 +170,123 @@ pub enum BufferAccess {
 #[derive(Debug)]
 pub struct Buffer<T> {
     pub(crate) device: Arc<crate::Device>,
-    pub(crate) handle: wgpu::Buffer,
+    pub(crate) handle: Arc<wgpu::Buffer>,
+    /// Shared with the views of the buffer.
+    allocation: Arc<Allocation>,
+    /// Set by `Buffer::set_label()`, shared with the views of the buffer.
+    label: Arc<Mutex<Option<String>>>,
+    len: wgpu::BufferAddress,
+    /// Set by `Buffer::mark_as_output()`, whether the buffer was read back, shared
+    /// with the views of the buffer.
+    #[cfg(all(debug_assertions, feature = "log"))]
+    output: Option<Arc<std::sync::atomic::AtomicBool>>,
     _marker: PhantomData<Vec<T>>,
 }
 
 impl<T: Pod> Buffer<T> {
-    // cheesy workaround to be able to make a const bitflag
-    // see: https://github.com/bitflags/bitflags/issues/180
-    const USAGES: wgpu::BufferUsages = wgpu::BufferUsages::from_bits_truncate(
-        wgpu::BufferUsages::STORAGE.bits()
-            | wgpu::BufferUsages::COPY_DST.bits()
-            | wgpu::BufferUsages::COPY_SRC.bits(),
-    );
-
     /// Allocate a buffer on the GPU with `capacity` **elements of T**.
     ///
+    /// The buffer is zero-initialized, wgpu-core tracks the initialized ranges of every
+    /// buffer and clears the rest before they're first read (by a kernel, a copy or a map)
+    /// on all backends, so it's safe to read it in a kernel before writing to it without
+    /// an explicit clear.
+    ///
     /// # Panics
     ///
     /// - if capacity exceeds the limit of `max_buffer_size` (with a default
-    /// value of **2^30 bytes** that can be configured in `ContextInfo`).
+    ///   value of **2^30 bytes** that can be configured in `ContextInfo`).
+    /// - if `capacity * std::mem::size_of::<T>()` overflows a `u64`.
     pub fn new(context: &Context, capacity: wgpu::BufferAddress) -> Self {
+        Self::with_usage(context, capacity, BufferUsage::default())
+    }
+
+    /// Allocate a zero-initialized buffer on the GPU with `capacity` **elements of T**
+    /// and the copy usages of `usage`.
+    ///
+    /// # Note
+    ///
+    /// Buffers without `copy_src` can't be read back nor copied into an image, and buffers
+    /// without `copy_dst` can't be written from the host nor from an image, doing so panics
+    /// (`Buffer::try_write()` returns `WriteError::NotWritable` instead).
+    ///
+    /// # Panics
+    ///
+    /// - if capacity exceeds the limit of `max_buffer_size` (with a default
+    ///   value of **2^30 bytes** that can be configured in `ContextInfo`).
+    /// - if `capacity * std::mem::size_of::<T>()` overflows a `u64`.
+    pub fn with_usage(
+        context: &Context,
+        capacity: wgpu::BufferAddress,
+        usage: BufferUsage,
+    ) -> Self {
         let buffer = context
             .device
             .handle
             .create_buffer(&wgpu::BufferDescriptor {
                 label: Some("buffer"),
-                size: capacity * mem::size_of::<T>() as wgpu::BufferAddress,
-                usage: Self::USAGES,
+                size: Self::aligned_size(capacity),
+                usage: usage.to_wgpu(),
                 mapped_at_creation: false,
             });
 
         Self {
+            allocation: Arc::new(Allocation::new(
+                &context.device,
+                ResourceKind::Buffer,
+                buffer.size(),
+            )),
+            label: Arc::default(),
             device: Arc::clone(&context.device),
-            handle: buffer,
+            handle: Arc::new(buffer),
+            len: capacity,
+            #[cfg(all(debug_assertions, feature = "log"))]
+            output: None,
             _marker: PhantomData,
         }
     }
 
-    /// Creates an empty buffer able to store the same ammount of data that `original` does.
+    /// Allocates a zero-initialized buffer with `capacity` elements meant to be used
+    /// with atomic operations in the kernel.
+    ///
+    /// The buffer must be pushed with `BufferAccess::ReadWrite`, its values can be cheaply
+    /// read back one at a time with `Buffer::read_element()`.
+    ///
+    /// # Example wgsl syntax
+    /// ```cpp,ignore
+    /// @group(X) @binding(Y)
+    /// var<storage, read_write> counters: array<atomic<u32>>;
+    ///
+    /// atomicAdd(&counters[i], 1u);
+    /// ```
+    pub fn atomic(context: &Context, capacity: wgpu::BufferAddress) -> Self
+    where
+        T: AtomicElement,
+    {
+        Self::zeroed(context, capacity)
+    }
+
+    /// Allocate a buffer on the GPU with `capacity` **elements of T** with all of its
+    /// bytes set to 0.
+    ///
+    /// # Note
+    ///
+    /// This is equivalent to `Buffer::new()` since wgpu already zero-initializes every
+    /// buffer, it only exists to make the intent explicit at the call site.
+    ///
+    /// # Panics
+    ///
+    /// - if capacity exceeds the limit of `max_buffer_size` (with a default
+    ///   value of **2^30 bytes** that can be configured in `ContextInfo`).
+    /// - if `capacity * std::mem::size_of::<T>()` overflows a `u64`.
+    pub fn zeroed(context: &Context, capacity: wgpu::BufferAddress) -> Self {
+        Self::new(context, capacity)
+    }
+
+    /// Creates an empty buffer able to store the same ammount of data that `original` does,
+    /// with the same usage.
+    ///
+    /// Just like `Buffer::new()` the contents of the buffer are zero-initialized, nothing
+    /// is copied from `original`.
     pub fn empty_like(original: &Self) -> Self {
         let buffer = original
             .device
@@ -71,56 +294,637 @@ impl<T: Pod> Buffer<T> {
             .create_buffer(&wgpu::BufferDescriptor {
                 label: Some("buffer"),
                 size: original.handle.size(),
-                usage: Self::USAGES,
+                usage: original.handle.usage(),
                 mapped_at_creation: false,
             });
 
         Self {
+            allocation: Arc::new(Allocation::new(
+                &original.device,
+                ResourceKind::Buffer,
+                buffer.size(),
+            )),
+            label: Arc::default(),
             device: Arc::clone(&original.device),
-            handle: buffer,
+            handle: Arc::new(buffer),
+            len: original.len,
+            #[cfg(all(debug_assertions, feature = "log"))]
+            output: None,
             _marker: PhantomData,
         }
     }
 
     /// Write to a buffer starting at `index`.
     ///
+    /// # Note
+    ///
+    /// The write is visible to every kernel dispatched or command queue executed after
+    /// it, and to none executed before it, see the ordering section of [`CommandQueue`].
+    ///
+    /// [`CommandQueue`]: crate::CommandQueue
+    ///
     /// # Panics
     ///
     /// - if `data` overruns the buffer from any index.
+    /// - if the byte offset or size of the write is not a multiple of
+    ///   `wgpu::COPY_BUFFER_ALIGNMENT` (4 bytes).
     pub fn write(&self, data: &[T], index: wgpu::BufferAddress) {
+        self.try_write(data, index)
+            .unwrap_or_else(|error| panic!("{error}"));
+    }
+
+    /// Write to a buffer starting at `index`, returning an error instead of panicking
+    /// when the write is invalid, in which case nothing is written.
+    pub fn try_write(&self, data: &[T], index: wgpu::BufferAddress) -> Result<(), WriteError> {
+        if !self.usage().copy_dst {
+            return Err(WriteError::NotWritable);
+        }
+
+        let len = data.len() as wgpu::BufferAddress;
+
+        if index.checked_add(len).map_or(true, |end| end > self.len) {
+            return Err(WriteError::BufferOverrun {
+                index,
+                len,
+                capacity: self.len,
+            });
+        }
+
         let offset = index * mem::size_of::<T>() as u64;
+        let size = len * mem::size_of::<T>() as u64;
+
+        if offset % wgpu::COPY_BUFFER_ALIGNMENT != 0 || size % wgpu::COPY_BUFFER_ALIGNMENT != 0 {
+            return Err(WriteError::Unaligned { offset, size });
+        }
+
         self.device
             .queue
             .write_buffer(&self.handle, offset, bytemuck::cast_slice(data));
+
+        Ok(())
     }
 
     /// Allocates a buffer on the GPU and initializes it with data.
     pub fn from_slice(context: &Context, data: &[T]) -> Self {
+        Self::from_bytes(context, bytemuck::cast_slice(data))
+    }
+
+    /// Allocates a buffer on the GPU and initializes it with the raw bytes of its elements
+    /// (i.e. the ones of `Buffer::read_bytes()` saved to restore a simulation), they don't
+    /// have to be aligned for `T`.
+    ///
+    /// # Panics
+    ///
+    /// - if `bytes.len()` is not a multiple of `std::mem::size_of::<T>()`.
+    /// - for the same reasons as `Buffer::new()`.
+    pub fn from_bytes(context: &Context, bytes: &[u8]) -> Self {
+        let element_size = mem::size_of::<T>();
+        assert!(
+            bytes.len() % element_size == 0,
+            "{} bytes can't be split in elements of {element_size} bytes",
+            bytes.len(),
+        );
+
         let buffer = context
             .device
             .handle
             .create_buffer_init(&wgpu::util::BufferInitDescriptor {
                 label: Some("buffer"),
-                contents: bytemuck::cast_slice(data),
-                usage: Self::USAGES,
+                contents: bytes,
+                usage: BufferUsage::default().to_wgpu(),
             });
 
         Self {
+            allocation: Arc::new(Allocation::new(
+                &context.device,
+                ResourceKind::Buffer,
+                buffer.size(),
+            )),
+            label: Arc::default(),
             device: Arc::clone(&context.device),
-            handle: buffer,
+            handle: Arc::new(buffer),
+            len: (bytes.len() / element_size) as wgpu::BufferAddress,
+            #[cfg(all(debug_assertions, feature = "log"))]
+            output: None,
             _marker: PhantomData,
         }
     }
 
+    /// Allocates a buffer on the GPU and initializes it with the elements of `iter`, which
+    /// are written straight into the mapped buffer without collecting them first.
+    ///
+    /// # Panics
+    ///
+    /// - if `iter` yields a different number of elements than its `len()`.
+    /// - for the same reasons as `Buffer::new()`.
+    pub fn from_iter<I>(context: &Context, iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let mut iter = iter.into_iter();
+        let len = iter.len() as wgpu::BufferAddress;
+
+        if len == 0 {
+            return Self::new(context, 0);
+        }
+
+        let buffer = context
+            .device
+            .handle
+            .create_buffer(&wgpu::BufferDescriptor {
+                label: Some("buffer"),
+                size: Self::aligned_size(len),
+                usage: BufferUsage::default().to_wgpu(),
+                mapped_at_creation: true,
+            });
+
+        {
+            let mut view = buffer.slice(..).get_mapped_range_mut();
+            let elements: &mut [T] =
+                bytemuck::cast_slice_mut(&mut view[..len as usize * mem::size_of::<T>()]);
+
+            let mut count = 0;
+            for (element, value) in elements.iter_mut().zip(&mut iter) {
+                *element = value;
+                count += 1;
+            }
+            count += iter.count() as wgpu::BufferAddress;
+
+            assert!(
+                count == len,
+                "the iterator yielded {count} elements but its length was {len}"
+            );
+        }
+        buffer.unmap();
+
+        Self {
+            allocation: Arc::new(Allocation::new(
+                &context.device,
+                ResourceKind::Buffer,
+                buffer.size(),
+            )),
+            label: Arc::default(),
+            device: Arc::clone(&context.device),
+            handle: Arc::new(buffer),
+            len,
+            #[cfg(all(debug_assertions, feature = "log"))]
+            output: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Wraps a buffer created with wgpu (i.e. the storage buffer of a renderer) so kernels
+    /// can read and write it directly, see `Buffer::as_wgpu()` to keep using it with wgpu.
+    ///
+    /// Its length is the size of the buffer in elements of T, and its usages other than
+    /// `STORAGE` are kept as they are, so it can only be read back or written if it was
+    /// created with `COPY_SRC` or `COPY_DST` (see `Buffer::usage()`).
+    ///
+    /// # Panics
+    ///
+    /// - if `buffer` was created without `STORAGE` usage.
+    /// - if the size of `buffer` is not a multiple of `std::mem::size_of::<T>()`.
+    ///
+    /// # Note
+    ///
+    /// The buffer must have been created by the device of `context` (see
+    /// `Context::wgpu_device()`), wgpu raises a validation error when it's used otherwise.
+    pub fn from_wgpu(context: &Context, buffer: wgpu::Buffer) -> Self {
+        assert!(
+            buffer.usage().contains(wgpu::BufferUsages::STORAGE),
+            "only buffers with storage usage can be bound to kernels"
+        );
+
+        let size = buffer.size();
+        let element_size = mem::size_of::<T>() as wgpu::BufferAddress;
+        assert!(
+            size % element_size == 0,
+            "buffer of {size} bytes can't hold elements of {element_size} bytes"
+        );
+
+        Self {
+            // the memory of the buffer isn't allocated by hac
+            allocation: Arc::new(Allocation::new(&context.device, ResourceKind::Buffer, 0)),
+            label: Arc::default(),
+            device: Arc::clone(&context.device),
+            handle: Arc::new(buffer),
+            len: size / element_size,
+            #[cfg(all(debug_assertions, feature = "log"))]
+            output: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Underlying wgpu buffer, to use it outside of hac (i.e. in a render pass).
+    pub fn as_wgpu(&self) -> &wgpu::Buffer {
+        &self.handle
+    }
+
+    /// Marks the buffer as an output of the kernels, in debug builds a warning is logged
+    /// with the [log](https://docs.rs/log/latest/log/) crate if it's dropped (along with
+    /// its views) without having been read back.
+    ///
+    /// It has no effect in release builds or without the `log` feature.
+    #[cfg_attr(not(all(debug_assertions, feature = "log")), allow(unused_mut))]
+    pub fn mark_as_output(mut self) -> Self {
+        #[cfg(all(debug_assertions, feature = "log"))]
+        {
+            self.output = Some(Arc::default());
+        }
+
+        self
+    }
+
+    /// Allocates a buffer on the GPU and initializes it with data after checking that `T`
+    /// matches the `layout` of the wgsl struct of its elements, see `StructLayout::check()`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// #[repr(C)]
+    /// #[derive(Clone, Copy, Default, bytemuck::Pod, bytemuck::Zeroable)]
+    /// struct Particle {
+    ///     position: [f32; 3],
+    ///     _padding: f32,
+    ///     velocity: [f32; 3],
+    ///     mass: f32,
+    /// }
+    ///
+    /// let layout = program.struct_layout("Particle").unwrap();
+    /// let offsets = [
+    ///     std::mem::offset_of!(Particle, position),
+    ///     std::mem::offset_of!(Particle, velocity),
+    ///     std::mem::offset_of!(Particle, mass),
+    /// ];
+    ///
+    /// // `_padding` has no member in wgsl, `velocity` is aligned to 16 bytes
+    /// let particles = hac::Buffer::from_slice_checked(&context, &data, &layout, &offsets)?;
+    /// ```
+    pub fn from_slice_checked(
+        context: &Context,
+        data: &[T],
+        layout: &StructLayout,
+        field_offsets: &[usize],
+    ) -> Result<Self, StructLayoutMismatch> {
+        layout.check::<T>(field_offsets)?;
+        Ok(Self::from_slice(context, data))
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Allocates a buffer on the GPU and initializes it with the elements of `array`
+    /// in logical (row major) order.
+    ///
+    /// Arrays that aren't contiguous in standard layout are copied into a contiguous
+    /// Vec before the upload.
+    pub fn from_array<S, D>(context: &Context, array: &ndarray::ArrayBase<S, D>) -> Self
+    where
+        S: ndarray::Data<Elem = T>,
+        D: ndarray::Dimension,
+    {
+        match array.as_slice() {
+            Some(data) => Self::from_slice(context, data),
+            None => Self::from_slice(context, &array.iter().copied().collect::<Vec<_>>()),
+        }
+    }
+
+    /// Number of elements of T that the buffer can hold.
+    ///
+    /// The allocation on the GPU may be slightly larger since buffer sizes are padded
+    /// to `wgpu::COPY_BUFFER_ALIGNMENT`, the padding is never read back.
+    pub fn len(&self) -> wgpu::BufferAddress {
+        self.len
+    }
+
+    /// Returns `true` if the buffer can't hold any element.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Copy usages the buffer was created with.
+    pub fn usage(&self) -> BufferUsage {
+        let usages = self.handle.usage();
+
+        BufferUsage {
+            copy_src: usages.contains(wgpu::BufferUsages::COPY_SRC),
+            copy_dst: usages.contains(wgpu::BufferUsages::COPY_DST),
+        }
+    }
+
+    /// Names the buffer (i.e. after its role in the current pass) in the messages of hac
+    /// and in the staging buffers it's read back through, replacing the previous label.
+    ///
+    /// The views of the buffer share its label.
+    ///
+    /// # Note
+    ///
+    /// wgpu 0.14 can't relabel a buffer after its creation, so the label isn't
+    /// visible in captures of the buffer itself.
+    pub fn set_label(&self, label: &str) {
+        *self.label.lock().unwrap() = Some(label.to_owned());
+    }
+
+    /// Label set with `Buffer::set_label()`.
+    pub fn label(&self) -> Option<String> {
+        self.label.lock().unwrap().clone()
+    }
+
+    /// Refers to the buffer by its label (if it has one) in messages.
+    fn describe(&self) -> String {
+        match self.label() {
+            Some(label) => format!("the buffer {label:?}"),
+            None => "the buffer".to_owned(),
+        }
+    }
+
+    pub(crate) fn assert_copy_src(&self) {
+        assert!(
+            self.usage().copy_src,
+            "{} was created without copy_src usage, it can't be read back or copied",
+            self.describe(),
+        );
+    }
+
+    pub(crate) fn assert_copy_dst(&self) {
+        assert!(
+            self.usage().copy_dst,
+            "{} was created without copy_dst usage, it can't be written",
+            self.describe(),
+        );
+    }
+
+    /// Reinterprets the contents of the buffer as elements of type `U` without copying them.
+    ///
+    /// # Panics
+    ///
+    /// - if the size of the buffer in bytes is not a multiple of `std::mem::size_of::<U>()`.
+    pub fn cast<U: Pod>(&self) -> BufferView<U> {
+        let size = self.len * mem::size_of::<T>() as wgpu::BufferAddress;
+        let element_size = mem::size_of::<U>() as wgpu::BufferAddress;
+
+        assert!(
+            size % element_size == 0,
+            "buffer of {size} bytes can't be viewed as elements of {element_size} bytes"
+        );
+
+        BufferView(Buffer {
+            device: Arc::clone(&self.device),
+            handle: Arc::clone(&self.handle),
+            allocation: Arc::clone(&self.allocation),
+            label: Arc::clone(&self.label),
+            len: size / element_size,
+            #[cfg(all(debug_assertions, feature = "log"))]
+            output: self.output.clone(),
+            _marker: PhantomData,
+        })
+    }
+
     /// Reads the contents of the buffer into a Vec.
     pub fn read_to_vec(&self) -> Vec<T> {
-        let dst_buffer = self.device.handle.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Destination copy buffer"),
-            size: self.handle.size(),
-            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
-            mapped_at_creation: false,
+        if self.is_empty() {
+            return Vec::new();
+        }
+
+        let size = self.len() * mem::size_of::<T>() as wgpu::BufferAddress;
+        self.read_mapped(0, size, |data| bytemuck::cast_slice(data).to_vec())
+    }
+
+    /// Reads the raw bytes of the elements of the buffer into a Vec (i.e. to save the state
+    /// of a simulation and restore it with `Buffer::from_bytes()`), without the padding
+    /// of the allocation.
+    pub fn read_bytes(&self) -> Vec<u8> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+
+        let size = self.len() * mem::size_of::<T>() as wgpu::BufferAddress;
+        self.read_mapped(0, size, <[u8]>::to_vec)
+    }
+
+    /// Reads the contents of the buffer into a Vec checking that it holds exactly
+    /// `expected` elements, to catch sizing mistakes at the read back.
+    ///
+    /// # Panics
+    ///
+    /// - if the length of the buffer is not `expected`.
+    pub fn read_exact(&self, expected: wgpu::BufferAddress) -> Vec<T> {
+        let len = self.len();
+        assert!(
+            len == expected,
+            "expected to read {expected} elements but {} has length {len}",
+            self.describe(),
+        );
+
+        self.read_to_vec()
+    }
+
+    /// Reads only the first `count` elements of the buffer into a Vec, cheaper than
+    /// `Buffer::read_to_vec()` for outputs with a known valid prefix.
+    ///
+    /// # Panics
+    ///
+    /// - if `count` is greater than the length of the buffer.
+    pub fn read_prefix(&self, count: wgpu::BufferAddress) -> Vec<T> {
+        let len = self.len();
+        assert!(
+            count <= len,
+            "can't read {count} elements of a buffer of length {len}"
+        );
+
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let size = count * mem::size_of::<T>() as wgpu::BufferAddress;
+        self.read_mapped(0, size, |data| bytemuck::cast_slice(data).to_vec())
+    }
+
+    /// Reads the buffer back lazily in chunks of `chunk_len` elements (the last one may be
+    /// shorter), only one chunk is copied and held in memory at a time.
+    ///
+    /// Useful to stream results that don't fit comfortably in memory (i.e. to a file).
+    ///
+    /// # Panics
+    ///
+    /// - if `chunk_len` is 0.
+    pub fn read_chunks(&self, chunk_len: usize) -> impl Iterator<Item = Vec<T>> + '_ {
+        assert!(chunk_len > 0, "the chunk length can't be 0");
+
+        let size = mem::size_of::<T>() as wgpu::BufferAddress;
+
+        (0..self.len()).step_by(chunk_len).map(move |start| {
+            let end = self.len().min(start + chunk_len as wgpu::BufferAddress);
+            self.read_mapped(start * size, end * size, |data| {
+                bytemuck::cast_slice(data).to_vec()
+            })
+        })
+    }
+
+    /// Maps a copy of the buffer for reading, its elements can then be accessed with
+    /// `MappedBuffer::view()` avoiding the allocation and copy into a Vec of
+    /// `Buffer::read_to_vec()`.
+    ///
+    /// The copy is unmapped and freed when the [`MappedBuffer`] is dropped, later writes
+    /// to the buffer are not seen by it.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut mapped = buffer.map_read();
+    /// let max = mapped.view().iter().copied().fold(f32::MIN, f32::max);
+    /// ```
+    pub fn map_read(&self) -> MappedBuffer<T> {
+        let staging = (!self.is_empty()).then(|| {
+            let staging = self.copy_to_staging(0, Self::aligned_size(self.len()));
+            staging
+                .slice(..)
+                .map_async(wgpu::MapMode::Read, move |_| {});
+            staging
         });
 
+        self.device.handle.poll(wgpu::Maintain::Wait);
+
+        MappedBuffer {
+            staging,
+            len: self.len() as usize,
+            _marker: PhantomData,
+        }
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Reads the contents of the buffer into an `ndarray::Array` with `shape`
+    /// (in standard layout).
+    ///
+    /// Returns an error if the number of elements of `shape` doesn't match the
+    /// length of the buffer.
+    pub fn read_to_array<Sh>(
+        &self,
+        shape: Sh,
+    ) -> Result<ndarray::Array<T, Sh::Dim>, ndarray::ShapeError>
+    where
+        Sh: ndarray::ShapeBuilder,
+    {
+        ndarray::Array::from_shape_vec(shape, self.read_to_vec())
+    }
+
+    /// Reads the element of the buffer at `index`.
+    ///
+    /// It's cheaper than `Buffer::read_to_vec()` when only a single value is needed
+    /// (i.e. a counter accumulated with atomics).
+    ///
+    /// # Panics
+    ///
+    /// - if `index` is out of bounds.
+    pub fn read_element(&self, index: wgpu::BufferAddress) -> T {
+        let len = self.len();
+        assert!(
+            index < len,
+            "index {index} is out of bounds of a buffer of length {len}"
+        );
+
+        let size = mem::size_of::<T>() as wgpu::BufferAddress;
+        self.read_mapped(
+            index * size,
+            (index + 1) * size,
+            bytemuck::pod_read_unaligned,
+        )
+    }
+
+    /// Copies the bytes in the range `start`..`end` of the buffer into a staging buffer
+    /// and calls `f` with them once it's mapped, the range must not be empty.
+    fn read_mapped<R>(
+        &self,
+        start: wgpu::BufferAddress,
+        end: wgpu::BufferAddress,
+        f: impl FnOnce(&[u8]) -> R,
+    ) -> R {
+        // copies must be aligned, the extra bytes are skipped when calling `f`
+        let aligned_start = start - start % wgpu::COPY_BUFFER_ALIGNMENT;
+        let aligned_end = end.next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT);
+
+        let dst_buffer = self.copy_to_staging(aligned_start, aligned_end);
+        let dst_slice = dst_buffer.slice(..);
+
+        dst_slice.map_async(wgpu::MapMode::Read, move |_| {});
+
+        self.device.handle.poll(wgpu::Maintain::Wait);
+
+        let data = dst_slice.get_mapped_range();
+        let skip = (start - aligned_start) as usize;
+        f(&data[skip..skip + (end - start) as usize])
+    }
+
+    /// Reads the contents of the buffer into a Vec without blocking.
+    ///
+    /// This is the readback path for targets where blocking isn't allowed (i.e. WebGPU
+    /// in the browser), on native targets the device is polled every time the future
+    /// is polled.
+    pub async fn read_to_vec_async(&self) -> Vec<T> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+
+        let size = self.len() * mem::size_of::<T>() as wgpu::BufferAddress;
+
+        let dst_buffer = self.copy_to_staging(0, Self::aligned_size(self.len()));
+        let dst_slice = dst_buffer.slice(..);
+
+        MapRead::new(&self.device, &dst_slice)
+            .await
+            .expect("failed to map the buffer for reading");
+
+        let data = dst_slice.get_mapped_range();
+        bytemuck::cast_slice(&data[..size as usize]).to_vec()
+    }
+
+    /// Issues a read of the contents of the buffer that can be polled without blocking
+    /// with `PendingRead::try_read()`, meant for render loops that check once per frame
+    /// whether the read back is done.
+    ///
+    /// The elements are the ones the buffer holds when the read is issued, later writes
+    /// are seen by the next read.
+    ///
+    /// # Panics
+    ///
+    /// - if the buffer was created without `BufferUsage::copy_src`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut read = output.begin_read();
+    ///
+    /// // every frame
+    /// if let Some(results) = read.try_read() {
+    ///     display(&results);
+    ///     kernel.dispatch(workgroups);
+    ///     read = output.begin_read();
+    /// }
+    /// ```
+    pub fn begin_read(&self) -> PendingRead<T> {
+        if self.is_empty() {
+            return PendingRead {
+                staging: None,
+                mapped: true,
+                len: 0,
+                _marker: PhantomData,
+            };
+        }
+
+        let staging = self.copy_to_staging(0, Self::aligned_size(self.len()));
+        let map = MapRead::new(&self.device, &staging.slice(..));
+
+        PendingRead {
+            staging: Some((staging, map)),
+            mapped: false,
+            len: self.len() as usize,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Copies the aligned range `start`..`end` of the buffer into a new buffer that
+    /// can be mapped for reading.
+    fn copy_to_staging(
+        &self,
+        start: wgpu::BufferAddress,
+        end: wgpu::BufferAddress,
+    ) -> wgpu::Buffer {
         let mut encoder =
             self.device
                 .handle
@@ -128,17 +932,240 @@ impl<T: Pod> Buffer<T> {
                     label: Some("Copy buffer command encoder"),
                 });
 
-        encoder.copy_buffer_to_buffer(&self.handle, 0, &dst_buffer, 0, dst_buffer.size());
-
+        let dst_buffer = self.encode_copy_to_staging(&mut encoder, start, end);
         self.device.queue.submit(std::iter::once(encoder.finish()));
 
-        let dst_slice = dst_buffer.slice(..);
+        dst_buffer
+    }
 
-        dst_slice.map_async(wgpu::MapMode::Read, move |_| {});
+    /// Records a copy of the aligned range `start`..`end` of the buffer into a new buffer
+    /// that can be mapped for reading once `encoder` is submitted.
+    pub(crate) fn encode_copy_to_staging(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        start: wgpu::BufferAddress,
+        end: wgpu::BufferAddress,
+    ) -> wgpu::Buffer {
+        self.assert_copy_src();
 
-        self.device.handle.poll(wgpu::Maintain::Wait);
+        #[cfg(all(debug_assertions, feature = "log"))]
+        if let Some(read) = &self.output {
+            read.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
 
-        let data = dst_slice.get_mapped_range();
-        bytemuck::cast_slice(&data).to_vec()
+        let label = self.label().map(|label| format!("{label} staging buffer"));
+        let dst_buffer = self.device.handle.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(label.as_deref().unwrap_or("Destination copy buffer")),
+            size: end - start,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        encoder.copy_buffer_to_buffer(&self.handle, start, &dst_buffer, 0, dst_buffer.size());
+
+        dst_buffer
+    }
+
+    /// Size in bytes of the allocation for `len` elements, padded to `wgpu::COPY_BUFFER_ALIGNMENT`
+    /// so that the buffer can always be copied (i.e. when reading it back).
+    ///
+    /// # Panics
+    ///
+    /// - if the size in bytes doesn't fit in a `wgpu::BufferAddress`.
+    fn aligned_size(len: wgpu::BufferAddress) -> wgpu::BufferAddress {
+        len.checked_mul(mem::size_of::<T>() as wgpu::BufferAddress)
+            .and_then(|size| size.checked_next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT))
+            .unwrap_or_else(|| panic!("capacity {len} overflows buffer size"))
+    }
+}
+
+#[cfg(all(debug_assertions, feature = "log"))]
+impl<T> Drop for Buffer<T> {
+    fn drop(&mut self) {
+        let unread = self.output.as_ref().is_some_and(|read| {
+            Arc::strong_count(read) == 1 && !read.load(std::sync::atomic::Ordering::Relaxed)
+        });
+
+        if unread && !std::thread::panicking() {
+            let label = self.label.lock().unwrap();
+            let label = label
+                .as_ref()
+                .map_or(String::new(), |label| format!(" {label:?}"));
+            log::warn!(
+                "an output buffer{label} of {} elements was dropped without being read back",
+                self.len
+            );
+        }
+    }
+}
+
+/// View of the contents of a [`Buffer`] as elements of another type.
+///
+/// The view shares the memory of the original buffer, so writes through any of them
+/// are seen by both. It dereferences to a [`Buffer`] so it can be read, written and
+/// bound (i.e. with `BindGroupDescriptor::push_buffer()`) just like any other buffer.
+#[derive(Debug)]
+pub struct BufferView<T>(Buffer<T>);
+
+impl<T> Deref for BufferView<T> {
+    type Target = Buffer<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Copy of a [`Buffer`] mapped on the CPU for reading, created with `Buffer::map_read()`.
+///
+/// It's unmapped when dropped.
+#[derive(Debug)]
+pub struct MappedBuffer<T> {
+    staging: Option<wgpu::Buffer>,
+    len: usize,
+    _marker: PhantomData<Vec<T>>,
+}
+
+impl<T: Pod> MappedBuffer<T> {
+    /// Borrows the mapped elements without copying them.
+    ///
+    /// It borrows the mapped buffer mutably since wgpu doesn't allow two views of the
+    /// same range at once.
+    pub fn view(&mut self) -> MappedBufferGuard<'_, T> {
+        MappedBufferGuard {
+            view: self
+                .staging
+                .as_ref()
+                .map(|staging| staging.slice(..).get_mapped_range()),
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Drop for MappedBuffer<T> {
+    fn drop(&mut self) {
+        if let Some(staging) = &self.staging {
+            staging.unmap();
+        }
+    }
+}
+
+/// Read only access to the elements of a [`MappedBuffer`], created with
+/// `MappedBuffer::view()`.
+///
+/// It dereferences to a slice of the elements.
+#[derive(Debug)]
+pub struct MappedBufferGuard<'a, T> {
+    view: Option<wgpu::BufferView<'a>>,
+    len: usize,
+    _marker: PhantomData<&'a [T]>,
+}
+
+impl<T: Pod> Deref for MappedBufferGuard<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        match &self.view {
+            Some(view) => bytemuck::cast_slice(&view[..self.len * mem::size_of::<T>()]),
+            None => &[],
+        }
+    }
+}
+
+/// Read back of a [`Buffer`] issued with `Buffer::begin_read()`.
+#[derive(Debug)]
+pub struct PendingRead<T> {
+    /// Copy of the buffer being mapped, `None` if the buffer is empty.
+    staging: Option<(wgpu::Buffer, MapRead)>,
+    mapped: bool,
+    len: usize,
+    _marker: PhantomData<Vec<T>>,
+}
+
+impl<T: Pod> PendingRead<T> {
+    /// Polls the device once without blocking and returns the elements of the buffer
+    /// once the read back is done, `None` until then.
+    ///
+    /// Calls after the read is done keep returning the same elements.
+    pub fn try_read(&mut self) -> Option<Vec<T>> {
+        let Some((staging, map)) = &self.staging else {
+            return Some(Vec::new());
+        };
+
+        if !self.mapped {
+            map.try_result()?
+                .expect("failed to map the buffer for reading");
+            self.mapped = true;
+        }
+
+        let size = self.len * mem::size_of::<T>();
+        Some(bytemuck::cast_slice(&staging.slice(..).get_mapped_range()[..size]).to_vec())
+    }
+}
+
+/// Future that resolves once a buffer slice requested with `wgpu::BufferSlice::map_async()`
+/// is mapped for reading.
+#[derive(Debug)]
+pub(crate) struct MapRead {
+    device: Arc<crate::Device>,
+    state: Arc<Mutex<MapState>>,
+}
+
+#[derive(Debug, Default)]
+struct MapState {
+    result: Option<Result<(), wgpu::BufferAsyncError>>,
+    waker: Option<Waker>,
+}
+
+impl MapRead {
+    pub(crate) fn new(device: &Arc<crate::Device>, slice: &wgpu::BufferSlice) -> Self {
+        let state = Arc::new(Mutex::new(MapState::default()));
+
+        let callback_state = Arc::clone(&state);
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let mut state = callback_state.lock().unwrap();
+            state.result = Some(result);
+
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+
+        Self {
+            device: Arc::clone(device),
+            state,
+        }
+    }
+
+    /// Polls the device once without blocking and returns the result of the map if
+    /// it's done.
+    fn try_result(&self) -> Option<Result<(), wgpu::BufferAsyncError>> {
+        self.device.handle.poll(wgpu::Maintain::Poll);
+        self.state.lock().unwrap().result.take()
+    }
+}
+
+impl Future for MapRead {
+    type Output = Result<(), wgpu::BufferAsyncError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        // native backends only call back while polling the device, on the web the
+        // browser calls back on its own and polling is a no-op
+        self.device.handle.poll(wgpu::Maintain::Poll);
+
+        let mut state = self.state.lock().unwrap();
+
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+
+                if cfg!(not(target_arch = "wasm32")) {
+                    cx.waker().wake_by_ref();
+                }
+
+                Poll::Pending
+            }
+        }
     }
 }
@@ -0,0 +1,240 @@
+use bytemuck::Pod;
+
+use crate::{BindGroup, Buffer, BufferAccess, Context, KernelInfo, Program, Range};
+
+/// Array of elements of T split across multiple [`Buffer`]s, meant for data that
+/// exceeds the `max_buffer_size` (or `max_storage_buffer_binding_size`) limit.
+///
+/// Every chunk has `chunk_len()` elements except for the last one which holds
+/// the remainder.
+#[derive(Debug)]
+pub struct ChunkedBuffer<T> {
+    chunks: Vec<Buffer<T>>,
+    chunk_len: wgpu::BufferAddress,
+    len: wgpu::BufferAddress,
+}
+
+/// Provides the info required to dispatch a kernel over every chunk of a [`ChunkedBuffer`].
+#[derive(Debug)]
+pub struct ChunkKernelInfo<'a> {
+    /// Program that will be executed.
+    pub program: &'a Program,
+
+    /// Function of the program that will run for every chunk.
+    pub entry_point: &'a str,
+
+    /// Access of the chunks in the kernel.
+    pub access: BufferAccess,
+
+    /// Bind groups set after the one of the chunk, the first one is set at `@group(1)`.
+    pub bind_groups: &'a [&'a BindGroup],
+
+    /// Number of invocations of a workgroup, must match the `@workgroup_size` of
+    /// the entry point (which must be 1D).
+    pub workgroup_size: u32,
+}
+
+impl<T: Pod> ChunkedBuffer<T> {
+    /// Largest number of elements of T per chunk that can be allocated and bound
+    /// with the limits of the context.
+    pub fn max_chunk_len(context: &Context) -> wgpu::BufferAddress {
+        let limits = context.device.handle.limits();
+        let max_size = limits
+            .max_buffer_size
+            .min(limits.max_storage_buffer_binding_size as wgpu::BufferAddress);
+
+        max_size / std::mem::size_of::<T>() as wgpu::BufferAddress
+    }
+
+    /// Allocates `len` zero-initialized **elements of T** in chunks of `chunk_len` elements.
+    ///
+    /// # Panics
+    ///
+    /// - if `chunk_len` is 0 or exceeds `ChunkedBuffer::max_chunk_len()`.
+    pub fn new(
+        context: &Context,
+        len: wgpu::BufferAddress,
+        chunk_len: wgpu::BufferAddress,
+    ) -> Self {
+        Self::assert_chunk_len(context, chunk_len);
+
+        let chunks = (0..len)
+            .step_by(chunk_len as usize)
+            .map(|start| Buffer::new(context, chunk_len.min(len - start)))
+            .collect();
+
+        Self {
+            chunks,
+            chunk_len,
+            len,
+        }
+    }
+
+    /// Uploads `data` in chunks of `chunk_len` elements.
+    ///
+    /// # Panics
+    ///
+    /// - if `chunk_len` is 0 or exceeds `ChunkedBuffer::max_chunk_len()`.
+    pub fn from_slice(context: &Context, data: &[T], chunk_len: wgpu::BufferAddress) -> Self {
+        Self::assert_chunk_len(context, chunk_len);
+
+        let chunks = data
+            .chunks(chunk_len as usize)
+            .map(|chunk| Buffer::from_slice(context, chunk))
+            .collect();
+
+        Self {
+            chunks,
+            chunk_len,
+            len: data.len() as wgpu::BufferAddress,
+        }
+    }
+
+    fn assert_chunk_len(context: &Context, chunk_len: wgpu::BufferAddress) {
+        let max_chunk_len = Self::max_chunk_len(context);
+
+        assert!(
+            chunk_len > 0 && chunk_len <= max_chunk_len,
+            "chunk length {chunk_len} must be between 1 and {max_chunk_len}"
+        );
+    }
+
+    /// Number of elements of all the chunks.
+    pub fn len(&self) -> wgpu::BufferAddress {
+        self.len
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of elements of every chunk but the last one.
+    pub fn chunk_len(&self) -> wgpu::BufferAddress {
+        self.chunk_len
+    }
+
+    /// The chunks in order, useful to bind them manually.
+    pub fn chunks(&self) -> &[Buffer<T>] {
+        &self.chunks
+    }
+
+    /// Reads the chunks back stitched together in a single Vec.
+    pub fn read_to_vec(&self) -> Vec<T> {
+        let mut data = Vec::with_capacity(self.len as usize);
+
+        self.chunks
+            .iter()
+            .for_each(|chunk| data.extend(chunk.read_to_vec()));
+
+        data
+    }
+
+    /// Dispatches the kernel described by `info` once per chunk in a single submission.
+    ///
+    /// Each chunk is bound at `@group(0) @binding(0)` along with its element offset
+    /// and length at `@binding(1)`. The workgroups of a chunk may be laid out in 2D
+    /// to stay under the `max_compute_workgroups_per_dimension` limit, so the kernel
+    /// must flatten its workgroup id and discard the excess invocations.
+    ///
+    /// # Panics
+    ///
+    /// - if the program doesn't match the expected layout.
+    /// - if `info.workgroup_size` is 0.
+    /// - if the buffer has more than `u32::MAX` elements (the offsets wouldn't fit a `u32`).
+    ///
+    /// # Example wgsl syntax
+    /// ```cpp,ignore
+    /// struct Chunk {
+    ///     offset: u32, // index of the first element of the chunk
+    ///     len: u32,
+    /// }
+    ///
+    /// @group(0) @binding(0)
+    /// var<storage, 'access'> data: array<'T'>;
+    /// @group(0) @binding(1)
+    /// var<storage, read> chunk: Chunk;
+    ///
+    /// @compute @workgroup_size(64)
+    /// fn main(
+    ///     @builtin(workgroup_id) wid: vec3<u32>,
+    ///     @builtin(num_workgroups) nwg: vec3<u32>,
+    ///     @builtin(local_invocation_index) lid: u32,
+    /// ) {
+    ///     let i = (wid.x + wid.y * nwg.x) * 64u + lid;
+    ///     if i >= chunk.len {
+    ///         return;
+    ///     }
+    ///
+    ///     let global_index = chunk.offset + i;
+    /// }
+    /// ```
+    pub fn dispatch(&self, context: &Context, info: &ChunkKernelInfo) {
+        assert!(info.workgroup_size > 0, "the workgroup size can't be 0");
+        assert!(
+            self.len <= u32::MAX as wgpu::BufferAddress,
+            "chunked buffers with more than u32::MAX elements can't be dispatched"
+        );
+
+        if self.is_empty() {
+            return;
+        }
+
+        let chunk_infos: Vec<Buffer<u32>> = self
+            .chunks
+            .iter()
+            .scan(0, |offset, chunk| {
+                let chunk_info = [*offset as u32, chunk.len() as u32];
+                *offset += chunk.len();
+                Some(context.buffer_from_slice(&chunk_info))
+            })
+            .collect();
+
+        let chunk_bind_groups: Vec<BindGroup> = self
+            .chunks
+            .iter()
+            .zip(&chunk_infos)
+            .map(|(chunk, chunk_info)| {
+                context
+                    .bind_group_descriptor()
+                    .push_buffer(chunk, info.access)
+                    .push_buffer(chunk_info, BufferAccess::ReadOnly)
+                    .into_bind_group()
+            })
+            .collect();
+
+        let bind_groups: Vec<&BindGroup> = std::iter::once(&chunk_bind_groups[0])
+            .chain(info.bind_groups.iter().copied())
+            .collect();
+
+        let kernel = context.kernel(&KernelInfo {
+            program: info.program,
+            entry_point: info.entry_point,
+            bind_groups: &bind_groups,
+            push_constants_range: None,
+        });
+
+        let max_per_dimension = context
+            .device
+            .handle
+            .limits()
+            .max_compute_workgroups_per_dimension;
+
+        let workgroup_size = info.workgroup_size as wgpu::BufferAddress;
+
+        chunk_bind_groups
+            .iter()
+            .zip(&self.chunks)
+            .fold(
+                context.command_queue().enqueue_set_kernel(&kernel),
+                |queue, (bind_group, chunk)| {
+                    let workgroups = chunk.len().div_ceil(workgroup_size);
+
+                    queue
+                        .enqueue_set_bind_group(0, bind_group)
+                        .enqueue_dispatch(Range::grid(workgroups, max_per_dimension))
+                },
+            )
+            .execute();
+    }
+}
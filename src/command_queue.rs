@@ -1,6 +1,14 @@
 use std::{collections::VecDeque, sync::Arc};
 
-use crate::{BindGroup, Context, Kernel, Range};
+use bytemuck::Pod;
+
+use crate::{BindGroup, Buffer, Context, Error, Image, ImageDataLayout, Kernel, Range};
+
+pub use wgpu::SubmissionIndex;
+
+/// Conventional push constants offset of the base workgroup written by
+/// `CommandQueue::enqueue_dispatch_offset()`, the start of the push constants struct.
+pub const BASE_WORKGROUP_OFFSET: u32 = 0;
 
 /// Avaiable commands to execute in a CommandQueue.
 #[derive(Debug)]
@@ -8,27 +16,106 @@ pub enum Command<'a> {
     /// Set a kernel to be able to set PushConstants or Dispatch it.
     SetKernel { kernel: &'a Kernel },
 
-    /// Set push constants in the range `offset`..`data.len()`
+    /// Set push constants in the range `offset`..`offset + data.len()`
     ///
     /// Requires a kernel to be set beforehand and the `PUSH_CONSTANT` feature.
+    /// It may be used any number of times, the values persist for every
+    /// following dispatch until they're overwritten.
     SetPushConstants { offset: u32, data: &'a [u8] },
 
     /// Sets a bind group at `index`.
     ///
     /// It's sometimes better to pre-create a set of bind groups with the same
-    /// layout rather than writing the buffers they point to.
+    /// layout rather than writing the buffers they point to (i.e. with
+    /// `BindGroup::update_buffer()`), since writes aren't ordered with the commands
+    /// of the queue.
+    ///
+    /// `offsets` has one offset in bytes for every binding of the bind group with a
+    /// dynamic offset, the windows start at offset 0 if it's empty.
     SetBindGroup {
         index: u32,
         bind_group: &'a BindGroup,
+        offsets: &'a [u32],
     },
 
     /// Dispatch a previously set Kernel with `workgroups` workgroup sizes.
     ///
     /// Requires a kernel to be set beforehand.
     Dispatch { workgroups: Range },
+
+    /// Dispatch a previously set Kernel with `workgroups` workgroup sizes split
+    /// in tiles that don't exceed the `max_compute_workgroups_per_dimension` limit.
+    ///
+    /// The workgroup offset of each tile is set as a `vec4<u32>` push constant at `offset`
+    /// with its `w` set to 0.
+    ///
+    /// Requires a kernel to be set beforehand and the `PUSH_CONSTANT` feature.
+    DispatchLarge { workgroups: Range, offset: u32 },
+
+    /// Dispatch a previously set Kernel with `workgroups` workgroup sizes starting
+    /// at the workgroup `base`, split in tiles just like `Command::DispatchLarge`.
+    ///
+    /// The base workgroup of each tile is set as a `vec4<u32>` push constant at
+    /// `offset` with its `w` set to 0.
+    ///
+    /// Requires a kernel to be set beforehand and the `PUSH_CONSTANT` feature.
+    DispatchOffset {
+        workgroups: Range,
+        base: Range,
+        offset: u32,
+    },
+
+    /// Copy `image` into `buffer` with the layout of `Image::padded_layout()`.
+    ///
+    /// Copies can't be recorded in a compute pass, so the pass ends before the copy and
+    /// the kernel, its bind groups and push constants have to be set again after it.
+    CopyImageToBuffer {
+        image: &'a Image,
+        buffer: &'a Buffer<u8>,
+    },
+
+    /// Copy the pixels of `buffer` laid out with `layout` into `image`.
+    ///
+    /// Just like `Command::CopyImageToBuffer` it ends the compute pass.
+    CopyBufferToImage {
+        buffer: &'a Buffer<u8>,
+        layout: ImageDataLayout,
+        image: &'a Image,
+    },
+}
+
+impl<'a> Command<'a> {
+    /// Returns `true` if the command is recorded outside of a compute pass.
+    fn is_copy(&self) -> bool {
+        matches!(
+            self,
+            Command::CopyImageToBuffer { .. } | Command::CopyBufferToImage { .. }
+        )
+    }
 }
 
 /// Queue that holds Commands and executes them in FIFO order.
+///
+/// # Ordering
+///
+/// Every command queue of a [`Context`] is submitted to the same `wgpu::Queue`, so
+/// executed queues run on the device one after the other in the order that they were
+/// executed, and each one sees the writes of the previous ones (including the ones of
+/// `Buffer::write()` and `Image::write()`). Executing only submits the work, it doesn't
+/// wait for it to finish, reading a buffer or image back waits for all the work before it.
+///
+/// Writes are staged and submitted right before the next execution, so every write made
+/// before executing a queue is seen by all of its commands and no write made after it
+/// (even if the device didn't run the queue yet) is. There's no need to flush anything
+/// between writing and dispatching.
+///
+/// # Overlapping work
+///
+/// Every queue records its commands into its own command buffer, commands of different
+/// queues don't depend on each other unless they use the same resources. Independent
+/// queues can be submitted together with `Context::command_queue_parallel()` so that the driver
+/// is free to overlap them, whether it does depends on the backend and the hardware
+/// (wgpu exposes a single queue, so there's no guarantee of concurrency).
 #[derive(Debug)]
 pub struct CommandQueue<'a> {
     pub(crate) device: Arc<crate::Device>,
@@ -55,9 +142,13 @@ impl<'a> CommandQueue<'a> {
         self
     }
 
-    /// Enqueue push constants in the range `offset`..`data.len()`.
+    /// Enqueue push constants in the range `offset`..`offset + data.len()`.
     /// - both `offset` and `data.len()` must always be a multiple of 4.
     ///
+    /// Push constants can be updated as many times as needed for the same kernel
+    /// (i.e. between dispatches of an iterative algorithm), each dispatch sees
+    /// the last values that were set before it.
+    ///
     /// # Note
     ///
     /// To be able to use push constants the `PUSH_CONSTANTS` feature must be enabled
@@ -69,6 +160,54 @@ impl<'a> CommandQueue<'a> {
         self
     }
 
+    /// Enqueue push constants with the bytes of `value` (i.e. a `#[repr(C)]` struct or an
+    /// array) at offset 0, checking that they fit the push constants range of the last
+    /// kernel set in the queue.
+    ///
+    /// The wgsl declaration must have the same layout as `T`, see
+    /// `CommandQueue::enqueue_set_push_constants()` for the requirements of push constants.
+    ///
+    /// # Example
+    /// ```ignore
+    /// queue.enqueue_set_kernel(&kernel)
+    ///     .enqueue_set_push_constants_typed(&[1.0f32, 1.0, 0.0, 1.0])
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - if no kernel was set before in the queue.
+    /// - if the kernel doesn't have a push constants range that starts at 0 and
+    ///   holds `std::mem::size_of::<T>()` bytes.
+    /// - if the size of `T` is not a multiple of 4.
+    pub fn enqueue_set_push_constants_typed<T: Pod>(self, value: &'a T) -> Self {
+        let kernel = self
+            .cmd_queue
+            .iter()
+            .rev()
+            .find_map(|command| match command {
+                Command::SetKernel { kernel } => Some(kernel),
+                _ => None,
+            })
+            .expect("a kernel must be set before its push constants");
+
+        let size = std::mem::size_of::<T>() as u32;
+        assert!(
+            size % wgpu::PUSH_CONSTANT_ALIGNMENT == 0,
+            "push constants of {size} bytes must be a multiple of {} bytes",
+            wgpu::PUSH_CONSTANT_ALIGNMENT,
+        );
+        assert!(
+            kernel
+                .push_constants_range
+                .as_ref()
+                .is_some_and(|range| range.start == 0 && size <= range.end),
+            "push constants of {size} bytes don't fit the push constants range {:?} of the kernel",
+            kernel.push_constants_range,
+        );
+
+        self.enqueue_set_push_constants(0, bytemuck::bytes_of(value))
+    }
+
     /// Enqueue setting a bind group at a certain index.
     ///
     /// This may be used for example when the same kernel has to run multiple times
@@ -80,9 +219,30 @@ impl<'a> CommandQueue<'a> {
     /// The bind group to be set must have the same layout as the one that was set
     /// when the currently bound kernel was created, the program will panic when executing
     /// the queue otherwise.
-    pub fn enqueue_set_bind_group(mut self, index: u32, bind_group: &'a BindGroup) -> Self {
-        self.cmd_queue
-            .push_back(Command::SetBindGroup { index, bind_group });
+    pub fn enqueue_set_bind_group(self, index: u32, bind_group: &'a BindGroup) -> Self {
+        self.enqueue_set_bind_group_with_offsets(index, bind_group, &[])
+    }
+
+    /// Enqueue setting a bind group at a certain index with the dynamic `offsets`
+    /// (in bytes) of its bindings pushed with `BindGroupDescriptor::push_dynamic_buffer()`,
+    /// in the order they were pushed.
+    ///
+    /// # Note
+    ///
+    /// Each offset must be a multiple of the `min_storage_buffer_offset_alignment` limit
+    /// and keep its window inside of the buffer, the program will panic when executing
+    /// the queue otherwise.
+    pub fn enqueue_set_bind_group_with_offsets(
+        mut self,
+        index: u32,
+        bind_group: &'a BindGroup,
+        offsets: &'a [u32],
+    ) -> Self {
+        self.cmd_queue.push_back(Command::SetBindGroup {
+            index,
+            bind_group,
+            offsets,
+        });
         self
     }
 
@@ -92,23 +252,199 @@ impl<'a> CommandQueue<'a> {
     ///
     /// Each dimension must not exceed the limit size `max_compute_workgroups_per_dimension`
     /// with a default value of 65535 that can be configured in `ContextInfo`.
-    pub fn enqueue_dispatch(mut self, workgroups: Range) -> Self {
-        self.cmd_queue.push_back(Command::Dispatch { workgroups });
+    pub fn enqueue_dispatch(mut self, workgroups: impl Into<Range>) -> Self {
+        self.cmd_queue.push_back(Command::Dispatch {
+            workgroups: workgroups.into(),
+        });
+        self
+    }
+
+    /// Enqueues a dispatch command on a set kernel that may exceed the
+    /// `max_compute_workgroups_per_dimension` limit in any dimension.
+    ///
+    /// The dispatch is split in tiles under the limit, before each tile the workgroup
+    /// offset of the tile is written as a `vec4<u32>` (with `w` set to 0) in the push
+    /// constants range `offset`..`offset + 16`, which must be part of the
+    /// `push_constants_range` of the kernel.
+    ///
+    /// # Note
+    ///
+    /// Requires the `PUSH_CONSTANTS` feature, see `CommandQueue::enqueue_set_push_constants()`.
+    ///
+    /// # Example wgsl syntax
+    /// ```cpp,ignore
+    /// struct Tile {
+    ///     offset: vec4<u32>,
+    /// }
+    ///
+    /// var<push_constant> tile: Tile;
+    ///
+    /// @compute @workgroup_size(64)
+    /// fn main(@builtin(workgroup_id) wid: vec3<u32>, @builtin(local_invocation_id) lid: vec3<u32>) {
+    ///     let workgroup = wid + tile.offset.xyz;
+    ///     let id = workgroup * vec3(64u, 1u, 1u) + lid;
+    /// }
+    /// ```
+    pub fn enqueue_dispatch_large(mut self, workgroups: impl Into<Range>, offset: u32) -> Self {
+        self.cmd_queue.push_back(Command::DispatchLarge {
+            workgroups: workgroups.into(),
+            offset,
+        });
+        self
+    }
+
+    /// Enqueues a dispatch command on a set kernel as if its workgroup ids started at `base`.
+    ///
+    /// Before the dispatch the base workgroup is written as a `vec4<u32>` (with `w` set to 0)
+    /// in the push constants range `offset`..`offset + 16`, which must be part of the
+    /// `push_constants_range` of the kernel and not overlap the push constants set by the
+    /// caller. Dispatches over the `max_compute_workgroups_per_dimension` limit are split
+    /// in tiles with their own base.
+    ///
+    /// By convention the base is the first member of the push constants struct, at
+    /// [`BASE_WORKGROUP_OFFSET`].
+    ///
+    /// # Note
+    ///
+    /// Requires the `PUSH_CONSTANTS` feature, see `CommandQueue::enqueue_set_push_constants()`.
+    ///
+    /// # Example wgsl syntax
+    /// ```cpp,ignore
+    /// struct Dispatch {
+    ///     base: vec4<u32>,
+    /// }
+    ///
+    /// var<push_constant> dispatch: Dispatch;
+    ///
+    /// @compute @workgroup_size(64)
+    /// fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    ///     let id = gid + dispatch.base.xyz * vec3(64u, 1u, 1u);
+    /// }
+    /// ```
+    pub fn enqueue_dispatch_offset(
+        mut self,
+        workgroups: impl Into<Range>,
+        base: impl Into<Range>,
+        offset: u32,
+    ) -> Self {
+        self.cmd_queue.push_back(Command::DispatchOffset {
+            workgroups: workgroups.into(),
+            base: base.into(),
+            offset,
+        });
+        self
+    }
+
+    /// Enqueues copying `image` into `buffer` on the device, see `Image::copy_to_buffer()`.
+    ///
+    /// # Note
+    ///
+    /// The kernel has to be set again after the copy to dispatch it, see
+    /// `Command::CopyImageToBuffer`.
+    ///
+    /// # Panics
+    ///
+    /// - if `buffer` is smaller than `Image::padded_size()` bytes.
+    pub fn enqueue_copy_image_to_buffer(
+        mut self,
+        image: &'a Image,
+        buffer: &'a Buffer<u8>,
+    ) -> Self {
+        assert!(
+            buffer.len() >= image.padded_size(),
+            "a buffer of {} bytes can't hold an image of {} padded bytes",
+            buffer.len(),
+            image.padded_size(),
+        );
+
+        self.cmd_queue
+            .push_back(Command::CopyImageToBuffer { image, buffer });
+        self
+    }
+
+    /// Enqueues copying the pixels of `buffer` laid out with `layout` into `image` on
+    /// the device, see `Image::write_from_buffer()`.
+    ///
+    /// # Note
+    ///
+    /// The kernel has to be set again after the copy to dispatch it, see
+    /// `Command::CopyImageToBuffer`.
+    ///
+    /// # Panics
+    ///
+    /// When executing the queue:
+    /// - if `layout` is invalid or `buffer` is too small, see `Image::write_from_buffer()`.
+    pub fn enqueue_copy_buffer_to_image(
+        mut self,
+        buffer: &'a Buffer<u8>,
+        layout: ImageDataLayout,
+        image: &'a Image,
+    ) -> Self {
+        self.cmd_queue.push_back(Command::CopyBufferToImage {
+            buffer,
+            layout,
+            image,
+        });
         self
     }
 
     /// Executes the Commands recorded in the queue.
     ///
+    /// Returns the index of the submission, see `CommandQueue::execute_after()`.
+    ///
     /// # Panics
     ///
     /// - if `Command::Dispatch` was enqueued before setting a kernel.
     /// - if `Command::SetPushConstants` was enqueued before setting a kernel.
     /// - if `Command::SetPushConstants` is used without enabling the `PUSH_CONSTANTS` feature
-    /// or exceeds the maximum set limit specified in [`ContextInfo`].
-    /// - if `Command::SetPushConstants` is used twice for the same Kernel.
+    ///   or exceeds the maximum set limit specified in [`ContextInfo`].
+    /// - if `Command::SetPushConstants` writes outside of the `push_constants_range`
+    ///   specified in [`KernelInfo`] of the currently set kernel.
     /// - if `Command::SetBindGroup` is bound at an index which is supposed to have a bind group
-    /// with a different layout.
-    pub fn execute(self) {
+    ///   with a different layout.
+    pub fn execute(self) -> SubmissionIndex {
+        self.submit()
+    }
+
+    /// Executes the Commands recorded in the queue once the submission `index` has finished
+    /// on the device, blocking the calling thread until then.
+    ///
+    /// Submissions of the same [`Context`] are already serialized on the device (see the
+    /// ordering section of [`CommandQueue`]), this is only needed when the host has to
+    /// observe the end of the previous work before submitting more (i.e. for timing).
+    pub fn execute_after(self, index: SubmissionIndex) -> SubmissionIndex {
+        self.device
+            .handle
+            .poll(wgpu::Maintain::WaitForSubmissionIndex(index));
+
+        self.submit()
+    }
+
+    /// Executes the Commands recorded in the queue, returning the errors that
+    /// `CommandQueue::execute()` would panic with.
+    pub fn try_execute(self) -> Result<SubmissionIndex, Error> {
+        let device = Arc::clone(&self.device);
+        device.capture_errors(|| self.submit())
+    }
+
+    /// Records the Commands of every queue in its own command buffer and submits all of them
+    /// at once, see `Context::command_queue_parallel()`.
+    pub(crate) fn submit_all(
+        device: &crate::Device,
+        queues: impl IntoIterator<Item = Self>,
+    ) -> SubmissionIndex {
+        let command_buffers: Vec<_> = queues.into_iter().map(Self::record).collect();
+        device.queue.submit(command_buffers)
+    }
+
+    /// Records the Commands of the queue and submits them.
+    fn submit(self) -> SubmissionIndex {
+        let device = Arc::clone(&self.device);
+        device.queue.submit(std::iter::once(self.record()))
+    }
+
+    /// Records the Commands of the queue in a command buffer of their own.
+    fn record(self) -> wgpu::CommandBuffer {
         let mut encoder =
             self.device
                 .handle
@@ -116,29 +452,128 @@ impl<'a> CommandQueue<'a> {
                     label: Some("Command encoder"),
                 });
 
-        let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-            label: Some("Compute pass"),
-        });
+        self.encode(&mut encoder);
+        encoder.finish()
+    }
 
-        self.cmd_queue
-            .into_iter()
-            .for_each(|command| compute_pass.execute(command));
+    /// Records the Commands of the queue in compute passes (split by the copies).
+    pub(crate) fn encode(self, encoder: &mut wgpu::CommandEncoder) {
+        let max_workgroups = self
+            .device
+            .handle
+            .limits()
+            .max_compute_workgroups_per_dimension;
+
+        let mut commands = self.cmd_queue.into_iter().peekable();
+
+        while commands.peek().is_some() {
+            if commands.peek().is_some_and(|command| !command.is_copy()) {
+                let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                    label: Some("Compute pass"),
+                });
+
+                while let Some(command) = commands.next_if(|command| !command.is_copy()) {
+                    compute_pass.execute(command, max_workgroups);
+                }
+            }
+
+            match commands.next() {
+                Some(Command::CopyImageToBuffer { image, buffer }) => {
+                    image.encode_copy_to_buffer_checked(encoder, buffer);
+                }
+                Some(Command::CopyBufferToImage {
+                    buffer,
+                    layout,
+                    image,
+                }) => {
+                    image.encode_copy_from_buffer_checked(encoder, buffer, layout);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Records the commands of many [`CommandQueue`]s into a single command encoder that
+/// is submitted once, created with `Context::begin_recording()`.
+///
+/// Executing a queue creates an encoder and submits it, for many small queues (i.e. a
+/// few dispatches each) that overhead can dominate the time it takes to run them.
+///
+/// # Note
+///
+/// Just like with `CommandQueue::execute()` writes are staged until the submission, so
+/// every write made before `Recorder::finish()` is seen by all of the recorded commands,
+/// even the ones recorded before the write.
+///
+/// # Example
+/// ```ignore
+/// let recorder = (0..iterations).fold(context.begin_recording(), |recorder, _| {
+///     recorder.record(context.command_queue().enqueue_set_kernel(&kernel).enqueue_dispatch(1))
+/// });
+///
+/// recorder.finish();
+/// ```
+#[derive(Debug)]
+pub struct Recorder {
+    device: Arc<crate::Device>,
+    encoder: wgpu::CommandEncoder,
+}
 
-        drop(compute_pass);
+impl Recorder {
+    /// Creates a recorder with an empty encoder.
+    pub fn new(context: &Context) -> Self {
+        let encoder =
+            context
+                .device
+                .handle
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("Recorder command encoder"),
+                });
 
-        self.device.queue.submit(std::iter::once(encoder.finish()));
+        Self {
+            device: Arc::clone(&context.device),
+            encoder,
+        }
+    }
+
+    /// Records the Commands of `queue` after the ones recorded so far, they run in the
+    /// same order as if the queues were executed one after the other.
+    ///
+    /// # Panics
+    ///
+    /// - if `queue` was created from another context.
+    /// - for the same reasons as `CommandQueue::execute()` (when the recorder is finished
+    ///   for the validation errors).
+    pub fn record(mut self, queue: CommandQueue<'_>) -> Self {
+        assert!(
+            Arc::ptr_eq(&queue.device, &self.device),
+            "command queues can only be recorded by a recorder of the same context"
+        );
+
+        queue.encode(&mut self.encoder);
+        self
+    }
+
+    /// Submits every recorded command at once.
+    ///
+    /// Returns the index of the submission, see `CommandQueue::execute_after()`.
+    pub fn finish(self) -> SubmissionIndex {
+        self.device
+            .queue
+            .submit(std::iter::once(self.encoder.finish()))
     }
 }
 
 trait ExecuteCommand<'a> {
-    fn execute(&mut self, command: Command<'a>);
+    fn execute(&mut self, command: Command<'a>, max_workgroups: u32);
 }
 
 impl<'a, 'b> ExecuteCommand<'b> for wgpu::ComputePass<'a>
 where
     'b: 'a,
 {
-    fn execute(&mut self, command: Command<'b>) {
+    fn execute(&mut self, command: Command<'b>, max_workgroups: u32) {
         match command {
             Command::SetPushConstants { offset, data } => self.set_push_constants(offset, data),
 
@@ -149,19 +584,72 @@ where
                     .bind_groups
                     .iter()
                     .enumerate()
-                    .for_each(|(i, bind_group)| {
-                        self.set_bind_group(i as u32, bind_group, &[]);
+                    .for_each(|(i, (bind_group, offsets))| {
+                        self.set_bind_group(i as u32, bind_group, offsets);
                     });
             }
 
-            Command::SetBindGroup { index, bind_group } => {
-                self.set_bind_group(index, &bind_group.handle, &[]);
+            Command::SetBindGroup {
+                index,
+                bind_group,
+                offsets,
+            } => {
+                let offsets = if offsets.is_empty() {
+                    &bind_group.default_offsets
+                } else {
+                    offsets
+                };
+
+                self.set_bind_group(index, &bind_group.handle, offsets);
             }
 
             Command::Dispatch { workgroups } => {
                 let Range { x, y, z } = workgroups;
                 self.dispatch_workgroups(x, y, z);
             }
+
+            Command::CopyImageToBuffer { .. } | Command::CopyBufferToImage { .. } => {
+                unreachable!("copies are recorded outside of compute passes")
+            }
+
+            Command::DispatchLarge { workgroups, offset } => {
+                self.dispatch_tiled(workgroups, Range::ZERO, offset, max_workgroups);
+            }
+
+            Command::DispatchOffset {
+                workgroups,
+                base,
+                offset,
+            } => {
+                self.dispatch_tiled(workgroups, base, offset, max_workgroups);
+            }
+        }
+    }
+}
+
+trait DispatchTiled {
+    /// Dispatches `workgroups` in tiles of at most `max_workgroups` per dimension,
+    /// writing the first workgroup of each tile plus `base` at the push constant `offset`
+    /// as a `vec4<u32>` with its `w` set to 0.
+    fn dispatch_tiled(&mut self, workgroups: Range, base: Range, offset: u32, max_workgroups: u32);
+}
+
+impl DispatchTiled for wgpu::ComputePass<'_> {
+    fn dispatch_tiled(&mut self, workgroups: Range, base: Range, offset: u32, max_workgroups: u32) {
+        let tiles = |total: u32| {
+            (0..total)
+                .step_by(max_workgroups as usize)
+                .map(move |start| (start, max_workgroups.min(total - start)))
+        };
+
+        for (z, depth) in tiles(workgroups.z) {
+            for (y, height) in tiles(workgroups.y) {
+                for (x, width) in tiles(workgroups.x) {
+                    let tile_base = [base.x + x, base.y + y, base.z + z, 0];
+                    self.set_push_constants(offset, bytemuck::cast_slice(&tile_base));
+                    self.dispatch_workgroups(width, height, depth);
+                }
+            }
         }
     }
 }
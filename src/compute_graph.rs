@@ -0,0 +1,222 @@
+use std::sync::Arc;
+
+use crate::{Buffer, CommandQueue, Context, Image, Kernel, Range, SubmissionIndex};
+
+/// Buffer or image read or written by a [`GraphNode`].
+///
+/// Views of a buffer (see `Buffer::cast()`) are the same resource as the buffer.
+#[derive(Debug, Clone, Copy)]
+pub struct GraphResource<'a>(ResourceRef<'a>);
+
+#[derive(Debug, Clone, Copy)]
+enum ResourceRef<'a> {
+    Buffer(&'a wgpu::Buffer),
+    Image(&'a wgpu::Texture),
+}
+
+impl GraphResource<'_> {
+    fn is(&self, other: &Self) -> bool {
+        match (self.0, other.0) {
+            (ResourceRef::Buffer(a), ResourceRef::Buffer(b)) => std::ptr::eq(a, b),
+            (ResourceRef::Image(a), ResourceRef::Image(b)) => std::ptr::eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl<'a, T> From<&'a Buffer<T>> for GraphResource<'a> {
+    fn from(buffer: &'a Buffer<T>) -> Self {
+        Self(ResourceRef::Buffer(&buffer.handle))
+    }
+}
+
+impl<'a> From<&'a Image> for GraphResource<'a> {
+    fn from(image: &'a Image) -> Self {
+        Self(ResourceRef::Image(&image.texture))
+    }
+}
+
+/// Dispatch of a kernel in a [`ComputeGraph`] along with the resources it reads
+/// and writes, which determine its dependencies.
+///
+/// The kernel is dispatched with the bind groups it was created with.
+#[derive(Debug)]
+pub struct GraphNode<'a> {
+    kernel: &'a Kernel,
+    workgroups: Range,
+    reads: Vec<GraphResource<'a>>,
+    writes: Vec<GraphResource<'a>>,
+}
+
+impl<'a> GraphNode<'a> {
+    /// Creates a node that dispatches `kernel` with `workgroups`.
+    pub fn new(kernel: &'a Kernel, workgroups: impl Into<Range>) -> Self {
+        Self {
+            kernel,
+            workgroups: workgroups.into(),
+            reads: Vec::new(),
+            writes: Vec::new(),
+        }
+    }
+
+    /// Declares that the kernel reads `resource`, so the node runs after the nodes that
+    /// write it.
+    pub fn reads(mut self, resource: impl Into<GraphResource<'a>>) -> Self {
+        self.reads.push(resource.into());
+        self
+    }
+
+    /// Declares that the kernel writes `resource`, so the node runs before the nodes that
+    /// read it.
+    pub fn writes(mut self, resource: impl Into<GraphResource<'a>>) -> Self {
+        self.writes.push(resource.into());
+        self
+    }
+
+    /// Returns `true` if the node reads a resource written by `other`.
+    fn reads_from(&self, other: &Self) -> bool {
+        self.reads
+            .iter()
+            .any(|read| other.writes.iter().any(|write| read.is(write)))
+    }
+
+    /// Returns `true` if both nodes write the same resource.
+    fn writes_with(&self, other: &Self) -> bool {
+        self.writes
+            .iter()
+            .any(|a| other.writes.iter().any(|b| a.is(b)))
+    }
+}
+
+/// Pipeline of kernels whose order is derived from the resources they read and write,
+/// executed as a whole in a single submission.
+///
+/// A node runs after every node that writes a resource it reads. When two nodes read
+/// each other's outputs (i.e. both update a buffer in place) or write the same resource
+/// they run in the order they were added.
+///
+/// The nodes are recorded in passes, each pass holds the nodes whose dependencies ran
+/// in the previous passes (see `ComputeGraph::passes()`).
+///
+/// # Example
+/// ```ignore
+/// context
+///     .compute_graph()
+///     .add_node(hac::GraphNode::new(&tonemap, workgroups).reads(&blurred).writes(&output))
+///     .add_node(hac::GraphNode::new(&blur, workgroups).reads(&input).writes(&blurred))
+///     .execute();
+/// ```
+#[derive(Debug)]
+pub struct ComputeGraph<'a> {
+    device: Arc<crate::Device>,
+    nodes: Vec<GraphNode<'a>>,
+}
+
+impl<'a> ComputeGraph<'a> {
+    /// Creates an empty graph.
+    pub fn new(context: &Context) -> Self {
+        Self {
+            device: Arc::clone(&context.device),
+            nodes: Vec::new(),
+        }
+    }
+
+    /// Adds `node` to the graph.
+    ///
+    /// # Panics
+    ///
+    /// - if the kernel of the node was created by another context.
+    pub fn add_node(mut self, node: GraphNode<'a>) -> Self {
+        assert!(
+            Arc::ptr_eq(&node.kernel.device, &self.device),
+            "graph nodes can only dispatch kernels of the same context"
+        );
+
+        self.nodes.push(node);
+        self
+    }
+
+    /// Indices of the nodes (in the order they were added) recorded in each pass.
+    ///
+    /// # Panics
+    ///
+    /// - if the dependencies of the nodes are circular.
+    pub fn passes(&self) -> Vec<Vec<usize>> {
+        let n = self.nodes.len();
+        let mut dependencies = vec![Vec::new(); n];
+
+        for i in 0..n {
+            for j in 0..i {
+                let (earlier, later) = (&self.nodes[j], &self.nodes[i]);
+                let forward = later.reads_from(earlier);
+                let backward = earlier.reads_from(later);
+
+                if backward && !forward {
+                    dependencies[j].push(i);
+                } else if forward || backward || earlier.writes_with(later) {
+                    dependencies[i].push(j);
+                }
+            }
+        }
+
+        let mut pass_of: Vec<Option<usize>> = vec![None; n];
+        let mut passes: Vec<Vec<usize>> = Vec::new();
+
+        while passes.iter().map(Vec::len).sum::<usize>() < n {
+            let pass: Vec<usize> = (0..n)
+                .filter(|&i| pass_of[i].is_none())
+                .filter(|&i| {
+                    dependencies[i]
+                        .iter()
+                        .all(|&j| pass_of[j].is_some_and(|pass| pass < passes.len()))
+                })
+                .collect();
+
+            assert!(
+                !pass.is_empty(),
+                "the nodes of the compute graph have circular dependencies"
+            );
+
+            pass.iter().for_each(|&i| pass_of[i] = Some(passes.len()));
+            passes.push(pass);
+        }
+
+        passes
+    }
+
+    /// Records every node in dependency order and submits them at once.
+    ///
+    /// Returns the index of the submission, see `CommandQueue::execute_after()`.
+    ///
+    /// # Panics
+    ///
+    /// - if the dependencies of the nodes are circular.
+    /// - for the same reasons as `CommandQueue::execute()`.
+    pub fn execute(self) -> SubmissionIndex {
+        let mut encoder =
+            self.device
+                .handle
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("Compute graph command encoder"),
+                });
+
+        for pass in self.passes() {
+            pass.into_iter()
+                .fold(
+                    CommandQueue {
+                        device: Arc::clone(&self.device),
+                        cmd_queue: std::collections::VecDeque::new(),
+                    },
+                    |queue, i| {
+                        let node = &self.nodes[i];
+                        queue
+                            .enqueue_set_kernel(node.kernel)
+                            .enqueue_dispatch(node.workgroups)
+                    },
+                )
+                .encode(&mut encoder);
+        }
+
+        self.device.queue.submit(std::iter::once(encoder.finish()))
+    }
+}
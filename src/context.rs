@@ -1,20 +1,60 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    fmt,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use bytemuck::Pod;
+#[cfg(not(target_arch = "wasm32"))]
 use pollster::FutureExt as _;
-pub use wgpu::{Backends, Features, Limits};
+pub use wgpu::{Backend, Backends, DeviceType, Error, Features, Limits, PowerPreference};
 
 use crate::{
-    BindGroupDescriptor, Buffer, CommandQueue, Image, ImageInfo, Kernel, KernelInfo, Program,
-    Sampler, SamplerInfo,
+    fill, histogram,
+    image::UNCOMPRESSED_FORMATS,
+    mipmap,
+    reduce::{self, ReduceOp},
+    resize, scan, sort, AtomicElement, BindGroupDescriptor, Buffer, BufferAccess, BufferUsage,
+    ChunkedBuffer, CommandQueue, ComputeGraph, Extent3d, FilterMode, Image, ImageDataLayout,
+    ImageFormat, ImageFormatFeatures, ImageInfo, Kernel, KernelCache, KernelInfo, MemoryReport,
+    Program, Range, Recorder, Sampler, SamplerInfo, ScanElement, ShaderError, Snapshot,
+    SubmissionIndex, UploadBuffer,
 };
 
 /// Information to create a context.
+///
+/// New fields may be added, create it with `ContextInfo::default()` or
+/// `ContextInfo::builder()`.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct ContextInfo {
     pub backends: Backends,
     pub features: Features,
     pub limits: Limits,
+    pub power_preference: PowerPreference,
+    #[cfg(feature = "trace")]
+    /// Directory where wgpu records a trace of the calls made to the device, to replay
+    /// and inspect them when debugging.
+    pub trace_path: Option<std::path::PathBuf>,
+}
+
+impl ContextInfo {
+    /// Creates a [`ContextInfoBuilder`] starting from the default info.
+    pub fn builder() -> ContextInfoBuilder {
+        ContextInfoBuilder::default()
+    }
+
+    /// Directory to record the trace of the device in, always `None` without the
+    /// `trace` feature.
+    fn trace_path(&self) -> Option<&Path> {
+        #[cfg(feature = "trace")]
+        return self.trace_path.as_deref();
+
+        #[cfg(not(feature = "trace"))]
+        None
+    }
 }
 
 impl Default for ContextInfo {
@@ -23,36 +63,353 @@ impl Default for ContextInfo {
             backends: Backends::all(),
             features: Features::empty(),
             limits: Limits::default(),
+            power_preference: PowerPreference::default(),
+            #[cfg(feature = "trace")]
+            trace_path: None,
         }
     }
 }
 
-/// Manager used to create resources
+/// Builder of a [`ContextInfo`].
+#[derive(Debug, Clone, Default)]
+pub struct ContextInfoBuilder {
+    info: ContextInfo,
+}
+
+impl ContextInfoBuilder {
+    /// Sets the backends the adapter can be requested from.
+    pub fn backends(mut self, backends: Backends) -> Self {
+        self.info.backends = backends;
+        self
+    }
+
+    /// Sets the features required by the device.
+    pub fn features(mut self, features: Features) -> Self {
+        self.info.features = features;
+        self
+    }
+
+    /// Sets the limits required by the device.
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.info.limits = limits;
+        self
+    }
+
+    /// Sets the power preference used to choose the adapter (i.e. a discrete or
+    /// an integrated GPU).
+    pub fn power_preference(mut self, power_preference: PowerPreference) -> Self {
+        self.info.power_preference = power_preference;
+        self
+    }
+
+    #[cfg(feature = "trace")]
+    /// Sets the directory where wgpu records a trace of the calls made to the device.
+    pub fn trace_path(mut self, trace_path: impl Into<std::path::PathBuf>) -> Self {
+        self.info.trace_path = Some(trace_path.into());
+        self
+    }
+
+    /// Creates the [`ContextInfo`].
+    pub fn build(self) -> ContextInfo {
+        self.info
+    }
+}
+
+/// Errors that prevent the creation of a [`Context`].
 #[derive(Debug)]
+pub enum ContextError {
+    /// There's no adapter that supports any of the requested backends.
+    NoAdapter,
+
+    /// The adapter couldn't create a device (i.e. the requested features or limits
+    /// aren't supported).
+    RequestDevice(wgpu::RequestDeviceError),
+
+    /// There's no adapter at `index`, only `count` adapters support the requested backends.
+    AdapterIndex { index: usize, count: usize },
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContextError::NoAdapter => write!(f, "no adapter supports the requested backends"),
+            ContextError::RequestDevice(e) => write!(f, "failed to request the device: {e}"),
+            ContextError::AdapterIndex { index, count } => write!(
+                f,
+                "there's no adapter at index {index}, only {count} support the requested backends"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ContextError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ContextError::NoAdapter | ContextError::AdapterIndex { .. } => None,
+            ContextError::RequestDevice(e) => Some(e),
+        }
+    }
+}
+
+/// The submitted work of the device didn't finish before the timeout of
+/// `Context::wait_idle_timeout()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeoutError {
+    pub timeout: Duration,
+}
+
+impl fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the device didn't finish its work within {:?}",
+            self.timeout
+        )
+    }
+}
+
+impl std::error::Error for TimeoutError {}
+
+/// Description of an adapter listed by `Context::enumerate_adapters()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdapterDescriptor {
+    /// Index to create a context with `Context::from_adapter_index()`.
+    pub index: usize,
+    /// Name of the adapter (i.e. the name of the GPU).
+    pub name: String,
+    /// Backend that the adapter uses.
+    pub backend: Backend,
+    /// Whether the adapter is an integrated, discrete, virtual or software device.
+    pub device_type: DeviceType,
+}
+
+/// Features and limits that `Context::new_best_effort()` had to give up to create the device.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContextFallback {
+    /// Requested features that the device was created without.
+    pub disabled_features: Features,
+    /// Names of the requested limits (i.e. `"max_buffer_size"`) that were lowered
+    /// to the ones supported by the adapter.
+    pub clamped_limits: Vec<&'static str>,
+}
+
+impl ContextFallback {
+    /// Returns `true` if the device was created with the requested features and limits.
+    pub fn is_empty(&self) -> bool {
+        self.disabled_features.is_empty() && self.clamped_limits.is_empty()
+    }
+}
+
+/// Manager used to create resources
+///
+/// Cloning a context is cheap, the clones share the same device (so the resources
+/// created from any of them can be used together) and the same kernel cache.
+#[derive(Debug, Clone)]
 pub struct Context {
     pub(crate) device: Arc<crate::Device>,
+    pub(crate) kernel_cache: Arc<KernelCache>,
 }
 
 impl Context {
+    #[cfg(not(target_arch = "wasm32"))]
     /// Creates a context.
+    ///
+    /// # Panics
+    ///
+    /// - if there's no adapter for the backends of `info` or the device can't be
+    ///   created with its features and limits, see `Context::new_async()` to handle
+    ///   these errors or `Context::new_best_effort()` to fall back to less of them.
     pub fn new(info: &ContextInfo) -> Self {
+        Self::new_async(info)
+            .block_on()
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Creates a context without blocking, meant to be awaited inside of an async
+    /// runtime where blocking on the adapter and device requests isn't desirable.
+    pub async fn new_async(info: &ContextInfo) -> Result<Self, ContextError> {
+        let adapter = Self::request_adapter(info)
+            .await
+            .ok_or(ContextError::NoAdapter)?;
+
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("Device"),
+                    features: info.features,
+                    limits: info.limits.clone(),
+                },
+                info.trace_path(),
+            )
+            .await
+            .map_err(ContextError::RequestDevice)?;
+
+        Ok(Self::from_wgpu_device(&adapter, device, queue))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    /// Creates a context like `Context::new()` but retrying with less features and lower
+    /// limits when the device can't be created with the ones of `info`.
+    ///
+    /// The device is requested with:
+    ///
+    /// 1. the features and limits of `info`.
+    /// 2. the features of `info` supported by the adapter and its limits clamped to the
+    ///    ones of the adapter.
+    /// 3. no features and the downlevel limits of WebGL2 clamped to the ones of the adapter.
+    ///
+    /// Returns the context along with what was given up to create it, or the error of the
+    /// last request.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let (context, fallback) = hac::Context::new_best_effort(&info)?;
+    /// if fallback.disabled_features.contains(hac::Features::PUSH_CONSTANTS) {
+    ///     // pass the parameters in a uniform buffer instead
+    /// }
+    /// ```
+    pub fn new_best_effort(info: &ContextInfo) -> Result<(Self, ContextFallback), ContextError> {
+        let adapter = Self::request_adapter(info)
+            .block_on()
+            .ok_or(ContextError::NoAdapter)?;
+
+        let adapter_features = adapter.features();
+        let adapter_limits = adapter.limits();
+
+        let attempts = [
+            (info.features, info.limits.clone()),
+            (
+                info.features & adapter_features,
+                clamp_limits(&info.limits, &adapter_limits),
+            ),
+            (
+                Features::empty(),
+                clamp_limits(&Limits::downlevel_webgl2_defaults(), &adapter_limits),
+            ),
+        ];
+
+        let mut error = None;
+        for (features, limits) in attempts {
+            let request = adapter
+                .request_device(
+                    &wgpu::DeviceDescriptor {
+                        label: Some("Device"),
+                        features,
+                        limits: limits.clone(),
+                    },
+                    info.trace_path(),
+                )
+                .block_on();
+
+            match request {
+                Ok((device, queue)) => {
+                    let mut clamped_limits = Vec::new();
+                    info.limits
+                        .check_limits_with_fail_fn(&limits, false, |name, _, _| {
+                            clamped_limits.push(name)
+                        });
+
+                    let fallback = ContextFallback {
+                        disabled_features: info.features - features,
+                        clamped_limits,
+                    };
+
+                    return Ok((Self::from_wgpu_device(&adapter, device, queue), fallback));
+                }
+                Err(e) => error = Some(e),
+            }
+        }
+
+        Err(ContextError::RequestDevice(
+            error.expect("at least one device was requested"),
+        ))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    /// Features supported by the adapter that `Context::new()` would pick with `info`
+    /// (only its backends and power preference are used), empty if there's no adapter.
+    ///
+    /// Useful to check which features can be requested before creating the context
+    /// (i.e. to fall back to storage buffers if `PUSH_CONSTANTS` isn't supported).
+    pub fn probe(info: &ContextInfo) -> Features {
+        Self::request_adapter(info)
+            .block_on()
+            .map_or(Features::empty(), |adapter| adapter.features())
+    }
+
+    async fn request_adapter(info: &ContextInfo) -> Option<wgpu::Adapter> {
         let instance = wgpu::Instance::new(info.backends);
 
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions::default())
+        instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: info.power_preference,
+                ..Default::default()
+            })
+            .await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    /// Lists the adapters that support any of `backends`, in the order that
+    /// `Context::from_adapter_index()` expects.
+    ///
+    /// # Note
+    ///
+    /// On the gl backend wgpu 0.14 shares the display between instances and releases it
+    /// when any of them is dropped, so adapters should be enumerated before creating the
+    /// contexts that use the gl backend.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let index = hac::Context::enumerate_adapters(hac::Backends::all())
+    ///     .into_iter()
+    ///     .find(|adapter| adapter.device_type == hac::DeviceType::DiscreteGpu)
+    ///     .map_or(0, |adapter| adapter.index);
+    /// ```
+    pub fn enumerate_adapters(backends: Backends) -> Vec<AdapterDescriptor> {
+        wgpu::Instance::new(backends)
+            .enumerate_adapters(backends)
+            .enumerate()
+            .map(|(index, adapter)| {
+                let info = adapter.get_info();
+
+                AdapterDescriptor {
+                    index,
+                    name: info.name,
+                    backend: info.backend,
+                    device_type: info.device_type,
+                }
+            })
+            .collect()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    /// Creates a context from the adapter at `index` of `Context::enumerate_adapters()`,
+    /// useful to pick a GPU of a machine that has more than one.
+    pub fn from_adapter_index(
+        backends: Backends,
+        index: usize,
+        device_descriptor: &wgpu::DeviceDescriptor,
+    ) -> Result<Self, ContextError> {
+        let mut adapters: Vec<_> = wgpu::Instance::new(backends)
+            .enumerate_adapters(backends)
+            .collect();
+        let count = adapters.len();
+
+        if index >= count {
+            return Err(ContextError::AdapterIndex { index, count });
+        }
+
+        let adapter = adapters.swap_remove(index);
+
+        let (device, queue) = adapter
+            .request_device(device_descriptor, None)
             .block_on()
-            .unwrap();
+            .map_err(ContextError::RequestDevice)?;
 
-        Self::from_wgpu_adapter(
-            &adapter,
-            &wgpu::DeviceDescriptor {
-                label: Some("Device"),
-                features: info.features,
-                limits: info.limits.clone(),
-            },
-        )
+        Ok(Self::from_wgpu_device(&adapter, device, queue))
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
     /// Creates a context from a wgpu Adapter.
     ///
     /// Useful when wanting to use a specific adapter i.e. one that supports presenting
@@ -64,43 +421,272 @@ impl Context {
         device_descriptor: &wgpu::DeviceDescriptor,
     ) -> Self {
         let (device, queue) = adapter
-            .request_device(&device_descriptor, None)
+            .request_device(device_descriptor, None)
             .block_on()
             .unwrap();
 
+        Self::from_wgpu_device(adapter, device, queue)
+    }
+
+    /// Creates a context from the device and queue that were requested from `adapter`.
+    fn from_wgpu_device(adapter: &wgpu::Adapter, device: wgpu::Device, queue: wgpu::Queue) -> Self {
+        // The formats allowed for storage (and whether they can be read or filtered) vary
+        // between adapters.
+        let format_features = if device
+            .features()
+            .contains(Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+        {
+            UNCOMPRESSED_FORMATS
+                .into_iter()
+                .map(|format| (format, adapter.get_texture_format_features(format)))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+
         Self {
             device: Arc::new(crate::Device {
                 handle: device,
                 queue,
+                adapter_features: adapter.features(),
+                format_features,
+                memory: Default::default(),
             }),
+            kernel_cache: Arc::default(),
         }
     }
 
+    /// Underlying wgpu device, to create resources shared with a wgpu renderer
+    /// (i.e. buffers wrapped with `Buffer::from_wgpu()`).
+    pub fn wgpu_device(&self) -> &wgpu::Device {
+        &self.device.handle
+    }
+
+    /// Underlying wgpu queue, the one that hac submits its commands to.
+    pub fn wgpu_queue(&self) -> &wgpu::Queue {
+        &self.device.queue
+    }
+
+    /// Features enabled in the device of the context.
+    pub fn features(&self) -> Features {
+        self.device.handle.features()
+    }
+
+    /// Limits of the device of the context (i.e. `max_compute_workgroup_storage_size`).
+    pub fn limits(&self) -> Limits {
+        self.device.handle.limits()
+    }
+
+    /// Features supported by the adapter of the context, which may include more
+    /// than the ones that were enabled when creating it.
+    pub fn adapter_features(&self) -> Features {
+        self.device.adapter_features
+    }
+
+    /// Bytes of device memory held by the buffers and images of the context that are
+    /// still alive, meant to catch runaway allocations.
+    ///
+    /// # Note
+    ///
+    /// wgpu doesn't expose the allocator of the device yet, so the memory is tracked by
+    /// hac summing the sizes of the resources it creates. Resources wrapped with
+    /// `Image::from_external()` or `Buffer::from_wgpu()` and the staging buffers of
+    /// reads aren't counted.
+    pub fn memory_report(&self) -> MemoryReport {
+        self.device.memory.report()
+    }
+
     /// Creates an empty buffer capable of holding `capacity` **elements of T**.
     ///
+    /// The contents of the buffer are zero-initialized (wgpu clears every new buffer
+    /// on all backends).
+    ///
     /// # Panics
     ///
     /// - if `capacity * std::mem::size_of::<T>()` exceeds the `max_buffer_size` limit
-    /// set in [`ContextInfo`] (with a default of 2^30).
+    ///   set in [`ContextInfo`] (with a default of 2^30).
+    /// - if `capacity * std::mem::size_of::<T>()` overflows a `u64`.
     pub fn buffer<T: Pod>(&self, capacity: wgpu::BufferAddress) -> Buffer<T> {
         Buffer::new(self, capacity)
     }
 
+    /// Creates a zero-initialized buffer capable of holding `capacity` **elements of T**
+    /// with the copy usages of `usage`.
+    ///
+    /// See `Buffer::with_usage()` for more information.
+    pub fn buffer_with_usage<T: Pod>(
+        &self,
+        capacity: wgpu::BufferAddress,
+        usage: BufferUsage,
+    ) -> Buffer<T> {
+        Buffer::with_usage(self, capacity, usage)
+    }
+
+    /// Creates a buffer capable of holding `capacity` **elements of T** with all its bytes set to 0.
+    ///
+    /// Equivalent to [`Context::buffer()`], it only makes the intent explicit.
+    ///
+    /// # Panics
+    ///
+    /// - if `capacity * std::mem::size_of::<T>()` exceeds the `max_buffer_size` limit
+    ///   set in [`ContextInfo`] (with a default of 2^30).
+    /// - if `capacity * std::mem::size_of::<T>()` overflows a `u64`.
+    pub fn zeroed_buffer<T: Pod>(&self, capacity: wgpu::BufferAddress) -> Buffer<T> {
+        Buffer::zeroed(self, capacity)
+    }
+
     /// Creates an buffer initialized from a slice.
     ///
     /// # Panics
     ///
     /// - if `std::mem::size_of_val(data)` exceeds the `max_buffer_size` limit
-    /// set in [`ContextInfo`] (with a default of 2^30).
+    ///   set in [`ContextInfo`] (with a default of 2^30).
     pub fn buffer_from_slice<T: Pod>(&self, data: &[T]) -> Buffer<T> {
         Buffer::from_slice(self, data)
     }
 
+    /// Creates a buffer initialized with the raw bytes of its elements.
+    ///
+    /// See `Buffer::from_bytes()` for more information.
+    pub fn buffer_from_bytes<T: Pod>(&self, bytes: &[u8]) -> Buffer<T> {
+        Buffer::from_bytes(self, bytes)
+    }
+
+    /// Creates a buffer initialized with the elements of an iterator of known length.
+    ///
+    /// See `Buffer::from_iter()` for more information.
+    pub fn buffer_from_iter<T, I>(&self, iter: I) -> Buffer<T>
+    where
+        T: Pod,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        Buffer::from_iter(self, iter)
+    }
+
+    /// Wraps a buffer created with the wgpu device of the context.
+    ///
+    /// See `Buffer::from_wgpu()` for more information.
+    pub fn buffer_from_wgpu<T: Pod>(&self, buffer: wgpu::Buffer) -> Buffer<T> {
+        Buffer::from_wgpu(self, buffer)
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Creates a buffer initialized from an `ndarray` array.
+    ///
+    /// See `Buffer::from_array()` for more information.
+    pub fn buffer_from_array<T, S, D>(&self, array: &ndarray::ArrayBase<S, D>) -> Buffer<T>
+    where
+        T: Pod,
+        S: ndarray::Data<Elem = T>,
+        D: ndarray::Dimension,
+    {
+        Buffer::from_array(self, array)
+    }
+
+    /// Creates a zero-initialized [`ChunkedBuffer`] of `len` elements split in the
+    /// largest chunks allowed by the limits of the context.
+    pub fn chunked_buffer<T: Pod>(&self, len: wgpu::BufferAddress) -> ChunkedBuffer<T> {
+        ChunkedBuffer::new(self, len, ChunkedBuffer::<T>::max_chunk_len(self))
+    }
+
+    /// Uploads `data` to a [`ChunkedBuffer`] split in the largest chunks allowed
+    /// by the limits of the context.
+    pub fn chunked_buffer_from_slice<T: Pod>(&self, data: &[T]) -> ChunkedBuffer<T> {
+        ChunkedBuffer::from_slice(self, data, ChunkedBuffer::<T>::max_chunk_len(self))
+    }
+
+    /// Creates a zero-initialized buffer of `capacity` elements meant to be used
+    /// with atomic operations (i.e. counters or histograms).
+    ///
+    /// See `Buffer::atomic()` for more information.
+    pub fn atomic_buffer<T: AtomicElement>(&self, capacity: wgpu::BufferAddress) -> Buffer<T> {
+        Buffer::atomic(self, capacity)
+    }
+
+    /// Creates an [`UploadBuffer`] of `capacity` **elements of T** for streaming uploads.
+    ///
+    /// See `UploadBuffer::new()` for more information.
+    pub fn upload_buffer<T: Pod>(&self, capacity: wgpu::BufferAddress) -> UploadBuffer<T> {
+        UploadBuffer::new(self, capacity)
+    }
+
     /// Creates an [`Image`] with info.
     pub fn image(&self, info: &ImageInfo) -> Image {
         Image::new(self, info)
     }
 
+    /// Creates an image of `size` with a single mip level and every pixel set to `color`
+    /// (i.e. to initialize accumulation images).
+    ///
+    /// The color is given in the linear space of the format (sRGB formats encode it) and
+    /// truncated for integer formats, the channels that the format lacks are ignored.
+    ///
+    /// # Note
+    ///
+    /// wgpu 0.14 can only clear images to zero with `CLEAR_TEXTURE`, so images that can be
+    /// rendered to (most 2D images) are cleared with a render pass and the rest are filled
+    /// with a kernel, which the gl backend doesn't support.
+    ///
+    /// # Panics
+    ///
+    /// - if `format` is a depth format.
+    /// - if the image can't be rendered to and `format` doesn't support storage.
+    pub fn image_filled(&self, size: Extent3d, format: ImageFormat, color: [f32; 4]) -> Image {
+        fill::image_filled(self, size, format, color)
+    }
+
+    /// Creates an [`Image`] initialized with `data` laid out with `layout`.
+    ///
+    /// See `Image::from_raw()` for more information.
+    pub fn image_from_raw(
+        &self,
+        data: &[u8],
+        layout: ImageDataLayout,
+        size: Extent3d,
+        format: ImageFormat,
+    ) -> Image {
+        Image::from_raw(self, data, layout, size, format)
+    }
+
+    /// Features of `format` on this context (i.e. whether it can be filtered or bound as
+    /// storage), useful to pick a fallback format before creating an image.
+    ///
+    /// The adapter specific features are only reported if the context was created with the
+    /// `TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES` feature (and never for compressed formats),
+    /// otherwise they are the features guaranteed by WebGPU.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let filterable = context
+    ///     .format_features(hac::ImageFormat::Rgba32Float)
+    ///     .flags
+    ///     .contains(hac::ImageFormatFeatureFlags::FILTERABLE);
+    /// ```
+    pub fn format_features(&self, format: ImageFormat) -> ImageFormatFeatures {
+        self.device.format_features(format)
+    }
+
+    /// Returns `true` if images with `format` can be bound as storage on this context.
+    ///
+    /// Some formats are only allowed with the `TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`
+    /// feature and an adapter that supports them, sRGB formats are never allowed.
+    pub fn supports_storage(&self, format: ImageFormat) -> bool {
+        Image::usages(&self.device, format).contains(wgpu::TextureUsages::STORAGE_BINDING)
+    }
+
+    /// Returns `true` if images with `format` can be bound as storage with
+    /// `StorageImageAccess::ReadOnly` or `StorageImageAccess::ReadWrite`.
+    ///
+    /// It's always `false` unless the context was created with the
+    /// `TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES` feature.
+    pub fn supports_read_write_storage(&self, format: ImageFormat) -> bool {
+        self.device
+            .format_features(format)
+            .flags
+            .contains(wgpu::TextureFormatFeatureFlags::STORAGE_READ_WRITE)
+    }
+
     /// Creates a [`Sampler`] with info.
     pub fn sampler(&self, info: &SamplerInfo) -> Sampler {
         Sampler::new(self, info)
@@ -108,7 +694,7 @@ impl Context {
 
     /// Creates a [`BindGroupDescriptor`] (a.k.a. descriptor set) to bind resources
     /// such as buffers, samplers and images.
-    pub fn bind_group_descriptor(&self) -> BindGroupDescriptor {
+    pub fn bind_group_descriptor(&self) -> BindGroupDescriptor<'_> {
         BindGroupDescriptor::new(self)
     }
 
@@ -123,16 +709,331 @@ impl Context {
         self.program_from_shader_source(shader_source)
     }
 
+    /// Creates a [`Program`] from the wgsl source at `entry_path` expanding its
+    /// `#include "path"` lines with the sources returned by `resolver`.
+    ///
+    /// See `Program::from_wgsl_with_includes()` for more information.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let program = context.program_from_wgsl_with_includes("main.wgsl", |path| {
+    ///     std::fs::read_to_string(std::path::Path::new("shaders").join(path)).unwrap()
+    /// });
+    /// ```
+    pub fn program_from_wgsl_with_includes(
+        &self,
+        entry_path: &str,
+        resolver: impl Fn(&str) -> String,
+    ) -> Program {
+        Program::from_wgsl_with_includes(self, entry_path, resolver)
+    }
+
+    /// Creates a [`Program`] from wgsl source code, returning the compilation error
+    /// instead of panicking if the source is invalid.
+    ///
+    /// See `Program::try_from_wgsl()` for more information.
+    pub fn try_program_from_wgsl(&self, source: &str) -> Result<Program, ShaderError> {
+        Program::try_from_wgsl(self, source)
+    }
+
     /// Creates a [`Kernel`] with info.
     pub fn kernel(&self, info: &KernelInfo) -> Kernel {
         Kernel::new(self, info)
     }
 
+    /// Creates a [`Kernel`] with info, reusing the pipeline of an equivalent kernel
+    /// created before with this method.
+    ///
+    /// See `Kernel::cached()` for more information.
+    pub fn kernel_cached(&self, info: &KernelInfo) -> Kernel {
+        Kernel::cached(self, info)
+    }
+
+    /// Number of pipelines cached by `Context::kernel_cached()`, at most
+    /// [`KERNEL_CACHE_CAPACITY`](crate::KERNEL_CACHE_CAPACITY).
+    pub fn kernel_cache_len(&self) -> usize {
+        self.kernel_cache.len()
+    }
+
+    /// Drops all the pipelines cached by `Context::kernel_cached()`.
+    pub fn clear_kernel_cache(&self) {
+        self.kernel_cache.clear();
+    }
+
+    /// Creates a [`Kernel`] with info, returning the validation errors instead of panicking.
+    ///
+    /// See `Kernel::try_new()` for more information.
+    pub fn try_kernel(&self, info: &KernelInfo) -> Result<Kernel, Error> {
+        Kernel::try_new(self, info)
+    }
+
+    /// Sets the `callback` that receives the errors of the device that weren't
+    /// captured by any of the fallible methods (i.e. `Context::try_kernel()`).
+    ///
+    /// The default behavior is to panic with the error message.
+    pub fn on_uncaptured_error(&self, callback: impl Fn(Error) + Send + 'static) {
+        self.device.handle.on_uncaptured_error(callback);
+    }
+
+    /// Processes the pending work of the device (i.e. map callbacks) without reading
+    /// anything back, if `wait` is `true` it blocks until all the submitted work is done.
+    ///
+    /// Returns `true` if there's no more submitted work in flight.
+    pub fn poll(&self, wait: bool) -> bool {
+        let maintain = if wait {
+            wgpu::Maintain::Wait
+        } else {
+            wgpu::Maintain::Poll
+        };
+
+        self.device.handle.poll(maintain)
+    }
+
+    /// Blocks until all the work submitted to the device so far has finished, an explicit
+    /// synchronization point for timing kernels or before exiting the program.
+    ///
+    /// Equivalent to `Context::poll(true)`.
+    pub fn wait_idle(&self) {
+        self.poll(true);
+    }
+
+    /// Reads the contents of `buffers` back in a single submission (i.e. to compare the
+    /// state of a kernel against a golden snapshot with `Snapshot::diff()`).
+    ///
+    /// Typed buffers can be snapshotted through a view of their bytes, `buffer.cast::<u8>()`.
+    ///
+    /// # Panics
+    ///
+    /// - if any of `buffers` was created by another context.
+    /// - if any of `buffers` was created without `BufferUsage::copy_src`.
+    pub fn snapshot(&self, buffers: &[&Buffer<u8>]) -> Snapshot {
+        Snapshot::new(self, buffers)
+    }
+
+    /// Waits until all the work submitted to the device so far has finished like
+    /// `Context::wait_idle()`, but gives up after `timeout` so a hung dispatch (i.e. a
+    /// kernel stuck in an endless loop) can be detected instead of freezing the program.
+    ///
+    /// # Note
+    ///
+    /// The timeout only stops the wait on the host, the work keeps running on the device
+    /// since it can't be preempted. The program can report the hang and stop submitting
+    /// work, but the device may still be reset by the watchdog of the OS.
+    pub fn wait_idle_timeout(&self, timeout: Duration) -> Result<(), TimeoutError> {
+        let deadline = Instant::now() + timeout;
+
+        while !self.poll(false) {
+            if Instant::now() >= deadline {
+                return Err(TimeoutError { timeout });
+            }
+
+            // short enough to not delay the wakeup, long enough to not spin on a core
+            std::thread::sleep(Duration::from_micros(100));
+        }
+
+        Ok(())
+    }
+
     /// Creates a [`CommandQueue`].
-    pub fn command_queue(&self) -> CommandQueue {
+    pub fn command_queue(&self) -> CommandQueue<'_> {
         CommandQueue::new(self)
     }
 
+    /// Creates an empty [`ComputeGraph`].
+    pub fn compute_graph(&self) -> ComputeGraph<'_> {
+        ComputeGraph::new(self)
+    }
+
+    /// Creates a [`Recorder`] to submit the commands of many queues at once.
+    pub fn begin_recording(&self) -> Recorder {
+        Recorder::new(self)
+    }
+
+    /// Executes independent `queues` in a single submission, letting the driver overlap
+    /// their work where the hardware allows it.
+    ///
+    /// The queues are ordered like consecutive executions of each one of them, so queues
+    /// that use the same resources still see each other's results (at the cost of the
+    /// overlap). See the overlapping work section of [`CommandQueue`].
+    ///
+    /// # Panics
+    ///
+    /// - if a queue was created from another context.
+    /// - for the same reasons as `CommandQueue::execute()`.
+    pub fn command_queue_parallel<'a>(
+        &self,
+        queues: impl IntoIterator<Item = CommandQueue<'a>>,
+    ) -> SubmissionIndex {
+        let queues = queues.into_iter().inspect(|queue| {
+            assert!(
+                Arc::ptr_eq(&queue.device, &self.device),
+                "command queues can only be executed by the context that created them"
+            );
+        });
+
+        CommandQueue::submit_all(&self.device, queues)
+    }
+
+    /// Fills every mip level of `image` after the first one by successively
+    /// downsampling the previous level with a box filter.
+    ///
+    /// # Panics
+    ///
+    /// - if the image is not 2D.
+    /// - if the format of the image is not a float format that supports storage:
+    ///   `Rgba8Unorm`, `Rgba8Snorm`, `Rgba16Float`, `R32Float`, `Rg32Float` or `Rgba32Float`.
+    pub fn generate_mipmaps(&self, image: &Image) {
+        mipmap::generate_mipmaps(self, image)
+    }
+
+    /// Creates a new image of `size` with the contents of `src` scaled up or down to fit it,
+    /// sampling `src` at the center of every new pixel with `filter`.
+    ///
+    /// The new image has the format of `src` and a single mip level. If `flip_y` is `true`
+    /// its rows are in reverse order, to convert between images with their first row at
+    /// the top (the convention of hac, see [`Image`]) and at the bottom (i.e. OpenGL).
+    ///
+    /// # Panics
+    ///
+    /// - if `src` or `size` are not 2D.
+    /// - if the format of `src` is not a float format that supports storage, just like
+    ///   `Context::generate_mipmaps()`.
+    /// - if `filter` is `FilterMode::Linear` and the format of `src` is not filterable
+    ///   (i.e. `R32Float`).
+    pub fn resize_image(
+        &self,
+        src: &Image,
+        size: Extent3d,
+        filter: FilterMode,
+        flip_y: bool,
+    ) -> Image {
+        resize::resize_image(self, src, size, filter, flip_y)
+    }
+
+    /// Counts the pixels of `image` whose luminance falls in each of `bins` bins that split
+    /// the range [0, 1] evenly (i.e. for auto exposure).
+    ///
+    /// The luminance of images with 3 or 4 channels is `0.2126 * r + 0.7152 * g + 0.0722 * b`
+    /// (Rec. 709), the one of images with 1 or 2 channels is their red channel. Integer
+    /// channels are divided by the largest value they can hold first (i.e. 255 for `Rgba8Uint`)
+    /// and negative luminances and the ones over 1 fall in the first and last bins.
+    ///
+    /// # Panics
+    ///
+    /// - if `bins` is 0.
+    /// - if the image is not 2D, it's multisampled or it has a depth format.
+    pub fn image_histogram(&self, image: &Image, bins: u32) -> Vec<u32> {
+        histogram::image_histogram(self, image, bins)
+    }
+
+    /// Computes the sum of all the elements of `buffer` on the device.
+    ///
+    /// Returns 0 if the buffer is empty.
+    pub fn reduce_sum(&self, buffer: &Buffer<f32>) -> f32 {
+        reduce::reduce(self, buffer, ReduceOp::Sum)
+    }
+
+    /// Computes the smallest element of `buffer` on the device.
+    ///
+    /// Returns `f32::INFINITY` if the buffer is empty.
+    pub fn reduce_min(&self, buffer: &Buffer<f32>) -> f32 {
+        reduce::reduce(self, buffer, ReduceOp::Min)
+    }
+
+    /// Computes the largest element of `buffer` on the device.
+    ///
+    /// Returns `f32::NEG_INFINITY` if the buffer is empty.
+    pub fn reduce_max(&self, buffer: &Buffer<f32>) -> f32 {
+        reduce::reduce(self, buffer, ReduceOp::Max)
+    }
+
+    /// Computes the exclusive prefix sum of `buffer` into a new buffer on the device.
+    ///
+    /// The element `i` of the result holds the sum of every element before `i` in `buffer`,
+    /// see [`ScanElement`] for the supported element types.
+    pub fn exclusive_scan<T: ScanElement>(&self, buffer: &Buffer<T>) -> Buffer<T> {
+        scan::scan(self, buffer, false)
+    }
+
+    /// Computes the inclusive prefix sum of `buffer` into a new buffer on the device.
+    ///
+    /// The element `i` of the result holds the sum of every element up to and including `i`
+    /// in `buffer`, see [`ScanElement`] for the supported element types.
+    pub fn inclusive_scan<T: ScanElement>(&self, buffer: &Buffer<T>) -> Buffer<T> {
+        scan::scan(self, buffer, true)
+    }
+
+    /// Sorts `keys` in ascending order on the device.
+    pub fn sort_u32(&self, keys: &Buffer<u32>) {
+        sort::sort_u32(self, keys, None)
+    }
+
+    /// Sorts `keys` in ascending order on the device, reordering `values` along with them.
+    ///
+    /// The sort is stable, values with equal keys keep their relative order.
+    ///
+    /// # Panics
+    ///
+    /// - if `keys` and `values` have different lengths.
+    pub fn sort_u32_by_key(&self, keys: &Buffer<u32>, values: &Buffer<u32>) {
+        sort::sort_u32(self, keys, Some(values))
+    }
+
+    /// Runs the `entry_point` of the wgsl `source` once for every element of `input`
+    /// and returns the output that it wrote, meant for prototyping.
+    ///
+    /// The input is bound read only at binding 0 and an output of the same length
+    /// at binding 1, the kernel is dispatched with `Range::d1(input.len())`.
+    ///
+    /// # Panics
+    ///
+    /// - if the program is invalid or doesn't match the expected layout.
+    /// - if `input.len()` exceeds the `max_compute_workgroups_per_dimension` limit
+    ///   set in [`ContextInfo`] (with a default of 65535), or `u32::MAX` whatever the limit.
+    ///
+    /// # Example wgsl syntax
+    /// ```cpp,ignore
+    /// @group(0) @binding(0)
+    /// var<storage, read> input: array<'T'>;
+    ///
+    /// @group(0) @binding(1)
+    /// var<storage, read_write> output: array<'T'>;
+    ///
+    /// @compute @workgroup_size(1)
+    /// fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    ///     output[id.x] = input[id.x] * 2.0;
+    /// }
+    /// ```
+    pub fn run_wgsl_1to1<T: Pod>(&self, source: &str, entry_point: &str, input: &[T]) -> Vec<T> {
+        if input.is_empty() {
+            return Vec::new();
+        }
+
+        let input = self.buffer_from_slice(input);
+        let output = Buffer::empty_like(&input);
+
+        let bind_group = self
+            .bind_group_descriptor()
+            .push_buffer(&input, BufferAccess::ReadOnly)
+            .push_buffer(&output, BufferAccess::ReadWrite)
+            .into_bind_group();
+
+        let program = self.program_from_wgsl(source);
+
+        let kernel = self.kernel(&KernelInfo {
+            program: &program,
+            entry_point,
+            bind_groups: &[&bind_group],
+            push_constants_range: None,
+        });
+
+        let workgroups = u32::try_from(input.len())
+            .expect("run_wgsl_1to1 can't dispatch more than u32::MAX workgroups");
+        kernel.dispatch(Range::d1(workgroups));
+
+        output.read_to_vec()
+    }
+
     #[cfg(feature = "from_image")]
     /// Creates an image from an RgbaImage of the image crate.
     pub fn image_from_rgba8_img(
@@ -142,4 +1043,92 @@ impl Context {
     ) -> Image {
         Image::from_rgba8_image(self, image, sample_type)
     }
+
+    #[cfg(feature = "from_image")]
+    /// Creates an sRGB image from an RgbaImage of the image crate.
+    ///
+    /// See `Image::from_srgba8_image()` for more information.
+    pub fn image_from_srgba8_img(&self, image: &image::RgbaImage) -> Image {
+        Image::from_srgba8_image(self, image)
+    }
+
+    #[cfg(feature = "from_image")]
+    /// Creates a single channel image from a GrayImage of the image crate.
+    pub fn image_from_luma8_img(
+        &self,
+        image: &image::GrayImage,
+        sample_type: crate::ImageSampleType,
+    ) -> Image {
+        Image::from_luma8_image(self, image, sample_type)
+    }
+
+    #[cfg(feature = "from_image")]
+    /// Creates a two channel image from a GrayAlphaImage of the image crate.
+    pub fn image_from_la8_img(
+        &self,
+        image: &image::GrayAlphaImage,
+        sample_type: crate::ImageSampleType,
+    ) -> Image {
+        Image::from_la8_image(self, image, sample_type)
+    }
+
+    #[cfg(feature = "from_image")]
+    /// Creates an image from the image file at `path`, converted to Rgba8.
+    ///
+    /// The image formats that can be decoded are the ones enabled in the image crate
+    /// (i.e. with its `png` or `jpeg` features).
+    pub fn image_from_path(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        sample_type: crate::ImageSampleType,
+    ) -> image::ImageResult<Image> {
+        let image = image::open(path)?.to_rgba8();
+        Ok(Image::from_rgba8_image(self, &image, sample_type))
+    }
+}
+
+/// Lowers the limits of `requested` that exceed the ones `allowed`.
+#[cfg(not(target_arch = "wasm32"))]
+fn clamp_limits(requested: &Limits, allowed: &Limits) -> Limits {
+    let mut limits = requested.clone();
+
+    macro_rules! clamp {
+        ($($name:ident),* ; $($alignment:ident),*) => {
+            $(limits.$name = limits.$name.min(allowed.$name);)*
+            $(limits.$alignment = limits.$alignment.max(allowed.$alignment);)*
+        };
+    }
+
+    clamp!(
+        max_texture_dimension_1d,
+        max_texture_dimension_2d,
+        max_texture_dimension_3d,
+        max_texture_array_layers,
+        max_bind_groups,
+        max_dynamic_uniform_buffers_per_pipeline_layout,
+        max_dynamic_storage_buffers_per_pipeline_layout,
+        max_sampled_textures_per_shader_stage,
+        max_samplers_per_shader_stage,
+        max_storage_buffers_per_shader_stage,
+        max_storage_textures_per_shader_stage,
+        max_uniform_buffers_per_shader_stage,
+        max_uniform_buffer_binding_size,
+        max_storage_buffer_binding_size,
+        max_vertex_buffers,
+        max_vertex_attributes,
+        max_vertex_buffer_array_stride,
+        max_push_constant_size,
+        max_inter_stage_shader_components,
+        max_compute_workgroup_storage_size,
+        max_compute_invocations_per_workgroup,
+        max_compute_workgroup_size_x,
+        max_compute_workgroup_size_y,
+        max_compute_workgroup_size_z,
+        max_compute_workgroups_per_dimension,
+        max_buffer_size;
+        min_uniform_buffer_offset_alignment,
+        min_storage_buffer_offset_alignment
+    );
+
+    limits
 }
@@ -0,0 +1,132 @@
+use crate::{
+    image::wgsl_storage_format, Context, Extent3d, Image, ImageDimension, ImageFormat, ImageInfo,
+    KernelInfo, Range, StorageImageAccess,
+};
+
+/// Creates a new image of `size` with every pixel set to `color`.
+pub(crate) fn image_filled(
+    context: &Context,
+    size: Extent3d,
+    format: ImageFormat,
+    color: [f32; 4],
+) -> Image {
+    assert!(
+        format.describe().sample_type != wgpu::TextureSampleType::Depth,
+        "depth images can't be filled"
+    );
+
+    let image = context.image(&ImageInfo {
+        size,
+        format,
+        ..Default::default()
+    });
+
+    // new images are already zero-initialized
+    if color == [0.0; 4] {
+        return image;
+    }
+
+    let renderable = Image::creation_usages(&context.device, format, image.dimension, 1)
+        .contains(wgpu::TextureUsages::RENDER_ATTACHMENT);
+
+    if renderable {
+        clear(context, &image, color);
+    } else {
+        fill(context, &image, color);
+    }
+
+    image
+}
+
+/// Clears `image` to `color` with a render pass.
+fn clear(context: &Context, image: &Image, [r, g, b, a]: [f32; 4]) {
+    let mut encoder =
+        context
+            .device
+            .handle
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Fill command encoder"),
+            });
+
+    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("Fill pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: &image.view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color {
+                    r: r as f64,
+                    g: g as f64,
+                    b: b as f64,
+                    a: a as f64,
+                }),
+                store: true,
+            },
+        })],
+        depth_stencil_attachment: None,
+    });
+
+    context
+        .device
+        .queue
+        .submit(std::iter::once(encoder.finish()));
+}
+
+/// Writes `color` to every pixel of `image` with a kernel, for the images that can't
+/// be rendered to (i.e. 3D images).
+fn fill(context: &Context, image: &Image, color: [f32; 4]) {
+    let format = wgsl_storage_format(image.format)
+        .expect("images can only be filled with formats that can be rendered to or stored");
+
+    let (scalar, components) = match image.format.describe().sample_type {
+        wgpu::TextureSampleType::Uint => ("u32", color.map(|c| format!("{}u", c as u32))),
+        wgpu::TextureSampleType::Sint => ("i32", color.map(|c| format!("{}", c as i32))),
+        _ => ("f32", color.map(|c| format!("{c:?}"))),
+    };
+
+    let (dimension, coords) = match image.dimension {
+        ImageDimension::D3 => ("3d", "vec3<i32>(id)"),
+        _ => ("2d", "vec2<i32>(id.xy)"),
+    };
+
+    let source = format!(
+        "@group(0) @binding(0)
+        var output: texture_storage_{dimension}<{format}, write>;
+
+        @compute @workgroup_size(4, 4, 4)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {{
+            if any(id >= vec3({}u, {}u, {}u)) {{
+                return;
+            }}
+
+            textureStore(output, {coords}, vec4<{scalar}>({}));
+        }}",
+        image.width(),
+        image.height(),
+        image.depth(),
+        components.join(", "),
+    );
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_storage_image(image, StorageImageAccess::WriteOnly)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(&source);
+    let kernel = context.kernel(&KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    context
+        .command_queue()
+        .enqueue_set_kernel(&kernel)
+        .enqueue_dispatch(Range::d3(
+            image.width().div_ceil(4),
+            image.height().div_ceil(4),
+            image.depth().div_ceil(4),
+        ))
+        .execute();
+}
@@ -0,0 +1,63 @@
+use crate::{BufferAccess, Context, Image, ImageDimension, KernelInfo, Range};
+
+/// Counts the pixels of `image` whose luminance falls in each of `bins` even bins of [0, 1].
+pub(crate) fn image_histogram(context: &Context, image: &Image, bins: u32) -> Vec<u32> {
+    assert!(bins > 0, "a histogram needs at least one bin");
+    assert!(
+        image.dimension == ImageDimension::D2 && image.sample_count == 1,
+        "histograms can only be computed for single sampled 2D images"
+    );
+
+    let info = image.format.describe();
+    let channels = info.components as u32;
+    let channel_bits = 8 * info.block_size as u32 / channels;
+
+    // integer values are normalized by the largest value of their channels
+    let (texel, max) = match info.sample_type {
+        wgpu::TextureSampleType::Float { .. } => ("f32", 1.0),
+        wgpu::TextureSampleType::Uint => ("u32", 2f64.powi(channel_bits as i32) - 1.0),
+        wgpu::TextureSampleType::Sint => ("i32", 2f64.powi(channel_bits as i32 - 1) - 1.0),
+        wgpu::TextureSampleType::Depth => panic!("histograms can't be computed for depth images"),
+    };
+
+    let luminance = if channels >= 3 {
+        "dot(color.rgb, vec3<f32>(0.2126, 0.7152, 0.0722))"
+    } else {
+        "color.r"
+    };
+
+    let source = format!(
+        "@group(0) @binding(0)\n\
+         var input: texture_2d<{texel}>;\n\
+         fn load(pos: vec2<i32>) -> vec4<f32> {{\n\
+             return vec4<f32>(textureLoad(input, pos, 0)) / {max:?};\n\
+         }}\n\
+         fn luminance(color: vec4<f32>) -> f32 {{\n\
+             return {luminance};\n\
+         }}\n\
+         {}",
+        include_str!("histogram.wgsl")
+    );
+    let program = context.program_from_wgsl(&source);
+
+    let counts = context.atomic_buffer::<u32>(bins as wgpu::BufferAddress);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_image(image)
+        .push_buffer(&counts, BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let kernel = context.kernel(&KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    kernel.dispatch(Range::d2(
+        image.width().div_ceil(8),
+        image.height().div_ceil(8),
+    ));
+
+    counts.read_to_vec()
+}
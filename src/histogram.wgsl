@@ -0,0 +1,20 @@
+// `input`, `load()` and `luminance()` are declared by `histogram.rs` for the format of the image.
+
+@group(0) @binding(1)
+var<storage, read_write> bins: array<atomic<u32>>;
+
+// every pixel adds one to the bin of its luminance, the bins split [0, 1] evenly
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let size = textureDimensions(input);
+    let pos = vec2<i32>(id.xy);
+
+    if (pos.x >= size.x || pos.y >= size.y) {
+        return;
+    }
+
+    let count = arrayLength(&bins);
+    let value = clamp(luminance(load(pos)), 0.0, 1.0);
+
+    atomicAdd(&bins[min(u32(value * f32(count)), count - 1u)], 1u);
+}
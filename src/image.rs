@@ -1,12 +1,17 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
-pub use wgpu::{Extent3d, ImageDataLayout};
+pub use wgpu::{Extent3d, ImageDataLayout, Origin3d};
 
-use crate::Context;
+use crate::{
+    memory::{Allocation, ResourceKind},
+    Buffer, Context, WriteError,
+};
 
 pub type ImageFormat = wgpu::TextureFormat;
 pub type ImageDimension = wgpu::TextureDimension;
 pub type StorageImageAccess = wgpu::StorageTextureAccess;
+pub type ImageFormatFeatures = wgpu::TextureFormatFeatures;
+pub type ImageFormatFeatureFlags = wgpu::TextureFormatFeatureFlags;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ImageSampleType {
@@ -30,8 +35,101 @@ impl From<ImageSampleType> for wgpu::TextureSampleType {
     }
 }
 
+/// Order of the channels of the pixels read with `Image::read_to_vec_as()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelOrder {
+    /// Red, green, blue and alpha (i.e. what `image::RgbaImage` expects).
+    Rgba,
+
+    /// Blue, green, red and alpha (i.e. the order of `ImageFormat::Bgra8Unorm`).
+    Bgra,
+}
+
+/// Formats that can be used for storage images along with their name in wgsl.
+pub(crate) const STORAGE_FORMATS: [(ImageFormat, &str); 16] = [
+    (ImageFormat::Rgba8Unorm, "rgba8unorm"),
+    (ImageFormat::Rgba8Snorm, "rgba8snorm"),
+    (ImageFormat::Rgba8Uint, "rgba8uint"),
+    (ImageFormat::Rgba8Sint, "rgba8sint"),
+    (ImageFormat::Rgba16Uint, "rgba16uint"),
+    (ImageFormat::Rgba16Sint, "rgba16sint"),
+    (ImageFormat::Rgba16Float, "rgba16float"),
+    (ImageFormat::R32Uint, "r32uint"),
+    (ImageFormat::R32Sint, "r32sint"),
+    (ImageFormat::R32Float, "r32float"),
+    (ImageFormat::Rg32Uint, "rg32uint"),
+    (ImageFormat::Rg32Sint, "rg32sint"),
+    (ImageFormat::Rg32Float, "rg32float"),
+    (ImageFormat::Rgba32Uint, "rgba32uint"),
+    (ImageFormat::Rgba32Sint, "rgba32sint"),
+    (ImageFormat::Rgba32Float, "rgba32float"),
+];
+
+/// Formats that aren't block compressed, the ones whose features are queried from the
+/// adapter when the device has the `TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES` feature.
+pub(crate) const UNCOMPRESSED_FORMATS: [ImageFormat; 47] = [
+    ImageFormat::R8Unorm,
+    ImageFormat::R8Snorm,
+    ImageFormat::R8Uint,
+    ImageFormat::R8Sint,
+    ImageFormat::R16Uint,
+    ImageFormat::R16Sint,
+    ImageFormat::R16Unorm,
+    ImageFormat::R16Snorm,
+    ImageFormat::R16Float,
+    ImageFormat::Rg8Unorm,
+    ImageFormat::Rg8Snorm,
+    ImageFormat::Rg8Uint,
+    ImageFormat::Rg8Sint,
+    ImageFormat::R32Uint,
+    ImageFormat::R32Sint,
+    ImageFormat::R32Float,
+    ImageFormat::Rg16Uint,
+    ImageFormat::Rg16Sint,
+    ImageFormat::Rg16Unorm,
+    ImageFormat::Rg16Snorm,
+    ImageFormat::Rg16Float,
+    ImageFormat::Rgba8Unorm,
+    ImageFormat::Rgba8UnormSrgb,
+    ImageFormat::Rgba8Snorm,
+    ImageFormat::Rgba8Uint,
+    ImageFormat::Rgba8Sint,
+    ImageFormat::Bgra8Unorm,
+    ImageFormat::Bgra8UnormSrgb,
+    ImageFormat::Rgb9e5Ufloat,
+    ImageFormat::Rgb10a2Unorm,
+    ImageFormat::Rg11b10Float,
+    ImageFormat::Rg32Uint,
+    ImageFormat::Rg32Sint,
+    ImageFormat::Rg32Float,
+    ImageFormat::Rgba16Uint,
+    ImageFormat::Rgba16Sint,
+    ImageFormat::Rgba16Unorm,
+    ImageFormat::Rgba16Snorm,
+    ImageFormat::Rgba16Float,
+    ImageFormat::Rgba32Uint,
+    ImageFormat::Rgba32Sint,
+    ImageFormat::Rgba32Float,
+    ImageFormat::Depth16Unorm,
+    ImageFormat::Depth24Plus,
+    ImageFormat::Depth24PlusStencil8,
+    ImageFormat::Depth32Float,
+    ImageFormat::Depth32FloatStencil8,
+];
+
+/// Name of `format` in wgsl if it can be used for storage images.
+pub(crate) fn wgsl_storage_format(format: ImageFormat) -> Option<&'static str> {
+    STORAGE_FORMATS
+        .into_iter()
+        .find_map(|(storage_format, name)| (storage_format == format).then_some(name))
+}
+
 /// Information to create an Image.
+///
+/// New fields may be added, create it with `ImageInfo::d2()`, `ImageInfo::d3()` or
+/// `ImageInfo::builder()`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct ImageInfo {
     /// Size of the image.
     ///
@@ -39,18 +137,128 @@ pub struct ImageInfo {
     pub size: Extent3d,
 
     /// Format of the image.
+    ///
+    /// Images can only be viewed, bound and copied to other images with this format, wgpu
+    /// doesn't support reinterpreting it (i.e. `Rgba8Unorm` as `Rgba8UnormSrgb`) yet. To get
+    /// the other interpretation copy the image into a buffer with `Image::copy_to_buffer()`
+    /// and then into an image of the other format with `Image::write_from_buffer()`.
     pub format: ImageFormat,
+
+    /// Number of mip levels of the image, must be at least 1 (the default).
+    ///
+    /// The levels after the first can be filled with `Context::generate_mipmaps()`.
+    pub mip_level_count: u32,
+
+    /// Number of samples per pixel, 1 (the default) for every image used in kernels.
+    ///
+    /// Multisampled images are meant to be rendered to by a graphics pass that shares
+    /// the device, they can be sampled with `textureLoad()` or resolved to a single
+    /// sampled image with `Image::resolve_to()`, but not copied nor used for storage.
+    pub sample_count: u32,
+}
+
+impl ImageInfo {
+    /// Creates an [`ImageInfoBuilder`] starting from the default info.
+    pub fn builder() -> ImageInfoBuilder {
+        ImageInfoBuilder::default()
+    }
+
+    /// Info of a 2D image of `width` x `height` pixels with a single mip level and sample.
+    pub const fn d2(width: u32, height: u32, format: ImageFormat) -> Self {
+        Self::d3(width, height, 1, format)
+    }
+
+    /// Info of a 3D image of `width` x `height` x `depth` pixels with a single mip level
+    /// and sample.
+    pub const fn d3(width: u32, height: u32, depth: u32, format: ImageFormat) -> Self {
+        Self {
+            size: Extent3d {
+                width,
+                height,
+                depth_or_array_layers: depth,
+            },
+            format,
+            mip_level_count: 1,
+            sample_count: 1,
+        }
+    }
+}
+
+impl Default for ImageInfo {
+    /// A 1x1 `Rgba8Unorm` image with a single mip level and sample.
+    fn default() -> Self {
+        Self {
+            size: Extent3d::default(),
+            format: ImageFormat::Rgba8Unorm,
+            mip_level_count: 1,
+            sample_count: 1,
+        }
+    }
+}
+
+/// Builder of an [`ImageInfo`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImageInfoBuilder {
+    info: ImageInfo,
+}
+
+impl ImageInfoBuilder {
+    /// Sets the size of the image.
+    pub fn size(mut self, size: Extent3d) -> Self {
+        self.info.size = size;
+        self
+    }
+
+    /// Sets the format of the image.
+    pub fn format(mut self, format: ImageFormat) -> Self {
+        self.info.format = format;
+        self
+    }
+
+    /// Sets the number of mip levels of the image.
+    pub fn mip_level_count(mut self, mip_level_count: u32) -> Self {
+        self.info.mip_level_count = mip_level_count;
+        self
+    }
+
+    /// Sets the number of samples per pixel of the image.
+    pub fn sample_count(mut self, sample_count: u32) -> Self {
+        self.info.sample_count = sample_count;
+        self
+    }
+
+    /// Creates the [`ImageInfo`].
+    pub fn build(self) -> ImageInfo {
+        self.info
+    }
 }
 
 /// Handle of an image stored in the GPU.
+///
+/// # Coordinates
+///
+/// The pixel (0, 0) is the top left one and rows go from top to bottom: the data written
+/// and read back starts with the top row, `textureLoad(image, vec2(x, y))` loads the
+/// column `x` of the row `y` and the texture coordinates (0, 0) are at the top left
+/// corner. Kernels dispatched over an image get the column in `id.x` and the row in
+/// `id.y`, just like CUDA and OpenCL.
+///
+/// Data with the first row at the bottom (i.e. read back from OpenGL) appears upside
+/// down, see the `flip_y` argument of `Context::resize_image()`.
 #[derive(Debug)]
 pub struct Image {
     pub(crate) device: Arc<crate::Device>,
     pub(crate) texture: wgpu::Texture,
     pub(crate) view: wgpu::TextureView,
+    pub(crate) mip_views: Vec<wgpu::TextureView>,
     pub(crate) size: Extent3d,
     pub(crate) format: ImageFormat,
     pub(crate) dimension: ImageDimension,
+    pub(crate) mip_level_count: u32,
+    pub(crate) sample_count: u32,
+    /// Set by `Image::set_label()`.
+    label: Mutex<Option<String>>,
+    _allocation: Allocation,
 }
 
 impl Image {
@@ -63,8 +271,45 @@ impl Image {
             | wgpu::TextureUsages::COPY_SRC.bits(),
     );
 
+    /// Usages of an image with `format` on `device`, storage is left out for the formats
+    /// that don't allow it.
+    pub(crate) fn usages(device: &crate::Device, format: ImageFormat) -> wgpu::TextureUsages {
+        Self::USAGES & device.format_features(format).allowed_usages
+    }
+
+    /// Usages an image is created with, 2D images can also be render attachments to be
+    /// resolve targets and multisampled images can only be sampled and rendered to.
+    pub(crate) fn creation_usages(
+        device: &crate::Device,
+        format: ImageFormat,
+        dimension: ImageDimension,
+        sample_count: u32,
+    ) -> wgpu::TextureUsages {
+        let allowed_usages = device.format_features(format).allowed_usages;
+
+        if sample_count > 1 {
+            return (wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT)
+                & allowed_usages;
+        }
+
+        let mut usages = Self::usages(device, format);
+
+        if dimension == ImageDimension::D2 {
+            usages |= wgpu::TextureUsages::RENDER_ATTACHMENT & allowed_usages;
+        }
+
+        usages
+    }
+
     /// Creates an empty image with the specified info.
     ///
+    /// # Panics
+    ///
+    /// - if `info.mip_level_count` is 0 or exceeds the number of times the image
+    ///   can be halved (i.e. `1 + log2(max(width, height))` for 2D images).
+    /// - if `info.sample_count` is greater than 1 and the image is not 2D, has more
+    ///   than one mip level or its format can't be multisampled.
+    ///
     /// # Note
     ///
     /// The easiest way to submit an image to the GPU is by creating an
@@ -78,106 +323,560 @@ impl Image {
             wgpu::TextureDimension::D3
         };
 
+        if info.sample_count > 1 {
+            assert!(
+                dimension == ImageDimension::D2 && info.mip_level_count == 1,
+                "multisampled images must be 2D and have a single mip level"
+            );
+            assert!(
+                context
+                    .device
+                    .format_features(info.format)
+                    .flags
+                    .contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE),
+                "{:?} images can't be multisampled",
+                info.format,
+            );
+        }
+
         let texture = context
             .device
             .handle
             .create_texture(&wgpu::TextureDescriptor {
                 label: Some("Image"),
-                usage: Self::USAGES,
-                mip_level_count: 1,
-                sample_count: 1,
+                usage: Self::creation_usages(
+                    &context.device,
+                    info.format,
+                    dimension,
+                    info.sample_count,
+                ),
+                mip_level_count: info.mip_level_count,
+                sample_count: info.sample_count,
                 format: info.format,
                 size: info.size,
                 dimension,
             });
 
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let mip_views = Self::mip_views(&texture, info.mip_level_count);
 
         Self {
             view,
+            mip_views,
             texture,
             dimension,
             size: info.size,
             format: info.format,
+            mip_level_count: info.mip_level_count,
+            sample_count: info.sample_count,
+            label: Mutex::default(),
+            _allocation: Self::allocation(&context.device, info, dimension),
             device: Arc::clone(&context.device),
         }
     }
 
-    /// Creates an empty image with the same size and format of the original image.
+    /// Creates an image of `size` with `format` initialized with `data` laid out with `layout`.
+    ///
+    /// Unlike the constructors from `image` buffers the rows of `data` don't have to be
+    /// tightly packed, `layout.bytes_per_row` and `layout.rows_per_image` give the stride
+    /// of its rows and layers (i.e. for decoders that align their rows).
+    ///
+    /// # Panics
+    ///
+    /// - for the same reasons as `Image::new()` and `Image::write()`.
+    pub fn from_raw(
+        context: &Context,
+        data: &[u8],
+        layout: ImageDataLayout,
+        size: Extent3d,
+        format: ImageFormat,
+    ) -> Self {
+        let self_ = Self::new(
+            context,
+            &ImageInfo {
+                size,
+                format,
+                ..Default::default()
+            },
+        );
+
+        self_.write(data, layout, size);
+        self_
+    }
+
+    /// Creates an empty image with the same size, format and mip levels of the original image.
     pub fn empty_like(original: &Self) -> Self {
+        Self::empty_with_format(original, original.format)
+    }
+
+    /// Creates a copy of the original image with the contents of all its mip levels.
+    ///
+    /// # Panics
+    ///
+    /// - if the image is multisampled.
+    pub fn duplicate(original: &Self) -> Self {
+        original.assert_single_sampled();
+
+        let copy = Self::empty_like(original);
+
+        let mut encoder =
+            original
+                .device
+                .handle
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("Duplicate command encoder"),
+                });
+
+        for level in 0..original.mip_level_count {
+            let texture_copy = |texture| wgpu::ImageCopyTexture {
+                texture,
+                mip_level: level,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            };
+
+            encoder.copy_texture_to_texture(
+                texture_copy(&original.texture),
+                texture_copy(&copy.texture),
+                original.mip_level_size(level),
+            );
+        }
+
+        original
+            .device
+            .queue
+            .submit(std::iter::once(encoder.finish()));
+
+        copy
+    }
+
+    /// Creates an empty image with the same size and mip levels of the original image
+    /// but with another `format` (i.e. an `Rgba16Float` intermediate of an `Rgba8Unorm` input).
+    ///
+    /// # Panics
+    ///
+    /// - if `format` requires features that aren't enabled (i.e. compressed formats).
+    /// - if images with `format` can't be sampled and copied on the device.
+    pub fn empty_with_format(original: &Self, format: ImageFormat) -> Self {
         let &Image {
-            format,
             size,
             dimension,
+            mip_level_count,
+            sample_count,
             ..
         } = original;
 
+        let required_features = format.describe().required_features;
+        assert!(
+            original
+                .device
+                .handle
+                .features()
+                .contains(required_features),
+            "{format:?} images require the {required_features:?} features"
+        );
+
+        let usage = Self::creation_usages(&original.device, format, dimension, sample_count);
+        let required_usages = if sample_count > 1 {
+            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT
+        } else {
+            wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC
+                | wgpu::TextureUsages::COPY_DST
+        };
+
+        assert!(
+            usage.contains(required_usages),
+            "{format:?} images can't be sampled and copied"
+        );
+
         let texture = original
             .device
             .handle
             .create_texture(&wgpu::TextureDescriptor {
                 label: Some("Image"),
-                usage: Self::USAGES,
-                mip_level_count: 1,
-                sample_count: 1,
+                usage,
+                mip_level_count,
+                sample_count,
                 dimension,
                 format,
                 size,
             });
 
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let mip_views = Self::mip_views(&texture, mip_level_count);
 
         Self {
             size,
             view,
+            mip_views,
             format,
             texture,
             dimension,
+            mip_level_count,
+            sample_count,
+            label: Mutex::default(),
+            _allocation: Self::allocation(
+                &original.device,
+                &ImageInfo {
+                    size,
+                    format,
+                    mip_level_count,
+                    sample_count,
+                },
+                dimension,
+            ),
             device: Arc::clone(&original.device),
         }
     }
 
+    /// Wraps an image created outside of hac on the Vulkan device of the context, i.e. a
+    /// decoder frame imported from a DMA-BUF with `VK_EXT_external_memory_dma_buf`, without
+    /// copying it.
+    ///
+    /// `drop_guard` is dropped along with the image, use it to release the memory of the
+    /// image (the `vk::Image` itself is never destroyed by hac).
+    ///
+    /// # Safety
+    ///
+    /// - the context must use the Vulkan backend and `raw_image` must belong to its device.
+    /// - `raw_image` must match `info` (size, format, mip levels and samples), be 2D unless
+    ///   its depth is greater than 1 and be bound to memory that outlives `drop_guard`.
+    /// - `raw_image` must have been created with the transfer src/dst and sampled usages,
+    ///   plus storage if the format supports it (see `Context::supports_storage()`).
+    /// - the contents must be in `VK_IMAGE_LAYOUT_GENERAL` or be acceptable to lose, wgpu
+    ///   transitions the image from `VK_IMAGE_LAYOUT_UNDEFINED` on its first use.
+    ///
+    /// # Note
+    ///
+    /// wgpu doesn't enable the external memory extensions when requesting a device, the
+    /// context must be created from a device that has them for the import to be possible.
+    #[cfg(feature = "vulkan-interop")]
+    pub unsafe fn from_external(
+        context: &Context,
+        raw_image: ash::vk::Image,
+        info: &ImageInfo,
+        drop_guard: Option<Box<dyn std::any::Any + Send + Sync>>,
+    ) -> Self {
+        let dimension = if info.size.depth_or_array_layers == 1 {
+            wgpu::TextureDimension::D2
+        } else {
+            wgpu::TextureDimension::D3
+        };
+
+        let desc = wgpu::TextureDescriptor {
+            label: Some("External image"),
+            usage: Self::creation_usages(
+                &context.device,
+                info.format,
+                dimension,
+                info.sample_count,
+            ),
+            mip_level_count: info.mip_level_count,
+            sample_count: info.sample_count,
+            format: info.format,
+            size: info.size,
+            dimension,
+        };
+
+        let mut hal_usage = wgpu_hal::TextureUses::COPY_SRC
+            | wgpu_hal::TextureUses::COPY_DST
+            | wgpu_hal::TextureUses::RESOURCE;
+        if desc.usage.contains(wgpu::TextureUsages::STORAGE_BINDING) {
+            hal_usage |= wgpu_hal::TextureUses::STORAGE_READ_WRITE;
+        }
+
+        let hal_texture = <wgpu_hal::api::Vulkan as wgpu_hal::Api>::Device::texture_from_raw(
+            raw_image,
+            &wgpu_hal::TextureDescriptor {
+                label: desc.label,
+                size: desc.size,
+                mip_level_count: desc.mip_level_count,
+                sample_count: desc.sample_count,
+                dimension: desc.dimension,
+                format: desc.format,
+                usage: hal_usage,
+                memory_flags: wgpu_hal::MemoryFlags::empty(),
+            },
+            drop_guard,
+        );
+
+        let texture = context
+            .device
+            .handle
+            .create_texture_from_hal::<wgpu_hal::api::Vulkan>(hal_texture, &desc);
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let mip_views = Self::mip_views(&texture, info.mip_level_count);
+
+        Self {
+            view,
+            mip_views,
+            texture,
+            dimension,
+            size: info.size,
+            format: info.format,
+            mip_level_count: info.mip_level_count,
+            sample_count: info.sample_count,
+            // the memory of external images isn't allocated by hac
+            label: Mutex::default(),
+            _allocation: Allocation::new(&context.device, ResourceKind::Image, 0),
+            device: Arc::clone(&context.device),
+        }
+    }
+
+    /// Accounts the bytes of every mip level and sample of an image to the memory usage
+    /// of `device`.
+    fn allocation(
+        device: &Arc<crate::Device>,
+        info: &ImageInfo,
+        dimension: ImageDimension,
+    ) -> Allocation {
+        let format_info = info.format.describe();
+        let (block_width, block_height) = format_info.block_dimensions;
+
+        let size: u64 = (0..info.mip_level_count)
+            .map(|level| {
+                let size = info
+                    .size
+                    .mip_level_size(level, dimension == ImageDimension::D3);
+
+                let blocks_wide = size.width.div_ceil(block_width as u32) as u64;
+                let blocks_high = size.height.div_ceil(block_height as u32) as u64;
+
+                blocks_wide
+                    * blocks_high
+                    * size.depth_or_array_layers as u64
+                    * format_info.block_size as u64
+            })
+            .sum();
+
+        Allocation::new(device, ResourceKind::Image, size * info.sample_count as u64)
+    }
+
+    /// Creates a view for each mip level of `texture`.
+    fn mip_views(texture: &wgpu::Texture, mip_level_count: u32) -> Vec<wgpu::TextureView> {
+        (0..mip_level_count)
+            .map(|level| {
+                texture.create_view(&wgpu::TextureViewDescriptor {
+                    label: Some("Image mip level"),
+                    base_mip_level: level,
+                    mip_level_count: std::num::NonZeroU32::new(1),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
     /// Writes data to an image.
     ///
     /// # Panics
     ///
     /// - if data overruns the size of the image.
     pub fn write(&self, data: &[u8], data_layout: ImageDataLayout, size: Extent3d) {
+        self.write_region(data, data_layout, 0, Origin3d::ZERO, size);
+    }
+
+    /// Writes data to an image, returning an error instead of panicking when the write
+    /// is invalid, see `Image::try_write_region()`.
+    pub fn try_write(
+        &self,
+        data: &[u8],
+        data_layout: ImageDataLayout,
+        size: Extent3d,
+    ) -> Result<(), WriteError> {
+        self.try_write_region(data, data_layout, 0, Origin3d::ZERO, size)
+    }
+
+    /// Writes data to the region of the mip level `mip_level` that starts at `origin`
+    /// and has `size`, the z coordinate of `origin` selects the first depth layer.
+    ///
+    /// Useful to upload pre-computed mip chains or individual layers of an image.
+    ///
+    /// # Panics
+    ///
+    /// - if `mip_level` is not less than the mip level count of the image.
+    /// - if the region exceeds the size of the mip level (see `Image::mip_level_size()`).
+    /// - if data overruns the size of the region.
+    pub fn write_region(
+        &self,
+        data: &[u8],
+        data_layout: ImageDataLayout,
+        mip_level: u32,
+        origin: Origin3d,
+        size: Extent3d,
+    ) {
+        self.try_write_region(data, data_layout, mip_level, origin, size)
+            .unwrap_or_else(|error| panic!("{error}"));
+    }
+
+    /// Writes data to a region of a mip level like `Image::write_region()`, returning an
+    /// error instead of panicking when the write is invalid, in which case nothing is written.
+    ///
+    /// # Panics
+    ///
+    /// - if the image is multisampled.
+    pub fn try_write_region(
+        &self,
+        data: &[u8],
+        data_layout: ImageDataLayout,
+        mip_level: u32,
+        origin: Origin3d,
+        size: Extent3d,
+    ) -> Result<(), WriteError> {
+        self.assert_single_sampled();
+
+        if mip_level >= self.mip_level_count {
+            return Err(WriteError::MipLevel {
+                mip_level,
+                mip_level_count: self.mip_level_count,
+            });
+        }
+
+        let level_size = self.mip_level_size(mip_level);
+        if origin.x as u64 + size.width as u64 > level_size.width as u64
+            || origin.y as u64 + size.height as u64 > level_size.height as u64
+            || origin.z as u64 + size.depth_or_array_layers as u64
+                > level_size.depth_or_array_layers as u64
+        {
+            return Err(WriteError::RegionOutOfBounds {
+                origin,
+                size,
+                level_size,
+            });
+        }
+
+        let required = self.required_data_size(data_layout, size)?;
+        if (data.len() as u64) < required {
+            return Err(WriteError::DataOverrun {
+                len: data.len() as u64,
+                required,
+            });
+        }
+
         self.device.queue.write_texture(
             wgpu::ImageCopyTexture {
                 texture: &self.texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
+                mip_level,
+                origin,
                 aspect: wgpu::TextureAspect::All,
             },
             data,
             data_layout,
             size,
         );
+
+        Ok(())
     }
 
-    /// Reads an image to a Vec of bytes.
-    pub fn read_to_vec(&self) -> Vec<u8> {
-        // KUDOS to @redwarp I struggled to much trying to copy a texture into a buffer
-        // https://github.com/redwarp/blog/tree/main/code-sample/image-filters
-        let bytes_per_pixel = self.format.describe().block_size as usize;
+    /// Number of bytes that a write of `size` with `layout` reads from its data.
+    fn required_data_size(
+        &self,
+        layout: ImageDataLayout,
+        size: Extent3d,
+    ) -> Result<wgpu::BufferAddress, WriteError> {
+        let info = self.format.describe();
+        let (block_width, block_height) = info.block_dimensions;
+        let (block_width, block_height) = (block_width as u64, block_height as u64);
 
-        let Extent3d { width, height, .. } = self.size;
+        let width = size.width as u64;
+        let height = size.height as u64;
+        let depth = size.depth_or_array_layers as u64;
+
+        if width % block_width != 0 || height % block_height != 0 {
+            return Err(WriteError::DataLayout(format!(
+                "the size {size:?} is not a multiple of the {block_width}x{block_height} blocks of {:?}",
+                self.format,
+            )));
+        }
+
+        let bytes_in_row = info.block_size as u64 * (width / block_width);
+        let block_rows = height / block_height;
+
+        let bytes_per_row = match layout.bytes_per_row {
+            Some(bytes_per_row) if (bytes_per_row.get() as u64) < bytes_in_row => {
+                return Err(WriteError::DataLayout(format!(
+                    "bytes per row must be at least {bytes_in_row}, got {bytes_per_row}"
+                )));
+            }
+            Some(bytes_per_row) => bytes_per_row.get() as u64,
+            None if block_rows > 1 || depth > 1 => {
+                return Err(WriteError::DataLayout(
+                    "bytes per row must be given for writes of more than one row".to_owned(),
+                ));
+            }
+            None => bytes_in_row,
+        };
 
-        let padded_bytes_per_row = {
-            let bytes_per_row = bytes_per_pixel * width as usize;
-            let padding = (256 - bytes_per_row % 256) % 256;
-            bytes_per_row + padding
+        let rows_per_image = match layout.rows_per_image {
+            Some(rows_per_image) if (rows_per_image.get() as u64) < block_rows => {
+                return Err(WriteError::DataLayout(format!(
+                    "rows per image must be at least {block_rows}, got {rows_per_image}"
+                )));
+            }
+            Some(rows_per_image) => rows_per_image.get() as u64,
+            None if depth > 1 => {
+                return Err(WriteError::DataLayout(
+                    "rows per image must be given for writes of more than one layer".to_owned(),
+                ));
+            }
+            None => block_rows,
         };
 
-        let unpadded_bytes_per_row = bytes_per_pixel * width as usize;
+        if width == 0 || height == 0 || depth == 0 {
+            return Ok(0);
+        }
 
-        let output_buffer_size =
-            padded_bytes_per_row as u64 * height as u64 * std::mem::size_of::<u8>() as u64;
+        Ok(layout.offset
+            + bytes_per_row * rows_per_image * (depth - 1)
+            + bytes_per_row * (block_rows - 1)
+            + bytes_in_row)
+    }
 
+    /// Reads an image to a Vec of bytes.
+    ///
+    /// The rows of every depth layer are tightly packed one after the other and the
+    /// channels of every pixel are in the order of the format (i.e. bgra for
+    /// `ImageFormat::Bgra8Unorm`), see `Image::read_to_vec_as()` to choose the order.
+    ///
+    /// # Panics
+    ///
+    /// - if the image is multisampled, resolve it with `Image::resolve_to()` first.
+    pub fn read_to_vec(&self) -> Vec<u8> {
+        self.read_mip_to_vec(0)
+    }
+
+    /// Reads the mip `level` of an image to a Vec of bytes, the level has the size
+    /// returned by `Image::mip_level_size()`.
+    ///
+    /// The bytes are laid out just like the ones of `Image::read_to_vec()`.
+    ///
+    /// # Panics
+    ///
+    /// - if `level` is not less than the number of mip levels of the image.
+    /// - if the image is multisampled, resolve it with `Image::resolve_to()` first.
+    pub fn read_mip_to_vec(&self, level: u32) -> Vec<u8> {
+        self.assert_single_sampled();
+        assert!(
+            level < self.mip_level_count,
+            "mip level {level} is out of bounds, {} has {} levels",
+            self.describe(),
+            self.mip_level_count,
+        );
+
+        let size = self.mip_level_size(level);
+
+        // KUDOS to @redwarp I struggled to much trying to copy a texture into a buffer
+        // https://github.com/redwarp/blog/tree/main/code-sample/image-filters
+        let padded_bytes_per_row = self.padded_bytes_per_row_of(size.width) as usize;
+        let unpadded_bytes_per_row =
+            self.format.describe().block_size as usize * size.width as usize;
+        let rows = size.height as usize * size.depth_or_array_layers as usize;
+
+        let label = self.label().map(|label| format!("{label} staging buffer"));
         let dst_buffer = self.device.handle.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Destination copy buffer"),
-            size: output_buffer_size,
+            label: Some(label.as_deref().unwrap_or("Destination copy buffer")),
+            size: (padded_bytes_per_row * rows) as wgpu::BufferAddress,
             usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
             mapped_at_creation: false,
         });
@@ -189,23 +888,7 @@ impl Image {
                     label: Some("Copy buffer command encoder"),
                 });
 
-        encoder.copy_texture_to_buffer(
-            wgpu::ImageCopyTexture {
-                texture: &self.texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            wgpu::ImageCopyBuffer {
-                buffer: &dst_buffer,
-                layout: wgpu::ImageDataLayout {
-                    offset: 0,
-                    bytes_per_row: std::num::NonZeroU32::new(padded_bytes_per_row as u32),
-                    rows_per_image: None,
-                },
-            },
-            self.size,
-        );
+        self.encode_copy_mip_to_buffer(&mut encoder, &dst_buffer, level);
 
         self.device.queue.submit(std::iter::once(encoder.finish()));
 
@@ -214,7 +897,7 @@ impl Image {
 
         self.device.handle.poll(wgpu::Maintain::Wait);
 
-        let mut pixels = vec![0; unpadded_bytes_per_row * height as usize];
+        let mut pixels = vec![0; unpadded_bytes_per_row * rows];
 
         dst_slice
             .get_mapped_range()
@@ -227,11 +910,342 @@ impl Image {
         pixels
     }
 
+    /// Reads an image to a Vec of bytes with its channels in `order`, swapping the red
+    /// and blue channels of every pixel if the image stores them the other way around.
+    ///
+    /// Useful to save bgra images (i.e. the format swapchains usually prefer) as rgba
+    /// without swapping the channels by hand.
+    ///
+    /// # Panics
+    ///
+    /// - if the format of the image doesn't have 4 channels (i.e. `Rg8Unorm`).
+    /// - for the same reasons as `Image::read_to_vec()`.
+    pub fn read_to_vec_as(&self, order: ChannelOrder) -> Vec<u8> {
+        let info = self.format.describe();
+        assert!(
+            info.components == 4 && info.block_dimensions == (1, 1),
+            "{:?} images don't have 4 channels to be read as {order:?}",
+            self.format,
+        );
+
+        let native_order = match self.format {
+            ImageFormat::Bgra8Unorm | ImageFormat::Bgra8UnormSrgb => ChannelOrder::Bgra,
+            _ => ChannelOrder::Rgba,
+        };
+
+        let mut pixels = self.read_to_vec();
+
+        if order != native_order {
+            let channel_size = info.block_size as usize / 4;
+
+            pixels
+                .chunks_exact_mut(info.block_size as usize)
+                .for_each(|pixel| {
+                    let (red, rest) = pixel.split_at_mut(channel_size);
+                    red.swap_with_slice(&mut rest[channel_size..2 * channel_size]);
+                });
+        }
+
+        pixels
+    }
+
+    /// Copies the image into `dst` on the device, without going through the CPU.
+    ///
+    /// Rows in the buffer are padded to `wgpu::COPY_BYTES_PER_ROW_ALIGNMENT` (256 bytes),
+    /// see `Image::padded_layout()` for the stride a kernel reading the buffer must use.
+    ///
+    /// # Panics
+    ///
+    /// - if `dst` is smaller than `Image::padded_size()` bytes.
+    /// - if `dst` was created without `BufferUsage::copy_dst`.
+    pub fn copy_to_buffer(&self, dst: &Buffer<u8>) {
+        let mut encoder =
+            self.device
+                .handle
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("Copy image command encoder"),
+                });
+
+        self.encode_copy_to_buffer_checked(&mut encoder, dst);
+
+        self.device.queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    /// Copies the pixels of `src` laid out with `layout` into the image on the device,
+    /// without going through the CPU (i.e. to sample what a kernel wrote to a buffer).
+    ///
+    /// `Image::padded_layout()` is the layout of a buffer with the same size as the image.
+    ///
+    /// # Panics
+    ///
+    /// - if `layout.bytes_per_row` is not a multiple of `wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`
+    ///   (256 bytes) or is smaller than a row of the image.
+    /// - if `src` is too small to hold the image with `layout`.
+    /// - if `src` was created without `BufferUsage::copy_src`.
+    pub fn write_from_buffer(&self, src: &Buffer<u8>, layout: ImageDataLayout) {
+        let mut encoder =
+            self.device
+                .handle
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("Copy image command encoder"),
+                });
+
+        self.encode_copy_from_buffer_checked(&mut encoder, src, layout);
+
+        self.device.queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    /// Layout of the image once it's copied into a buffer with `Image::copy_to_buffer()`,
+    /// each row takes `bytes_per_row` bytes and each depth layer `rows_per_image` rows.
+    pub fn padded_layout(&self) -> ImageDataLayout {
+        ImageDataLayout {
+            offset: 0,
+            bytes_per_row: std::num::NonZeroU32::new(self.padded_bytes_per_row()),
+            rows_per_image: std::num::NonZeroU32::new(self.size.height),
+        }
+    }
+
+    /// Size in bytes of the image once it's copied into a buffer with `Image::copy_to_buffer()`.
+    pub fn padded_size(&self) -> wgpu::BufferAddress {
+        let rows = self.size.height as wgpu::BufferAddress
+            * self.size.depth_or_array_layers as wgpu::BufferAddress;
+
+        self.padded_bytes_per_row() as wgpu::BufferAddress * rows
+    }
+
+    fn padded_bytes_per_row(&self) -> u32 {
+        self.padded_bytes_per_row_of(self.size.width)
+    }
+
+    /// Bytes per row of `width` pixels of the image padded for copies.
+    fn padded_bytes_per_row_of(&self, width: u32) -> u32 {
+        let bytes_per_row = self.format.describe().block_size as u32 * width;
+        bytes_per_row.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+    }
+
+    /// Records the copy of the image into `dst` after checking that it fits.
+    pub(crate) fn encode_copy_to_buffer_checked(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        dst: &Buffer<u8>,
+    ) {
+        dst.assert_copy_dst();
+        assert!(
+            dst.len() >= self.padded_size(),
+            "a buffer of {} bytes can't hold an image of {} padded bytes",
+            dst.len(),
+            self.padded_size(),
+        );
+
+        self.encode_copy_to_buffer(encoder, &dst.handle);
+    }
+
+    /// Records the copy of `src` into the image after checking `layout` and that the
+    /// buffer is large enough.
+    pub(crate) fn encode_copy_from_buffer_checked(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        src: &Buffer<u8>,
+        layout: ImageDataLayout,
+    ) {
+        self.assert_single_sampled();
+        src.assert_copy_src();
+
+        let Extent3d {
+            width,
+            height,
+            depth_or_array_layers,
+        } = self.size;
+
+        let unpadded_bytes_per_row = self.format.describe().block_size as u32 * width;
+        let bytes_per_row = layout
+            .bytes_per_row
+            .map_or(unpadded_bytes_per_row, |n| n.get());
+        let rows_per_image = layout.rows_per_image.map_or(height, |n| n.get());
+
+        assert!(
+            bytes_per_row % wgpu::COPY_BYTES_PER_ROW_ALIGNMENT == 0
+                && bytes_per_row >= unpadded_bytes_per_row,
+            "bytes per row must be a multiple of {} and at least {unpadded_bytes_per_row}, \
+             got {bytes_per_row}",
+            wgpu::COPY_BYTES_PER_ROW_ALIGNMENT,
+        );
+        assert!(
+            rows_per_image >= height,
+            "rows per image must be at least {height}, got {rows_per_image}"
+        );
+
+        let rows = rows_per_image as wgpu::BufferAddress * (depth_or_array_layers as u64 - 1)
+            + height as wgpu::BufferAddress;
+        let required_size = layout.offset
+            + bytes_per_row as wgpu::BufferAddress * (rows - 1)
+            + unpadded_bytes_per_row as wgpu::BufferAddress;
+
+        assert!(
+            src.len() >= required_size,
+            "a buffer of {} bytes is too small for the image, it needs {required_size} bytes",
+            src.len(),
+        );
+
+        encoder.copy_buffer_to_texture(
+            wgpu::ImageCopyBuffer {
+                buffer: &src.handle,
+                layout: ImageDataLayout {
+                    offset: layout.offset,
+                    bytes_per_row: std::num::NonZeroU32::new(bytes_per_row),
+                    rows_per_image: std::num::NonZeroU32::new(rows_per_image),
+                },
+            },
+            wgpu::ImageCopyTexture {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            self.size,
+        );
+    }
+
+    fn encode_copy_to_buffer(&self, encoder: &mut wgpu::CommandEncoder, dst: &wgpu::Buffer) {
+        self.encode_copy_mip_to_buffer(encoder, dst, 0);
+    }
+
+    /// Records the copy of the mip `level` into `dst` with its rows padded for copies.
+    fn encode_copy_mip_to_buffer(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        dst: &wgpu::Buffer,
+        level: u32,
+    ) {
+        self.assert_single_sampled();
+
+        let size = self.mip_level_size(level);
+
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &self.texture,
+                mip_level: level,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: dst,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: std::num::NonZeroU32::new(
+                        self.padded_bytes_per_row_of(size.width),
+                    ),
+                    rows_per_image: std::num::NonZeroU32::new(size.height),
+                },
+            },
+            size,
+        );
+    }
+
+    /// Names the image (i.e. after its role in the current pass) in the messages of hac
+    /// and in the staging buffers it's read back through, replacing the previous label.
+    ///
+    /// # Note
+    ///
+    /// wgpu 0.14 can't relabel a texture after its creation, so the label isn't
+    /// visible in captures of the image itself.
+    pub fn set_label(&self, label: &str) {
+        *self.label.lock().unwrap() = Some(label.to_owned());
+    }
+
+    /// Label set with `Image::set_label()`.
+    pub fn label(&self) -> Option<String> {
+        self.label.lock().unwrap().clone()
+    }
+
+    /// Refers to the image by its label (if it has one) in messages.
+    fn describe(&self) -> String {
+        match self.label() {
+            Some(label) => format!("the image {label:?}"),
+            None => "the image".to_owned(),
+        }
+    }
+
+    fn assert_single_sampled(&self) {
+        assert!(
+            self.sample_count == 1,
+            "multisampled images can't be copied, resolve them first"
+        );
+    }
+
+    /// Resolves the samples of every pixel of this multisampled image into `dst`.
+    ///
+    /// # Panics
+    ///
+    /// - if this image is not multisampled or `dst` is.
+    /// - if the images don't have the same size and format.
+    /// - if the format can't be resolved.
+    pub fn resolve_to(&self, dst: &Image) {
+        assert!(
+            self.sample_count > 1 && dst.sample_count == 1,
+            "only a multisampled image can be resolved into a single sampled one"
+        );
+        assert!(
+            self.size == dst.size && self.format == dst.format,
+            "a {:?} {:?} image can't be resolved into a {:?} {:?} one",
+            self.size,
+            self.format,
+            dst.size,
+            dst.format,
+        );
+        assert!(
+            self.device
+                .format_features(self.format)
+                .flags
+                .contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE),
+            "{:?} images can't be resolved",
+            self.format,
+        );
+
+        let mut encoder =
+            self.device
+                .handle
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("Resolve command encoder"),
+                });
+
+        // an empty render pass that loads and stores the samples is enough to resolve them
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Resolve pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &self.view,
+                resolve_target: Some(&dst.mip_views[0]),
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: None,
+        });
+
+        self.device.queue.submit(std::iter::once(encoder.finish()));
+    }
+
     /// Size of the image.
     pub fn size(&self) -> Extent3d {
         self.size
     }
 
+    /// Width of the image in pixels.
+    pub fn width(&self) -> u32 {
+        self.size.width
+    }
+
+    /// Height of the image in pixels.
+    pub fn height(&self) -> u32 {
+        self.size.height
+    }
+
+    /// Depth of the image in pixels, 1 for 2D images.
+    pub fn depth(&self) -> u32 {
+        self.size.depth_or_array_layers
+    }
+
     /// Format of the image.
     pub fn format(&self) -> ImageFormat {
         self.format
@@ -242,6 +1256,39 @@ impl Image {
         self.dimension
     }
 
+    /// Number of mip levels of the image.
+    pub fn mip_level_count(&self) -> u32 {
+        self.mip_level_count
+    }
+
+    /// Number of samples per pixel of the image.
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// Size of the mip level `level` of the image.
+    ///
+    /// Each level halves the previous one (rounding down) up to a minimum of 1.
+    pub fn mip_level_size(&self, level: u32) -> Extent3d {
+        let Extent3d {
+            width,
+            height,
+            depth_or_array_layers,
+        } = self.size;
+
+        let depth_or_array_layers = if self.dimension == ImageDimension::D3 {
+            (depth_or_array_layers >> level).max(1)
+        } else {
+            depth_or_array_layers
+        };
+
+        Extent3d {
+            width: (width >> level).max(1),
+            height: (height >> level).max(1),
+            depth_or_array_layers,
+        }
+    }
+
     #[cfg(feature = "from_image")]
     /// Creates an image from an Rgba8 image buffer.
     ///
@@ -251,56 +1298,154 @@ impl Image {
         image: &image::RgbaImage,
         sample_type: ImageSampleType,
     ) -> Self {
-        let (width, height) = image.dimensions();
-        let size = Extent3d {
-            width,
-            height,
-            depth_or_array_layers: 1,
-        };
-
         let format = match sample_type {
             ImageSampleType::Float { .. } => ImageFormat::Rgba8Unorm,
             ImageSampleType::Uint => ImageFormat::Rgba8Uint,
             ImageSampleType::Sint => ImageFormat::Rgba8Sint,
         };
 
-        let dimension = ImageDimension::D2;
+        let (width, height) = image.dimensions();
+        Self::from_pixels(context, image, width, height, format)
+    }
 
-        let texture = context
-            .device
-            .handle
-            .create_texture(&wgpu::TextureDescriptor {
-                label: Some("texture"),
-                usage: Self::USAGES,
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension,
-                format,
-                size,
-            });
+    #[cfg(feature = "from_image")]
+    /// Creates an `Rgba8UnormSrgb` image from an Rgba8 image buffer, the colors are
+    /// converted to linear when they're read in the kernel.
+    ///
+    /// The image is sampled as a filterable float image.
+    ///
+    /// # Note
+    ///
+    /// sRGB formats can't be used as storage images, write the results in an
+    /// `Rgba8Unorm` image (or a higher precision one) instead.
+    pub fn from_srgba8_image(context: &Context, image: &image::RgbaImage) -> Self {
+        let (width, height) = image.dimensions();
+        Self::from_pixels(context, image, width, height, ImageFormat::Rgba8UnormSrgb)
+    }
 
-        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    #[cfg(feature = "from_image")]
+    /// Creates a single channel image from a Luma8 image buffer.
+    ///
+    /// The `sample_type` parameter is used to choose the correct image format
+    /// (`R8Unorm`, `R8Uint` or `R8Sint`).
+    ///
+    /// # Note
+    ///
+    /// Single channel 8 bit formats can't be used as storage images.
+    pub fn from_luma8_image(
+        context: &Context,
+        image: &image::GrayImage,
+        sample_type: ImageSampleType,
+    ) -> Self {
+        let format = match sample_type {
+            ImageSampleType::Float { .. } => ImageFormat::R8Unorm,
+            ImageSampleType::Uint => ImageFormat::R8Uint,
+            ImageSampleType::Sint => ImageFormat::R8Sint,
+        };
 
-        let self_ = Self {
-            size,
-            view,
-            format,
-            texture,
-            dimension,
-            device: Arc::clone(&context.device),
+        let (width, height) = image.dimensions();
+        Self::from_pixels(context, image, width, height, format)
+    }
+
+    #[cfg(feature = "from_image")]
+    /// Creates a two channel image from a LumaA8 image buffer, the luma is stored in
+    /// the red channel and the alpha in the green channel.
+    ///
+    /// The `sample_type` parameter is used to choose the correct image format
+    /// (`Rg8Unorm`, `Rg8Uint` or `Rg8Sint`).
+    ///
+    /// # Note
+    ///
+    /// Two channel 8 bit formats can't be used as storage images.
+    pub fn from_la8_image(
+        context: &Context,
+        image: &image::GrayAlphaImage,
+        sample_type: ImageSampleType,
+    ) -> Self {
+        let format = match sample_type {
+            ImageSampleType::Float { .. } => ImageFormat::Rg8Unorm,
+            ImageSampleType::Uint => ImageFormat::Rg8Uint,
+            ImageSampleType::Sint => ImageFormat::Rg8Sint,
+        };
+
+        let (width, height) = image.dimensions();
+        Self::from_pixels(context, image, width, height, format)
+    }
+
+    #[cfg(feature = "from_image")]
+    /// Creates a 2D image with `format` initialized with tightly packed `pixels`.
+    fn from_pixels(
+        context: &Context,
+        pixels: &[u8],
+        width: u32,
+        height: u32,
+        format: ImageFormat,
+    ) -> Self {
+        let bytes_per_pixel = format.describe().block_size as u32;
+        let layout = ImageDataLayout {
+            offset: 0,
+            bytes_per_row: std::num::NonZeroU32::new(width * bytes_per_pixel),
+            rows_per_image: None,
         };
 
-        let bytes_per_pixel = 4;
-        self_.write(
-            image,
-            ImageDataLayout {
-                offset: 0,
-                bytes_per_row: std::num::NonZeroU32::new(width * bytes_per_pixel),
-                rows_per_image: None,
+        Self::from_raw(
+            context,
+            pixels,
+            layout,
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
             },
-            size,
+            format,
+        )
+    }
+
+    #[cfg(feature = "from_image")]
+    /// Reads the image into an Rgba8 image buffer.
+    ///
+    /// The channels of the image are rearranged as rgba: bgra images get their red and blue
+    /// channels swapped, single channel images are read as gray and two channel images are
+    /// read as red and green with the missing channels set to 0 (and alpha to 255).
+    ///
+    /// # Panics
+    ///
+    /// - if the format of the image doesn't have 8 bits per channel.
+    /// - if the image is 3D.
+    pub fn to_rgba8_image(&self) -> image::RgbaImage {
+        assert_eq!(
+            self.dimension,
+            ImageDimension::D2,
+            "only 2D images can be converted to an RgbaImage"
         );
 
-        self_
+        let to_rgba: fn(&[u8]) -> [u8; 4] = match self.format {
+            ImageFormat::Rgba8Unorm
+            | ImageFormat::Rgba8UnormSrgb
+            | ImageFormat::Rgba8Snorm
+            | ImageFormat::Rgba8Uint
+            | ImageFormat::Rgba8Sint => |p| [p[0], p[1], p[2], p[3]],
+            ImageFormat::Bgra8Unorm | ImageFormat::Bgra8UnormSrgb => |p| [p[2], p[1], p[0], p[3]],
+            ImageFormat::Rg8Unorm
+            | ImageFormat::Rg8Snorm
+            | ImageFormat::Rg8Uint
+            | ImageFormat::Rg8Sint => |p| [p[0], p[1], 0, 255],
+            ImageFormat::R8Unorm
+            | ImageFormat::R8Snorm
+            | ImageFormat::R8Uint
+            | ImageFormat::R8Sint => |p| [p[0], p[0], p[0], 255],
+            format => panic!("{format:?} images can't be converted to an RgbaImage"),
+        };
+
+        let bytes_per_pixel = self.format.describe().block_size as usize;
+
+        let pixels = self
+            .read_to_vec()
+            .chunks_exact(bytes_per_pixel)
+            .flat_map(to_rgba)
+            .collect();
+
+        let Extent3d { width, height, .. } = self.size;
+        image::RgbaImage::from_raw(width, height, pixels).unwrap()
     }
 }
@@ -1,18 +1,48 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    fmt,
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
 
-use crate::{BindGroup, CommandQueue, Context, Range};
+use bytemuck::Pod;
+
+use crate::{
+    bench, reflection::Reflection, BenchResult, BindGroup, Buffer, CommandQueue, Context, Error,
+    Image, LayoutReport, Range, StructLayout,
+};
+
+/// Identifies the programs in the kernel cache.
+static NEXT_PROGRAM_ID: AtomicU64 = AtomicU64::new(0);
 
 /// Wrapper of a `wgpu::ShaderModule`.
 #[derive(Debug)]
-pub struct Program(wgpu::ShaderModule);
+pub struct Program {
+    module: wgpu::ShaderModule,
+    id: u64,
+    reflection: Option<Reflection>,
+}
 
 impl Program {
     /// Creates a Program from a `wgpu::ShaderSource`.
     ///
     /// [`Context`] provides more ergonomic methods for creating a program
     /// (i.e `Context::program_from_wgsl()`).
+    ///
+    /// # Note
+    ///
+    /// wgsl sources are parsed and validated twice, once by hac to reflect the bindings
+    /// that the program expects and once more by wgpu when creating the shader module.
     pub fn from_source(context: &Context, source: wgpu::ShaderSource) -> Self {
-        let shader = context
+        let reflection = match &source {
+            wgpu::ShaderSource::Wgsl(code) => Reflection::from_wgsl(code),
+            _ => None,
+        };
+
+        let module = context
             .device
             .handle
             .create_shader_module(wgpu::ShaderModuleDescriptor {
@@ -20,10 +50,198 @@ impl Program {
                 source,
             });
 
-        Self(shader)
+        Self {
+            module,
+            id: NEXT_PROGRAM_ID.fetch_add(1, Ordering::Relaxed),
+            reflection,
+        }
+    }
+
+    /// Creates a Program from wgsl source code, returning an error that describes where
+    /// the source is invalid instead of panicking.
+    ///
+    /// Meant for sources that aren't known ahead of time (i.e. written in an editor).
+    pub fn try_from_wgsl(context: &Context, source: &str) -> Result<Self, ShaderError> {
+        let reflection = Reflection::try_from_wgsl(source)?;
+
+        let module = context
+            .device
+            .capture_errors(|| {
+                context
+                    .device
+                    .handle
+                    .create_shader_module(wgpu::ShaderModuleDescriptor {
+                        label: Some("Shader"),
+                        source: wgpu::ShaderSource::Wgsl(source.into()),
+                    })
+            })
+            .map_err(|error| ShaderError {
+                message: error.to_string(),
+                location: None,
+            })?;
+
+        Ok(Self {
+            module,
+            id: NEXT_PROGRAM_ID.fetch_add(1, Ordering::Relaxed),
+            reflection: Some(reflection),
+        })
+    }
+
+    /// Recompiles the program in place from `source` (i.e. when its file changes on disk),
+    /// the program is left untouched if the source is invalid.
+    ///
+    /// Kernels created from the program keep using the previous version until they're
+    /// rebuilt with `Kernel::rebuild()`. The pipelines of the previous version are
+    /// dropped from the kernel cache of `context`, see `Kernel::cached()`.
+    pub fn reload_from_wgsl(&mut self, context: &Context, source: &str) -> Result<(), ShaderError> {
+        let previous = std::mem::replace(self, Self::try_from_wgsl(context, source)?);
+        context.kernel_cache.remove_program(previous.id);
+        Ok(())
+    }
+
+    /// Bytes of the `var<push_constant>` used by `entry_point`, `None` if the entry point
+    /// doesn't use push constants (or doesn't exist) or the program wasn't created from wgsl.
+    ///
+    /// The `push_constants_range` of the kernels of the entry point must cover `0..size`.
+    pub fn push_constant_size(&self, entry_point: &str) -> Option<u32> {
+        self.reflection
+            .as_ref()
+            .and_then(|reflection| reflection.push_constant_size(entry_point))
+    }
+
+    /// Layout of the struct called `name` declared in the program, `None` if it isn't
+    /// declared or the program wasn't created from wgsl.
+    ///
+    /// See `Buffer::from_slice_checked()`.
+    pub fn struct_layout(&self, name: &str) -> Option<StructLayout> {
+        self.reflection
+            .as_ref()
+            .and_then(|reflection| reflection.struct_layout(name))
+    }
+
+    /// Creates a Program from the wgsl source at `entry_path` expanding its
+    /// `#include "path"` lines, `resolver` returns the source of every path.
+    ///
+    /// Each source is included only once, so shared files can be included from
+    /// several others without redefining their items.
+    ///
+    /// # Note
+    ///
+    /// Paths are passed to `resolver` as written, they aren't relative to the file
+    /// that includes them. The expansion shifts the lines of the source, so the lines
+    /// reported by compilation errors refer to the expanded source.
+    ///
+    /// # Panics
+    ///
+    /// - if the includes are circular or an `#include` line is malformed.
+    /// - if the expanded source is invalid.
+    pub fn from_wgsl_with_includes(
+        context: &Context,
+        entry_path: &str,
+        resolver: impl Fn(&str) -> String,
+    ) -> Self {
+        let mut source = String::new();
+        expand_includes(
+            entry_path,
+            &resolver,
+            &mut Vec::new(),
+            &mut Vec::new(),
+            &mut source,
+        );
+
+        Self::from_source(context, wgpu::ShaderSource::Wgsl(source.into()))
     }
 }
 
+/// Appends the source at `path` to `output` with its includes expanded. `stack` holds
+/// the files being expanded to detect cycles and `included` the ones already expanded.
+fn expand_includes(
+    path: &str,
+    resolver: &impl Fn(&str) -> String,
+    stack: &mut Vec<String>,
+    included: &mut Vec<String>,
+    output: &mut String,
+) {
+    assert!(
+        !stack.iter().any(|file| file == path),
+        "circular #include of \"{path}\" (included from {})",
+        stack.join(" -> "),
+    );
+
+    if included.iter().any(|file| file == path) {
+        return;
+    }
+
+    stack.push(path.to_string());
+    included.push(path.to_string());
+
+    for line in resolver(path).lines() {
+        match line.trim().strip_prefix("#include") {
+            Some(rest) => {
+                let include = rest
+                    .trim()
+                    .strip_prefix('"')
+                    .and_then(|rest| rest.strip_suffix('"'))
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "malformed include in \"{path}\": {line}, expected #include \"path\""
+                        )
+                    });
+
+                expand_includes(include, resolver, stack, included, output);
+            }
+            None => {
+                output.push_str(line);
+                output.push('\n');
+            }
+        }
+    }
+
+    stack.pop();
+}
+
+/// Position of an error in the source of a program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceLocation {
+    /// 1-based line of the error.
+    pub line: u32,
+    /// 1-based column where the error starts.
+    pub column: u32,
+    /// Offset in bytes of the start of the error in the source.
+    pub offset: u32,
+    /// Length in bytes of the erroneous part of the source.
+    pub length: u32,
+}
+
+impl From<naga::SourceLocation> for SourceLocation {
+    fn from(location: naga::SourceLocation) -> Self {
+        Self {
+            line: location.line_number,
+            column: location.line_position,
+            offset: location.offset,
+            length: location.length,
+        }
+    }
+}
+
+/// Error of a program that failed to compile, see `Program::try_from_wgsl()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderError {
+    /// Description of the error, with the offending source highlighted when it's known.
+    pub message: String,
+
+    /// Where the error is in the source, if it's known.
+    pub location: Option<SourceLocation>,
+}
+
+impl fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ShaderError {}
+
 /// Provides the info required to execute a kernel.
 #[derive(Debug)]
 pub struct KernelInfo<'a> {
@@ -37,36 +255,296 @@ pub struct KernelInfo<'a> {
     pub bind_groups: &'a [&'a BindGroup],
 
     /// Range of a small data that can be cheaply changed on every kernel dispatch.
+    ///
+    /// With `None` the range of wgsl programs is `0..Program::push_constant_size()` of the
+    /// entry point (no range if it doesn't use push constants), set it to reserve a larger
+    /// range or for programs that weren't created from wgsl.
     pub push_constants_range: Option<std::ops::Range<u32>>,
 }
 
+impl KernelInfo<'_> {
+    /// Info with the push constants range reflected from the program if it wasn't set.
+    fn with_reflected_push_constants(&self) -> Self {
+        Self {
+            push_constants_range: self.push_constants_range.clone().or_else(|| {
+                self.program
+                    .push_constant_size(self.entry_point)
+                    .map(|size| 0..size)
+            }),
+            ..*self
+        }
+    }
+}
+
+/// Conventional push constants offset of the image size written by
+/// `Kernel::dispatch_over_image_with_size()`, right after the base workgroup at
+/// [`crate::BASE_WORKGROUP_OFFSET`] so a kernel can use both.
+pub const IMAGE_SIZE_OFFSET: u32 = 16;
+
 /// Program that executes on the device.
 #[derive(Debug)]
 pub struct Kernel {
     pub(crate) device: Arc<crate::Device>,
-    pub(crate) pipeline: wgpu::ComputePipeline,
-    pub(crate) bind_groups: Vec<Arc<wgpu::BindGroup>>,
+    pub(crate) pipeline: Arc<wgpu::ComputePipeline>,
+    pub(crate) bind_groups: Vec<(Arc<wgpu::BindGroup>, Vec<u32>)>,
+    layouts: Vec<Vec<wgpu::BindGroupLayoutEntry>>,
+    bind_group_layouts: Vec<Arc<wgpu::BindGroupLayout>>,
+    entry_point: String,
+    pub(crate) push_constants_range: Option<std::ops::Range<u32>>,
+    layout_report: Option<LayoutReport>,
+    workgroup_size: Option<[u32; 3]>,
+    workgroup_storage_size: Option<u32>,
 }
 
 impl Kernel {
     /// Creates a kernel.
+    ///
+    /// # Panics
+    ///
+    /// - if the bind groups don't match the bindings used by the entry point of a
+    ///   wgsl program, the message describes the first mismatch.
+    /// - if the `var<workgroup>` memory of a wgsl program exceeds the
+    ///   `max_compute_workgroup_storage_size` limit.
+    /// - if the `@workgroup_size` of a wgsl program exceeds the `max_compute_workgroup_size_*`
+    ///   or `max_compute_invocations_per_workgroup` limits.
+    /// - if the `push_constants_range` doesn't cover the `var<push_constant>` of a wgsl
+    ///   program (see `Program::push_constant_size()`).
+    /// - if wgpu fails to create the compute pipeline.
     pub fn new(context: &Context, info: &KernelInfo) -> Self {
-        let device = Arc::clone(&context.device);
+        let info = &info.with_reflected_push_constants();
+        let layout_report =
+            Self::checked_layout_report(context, info).unwrap_or_else(|e| panic!("{e}"));
+        let pipeline = Arc::new(Self::create_pipeline(context, info));
 
-        let num_entries = info.bind_groups.len();
+        Self::from_pipeline(context, info, pipeline, layout_report)
+    }
 
-        let mut layouts = Vec::with_capacity(num_entries);
-        let mut bind_groups = Vec::with_capacity(num_entries);
+    /// Creates a kernel reusing the pipeline of a previously created kernel with
+    /// the same program, entry point, bind group layouts and push constants range.
+    ///
+    /// Building the pipeline is the expensive part of creating a kernel, so this is
+    /// useful when kernels are created repeatedly (i.e. every frame or iteration).
+    ///
+    /// # Note
+    ///
+    /// The cache keeps up to [`KERNEL_CACHE_CAPACITY`] pipelines, dropping the least
+    /// recently used one to make room for a new one. The cached pipelines live as long
+    /// as the [`Context`] (and its clones), see `Context::clear_kernel_cache()`.
+    pub fn cached(context: &Context, info: &KernelInfo) -> Self {
+        let info = &info.with_reflected_push_constants();
+        let layout_report =
+            Self::checked_layout_report(context, info).unwrap_or_else(|e| panic!("{e}"));
 
-        info.bind_groups.iter().for_each(|bind_group| {
-            layouts.push(&bind_group.layout);
-            bind_groups.push(Arc::clone(&bind_group.handle));
-        });
+        let key = KernelKey {
+            program: info.program.id,
+            entry_point: info.entry_point.to_owned(),
+            layouts: info
+                .bind_groups
+                .iter()
+                .map(|bind_group| bind_group.layout_entries.clone())
+                .collect(),
+            push_constants_range: info.push_constants_range.clone(),
+        };
+
+        let pipeline = context
+            .kernel_cache
+            .get_or_insert_with(key, || Self::create_pipeline(context, info));
+
+        Self::from_pipeline(context, info, pipeline, layout_report)
+    }
+
+    /// Reflects the layout of the program (if it was created from wgsl) and checks
+    /// the bind groups and the workgroup memory against it.
+    fn checked_layout_report(
+        context: &Context,
+        info: &KernelInfo,
+    ) -> Result<Option<LayoutReport>, Error> {
+        Self::check_workgroup_storage(context, info.program, info.entry_point)?;
+        Self::check_workgroup_size(context, info.program, info.entry_point)?;
+        Self::check_push_constants(
+            info.program,
+            info.entry_point,
+            info.push_constants_range.as_ref(),
+        )?;
+
+        let report = info
+            .program
+            .reflection
+            .as_ref()
+            .and_then(|reflection| reflection.layout_report(info.entry_point));
+
+        if let Some(Err(mismatch)) = report.as_ref().map(|r| r.validate(info.bind_groups)) {
+            return Err(Error::Validation {
+                description: mismatch.to_string(),
+                source: Box::new(mismatch),
+            });
+        }
+
+        Ok(report)
+    }
+
+    /// Checks the `var<workgroup>` memory of the entry point against the device limit.
+    fn check_workgroup_storage(
+        context: &Context,
+        program: &Program,
+        entry_point: &str,
+    ) -> Result<(), Error> {
+        let size = program
+            .reflection
+            .as_ref()
+            .and_then(|reflection| reflection.workgroup_storage_size(entry_point));
+        let limit = context
+            .device
+            .handle
+            .limits()
+            .max_compute_workgroup_storage_size;
+
+        match size {
+            Some(size) if size > limit => {
+                let error = WorkgroupStorageExceeded { size, limit };
+                Err(Error::Validation {
+                    description: error.to_string(),
+                    source: Box::new(error),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks the `@workgroup_size` of the entry point against the device limits.
+    fn check_workgroup_size(
+        context: &Context,
+        program: &Program,
+        entry_point: &str,
+    ) -> Result<(), Error> {
+        let Some(size) = program
+            .reflection
+            .as_ref()
+            .and_then(|reflection| reflection.workgroup_size(entry_point))
+        else {
+            return Ok(());
+        };
+
+        let limits = context.device.handle.limits();
+        let max_size = [
+            limits.max_compute_workgroup_size_x,
+            limits.max_compute_workgroup_size_y,
+            limits.max_compute_workgroup_size_z,
+        ];
+        let invocations = size.iter().map(|&n| n as u64).product::<u64>();
+
+        if size.iter().zip(max_size).all(|(&n, max)| n <= max)
+            && invocations <= limits.max_compute_invocations_per_workgroup as u64
+        {
+            return Ok(());
+        }
+
+        let error = WorkgroupSizeExceeded {
+            size,
+            max_size,
+            max_invocations: limits.max_compute_invocations_per_workgroup,
+        };
+        Err(Error::Validation {
+            description: error.to_string(),
+            source: Box::new(error),
+        })
+    }
+
+    /// Checks that `range` covers the `var<push_constant>` used by the entry point.
+    fn check_push_constants(
+        program: &Program,
+        entry_point: &str,
+        range: Option<&std::ops::Range<u32>>,
+    ) -> Result<(), Error> {
+        match program.push_constant_size(entry_point) {
+            Some(size) if !range.is_some_and(|range| range.start == 0 && size <= range.end) => {
+                let error = PushConstantsMismatch {
+                    size,
+                    range: range.cloned(),
+                };
+                Err(Error::Validation {
+                    description: error.to_string(),
+                    source: Box::new(error),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn from_pipeline(
+        context: &Context,
+        info: &KernelInfo,
+        pipeline: Arc<wgpu::ComputePipeline>,
+        layout_report: Option<LayoutReport>,
+    ) -> Self {
+        Self {
+            device: Arc::clone(&context.device),
+            pipeline,
+            bind_groups: info
+                .bind_groups
+                .iter()
+                .map(|bind_group| {
+                    (
+                        Arc::clone(&bind_group.handle),
+                        bind_group.default_offsets.clone(),
+                    )
+                })
+                .collect(),
+            layouts: info
+                .bind_groups
+                .iter()
+                .map(|bind_group| bind_group.layout_entries.clone())
+                .collect(),
+            bind_group_layouts: info
+                .bind_groups
+                .iter()
+                .map(|bind_group| Arc::clone(&bind_group.layout))
+                .collect(),
+            entry_point: info.entry_point.to_owned(),
+            push_constants_range: info.push_constants_range.clone(),
+            layout_report,
+            workgroup_size: info
+                .program
+                .reflection
+                .as_ref()
+                .and_then(|reflection| reflection.workgroup_size(info.entry_point)),
+            workgroup_storage_size: info
+                .program
+                .reflection
+                .as_ref()
+                .and_then(|reflection| reflection.workgroup_storage_size(info.entry_point)),
+        }
+    }
 
-        let is_some = info.push_constants_range.is_some() as usize;
+    fn create_pipeline(context: &Context, info: &KernelInfo) -> wgpu::ComputePipeline {
+        let layouts: Vec<_> = info
+            .bind_groups
+            .iter()
+            .map(|bind_group| bind_group.layout.as_ref())
+            .collect();
+
+        Self::create_pipeline_with_layouts(
+            context,
+            info.program,
+            info.entry_point,
+            &layouts,
+            info.push_constants_range.clone(),
+        )
+    }
+
+    fn create_pipeline_with_layouts(
+        context: &Context,
+        program: &Program,
+        entry_point: &str,
+        layouts: &[&wgpu::BindGroupLayout],
+        push_constants_range: Option<std::ops::Range<u32>>,
+    ) -> wgpu::ComputePipeline {
+        let device = &context.device;
+
+        let is_some = push_constants_range.is_some() as usize;
         let push_constant_ranges = &[wgpu::PushConstantRange {
             stages: wgpu::ShaderStages::COMPUTE,
-            range: info.push_constants_range.clone().unwrap_or(0..0),
+            range: push_constants_range.unwrap_or(0..0),
         }][0..is_some];
 
         let pipeline_layout =
@@ -74,24 +552,194 @@ impl Kernel {
                 .handle
                 .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                     label: Some("Pipeline layout"),
-                    bind_group_layouts: &layouts,
+                    bind_group_layouts: layouts,
                     push_constant_ranges,
                 });
 
-        let pipeline = device
+        device
             .handle
             .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
                 label: Some("Compute pipeline"),
                 layout: Some(&pipeline_layout),
-                module: &info.program.0,
-                entry_point: info.entry_point,
+                module: &program.module,
+                entry_point,
+            })
+    }
+
+    /// Creates a kernel, returning an error instead of panicking if the kernel is invalid
+    /// (i.e. the bind groups don't match the ones declared in the program).
+    pub fn try_new(context: &Context, info: &KernelInfo) -> Result<Self, Error> {
+        let info = &info.with_reflected_push_constants();
+        let layout_report = Self::checked_layout_report(context, info)?;
+
+        context.device.capture_errors(|| {
+            let pipeline = Arc::new(Self::create_pipeline(context, info));
+            Self::from_pipeline(context, info, pipeline, layout_report)
+        })
+    }
+
+    /// Rebuilds the pipeline of the kernel from `program` (i.e. after reloading it with
+    /// `Program::reload_from_wgsl()`) keeping its entry point, bind groups and push
+    /// constants range, so the bind groups don't have to be created again.
+    ///
+    /// The kernel is left untouched if the program doesn't match its bind groups or
+    /// the pipeline can't be created.
+    pub fn rebuild(&mut self, context: &Context, program: &Program) -> Result<(), Error> {
+        Self::check_workgroup_storage(context, program, &self.entry_point)?;
+        Self::check_workgroup_size(context, program, &self.entry_point)?;
+        Self::check_push_constants(
+            program,
+            &self.entry_point,
+            self.push_constants_range.as_ref(),
+        )?;
+
+        let layout_report = program
+            .reflection
+            .as_ref()
+            .and_then(|reflection| reflection.layout_report(&self.entry_point));
+
+        let layouts: Vec<_> = self.layouts.iter().map(Vec::as_slice).collect();
+        if let Some(Err(mismatch)) = layout_report
+            .as_ref()
+            .map(|report| report.validate_layouts(&layouts))
+        {
+            return Err(Error::Validation {
+                description: mismatch.to_string(),
+                source: Box::new(mismatch),
             });
+        }
 
-        Self {
-            device,
-            pipeline,
-            bind_groups,
+        let bind_group_layouts: Vec<_> = self
+            .bind_group_layouts
+            .iter()
+            .map(|layout| layout.as_ref())
+            .collect();
+
+        let pipeline = context.device.capture_errors(|| {
+            Self::create_pipeline_with_layouts(
+                context,
+                program,
+                &self.entry_point,
+                &bind_group_layouts,
+                self.push_constants_range.clone(),
+            )
+        })?;
+
+        self.pipeline = Arc::new(pipeline);
+        self.workgroup_size = program
+            .reflection
+            .as_ref()
+            .and_then(|reflection| reflection.workgroup_size(&self.entry_point));
+        self.workgroup_storage_size = program
+            .reflection
+            .as_ref()
+            .and_then(|reflection| reflection.workgroup_storage_size(&self.entry_point));
+        self.layout_report = layout_report;
+
+        Ok(())
+    }
+
+    /// Bindings that the entry point of the kernel expects, useful to find out
+    /// why a bind group doesn't match the program.
+    ///
+    /// Only available for programs created from wgsl.
+    pub fn expected_layout_report(&self) -> Option<&LayoutReport> {
+        self.layout_report.as_ref()
+    }
+
+    /// The `@workgroup_size` of the entry point of the kernel.
+    ///
+    /// Only available for programs created from wgsl.
+    pub fn workgroup_size(&self) -> Option<[u32; 3]> {
+        self.workgroup_size
+    }
+
+    /// Bytes of `var<workgroup>` memory used by the entry point of the kernel, counted
+    /// like the `max_compute_workgroup_storage_size` limit does (each variable is rounded
+    /// up to 16 bytes).
+    ///
+    /// Only available for programs created from wgsl.
+    pub fn workgroup_storage_size(&self) -> Option<u32> {
+        self.workgroup_storage_size
+    }
+
+    /// Entries of the layout of the bind group at `index` the kernel was created with.
+    pub(crate) fn bind_group_layout(&self, index: u32) -> Option<&[wgpu::BindGroupLayoutEntry]> {
+        self.layouts.get(index as usize).map(Vec::as_slice)
+    }
+
+    /// Workgroups needed to run one invocation per pixel of `image`, its width, height
+    /// and depth divided by the workgroup size of the kernel (rounding up).
+    ///
+    /// # Panics
+    ///
+    /// - if the program of the kernel wasn't created from wgsl.
+    pub fn workgroups_over_image(&self, image: &Image) -> Range {
+        let [x, y, z] = self
+            .workgroup_size
+            .expect("the workgroup size can only be reflected from wgsl programs");
+
+        Range::d3(
+            image.width().div_ceil(x),
+            image.height().div_ceil(y),
+            image.depth().div_ceil(z),
+        )
+    }
+
+    /// Checks that `bind_groups` can be set in the kernel and that `workgroups` can be
+    /// dispatched without running anything on the device.
+    ///
+    /// The bind groups must match the layouts of the ones the kernel was created with
+    /// (and the bindings of the program if it was created from wgsl) and no dimension
+    /// of `workgroups` can exceed the `max_compute_workgroups_per_dimension` limit.
+    pub fn validate(
+        &self,
+        bind_groups: &[&BindGroup],
+        workgroups: impl Into<Range>,
+    ) -> Result<(), ValidationError> {
+        if bind_groups.len() != self.layouts.len() {
+            return Err(ValidationError::BindGroupCount {
+                expected: self.layouts.len(),
+                found: bind_groups.len(),
+            });
+        }
+
+        if let Some(index) = bind_groups
+            .iter()
+            .zip(&self.layouts)
+            .position(|(bind_group, layout)| &bind_group.layout_entries != layout)
+        {
+            return Err(ValidationError::BindGroupLayout {
+                index: index as u32,
+            });
         }
+
+        if let Some(Err(mismatch)) = self
+            .layout_report
+            .as_ref()
+            .map(|report| report.validate(bind_groups))
+        {
+            return Err(ValidationError::Binding(mismatch.to_string()));
+        }
+
+        let workgroups = workgroups.into();
+        let max_per_dimension = self
+            .device
+            .handle
+            .limits()
+            .max_compute_workgroups_per_dimension;
+
+        if [workgroups.x, workgroups.y, workgroups.z]
+            .into_iter()
+            .any(|n| n > max_per_dimension)
+        {
+            return Err(ValidationError::Workgroups {
+                workgroups,
+                max_per_dimension,
+            });
+        }
+
+        Ok(())
     }
 
     /// Executes a kernel.
@@ -100,7 +748,7 @@ impl Kernel {
     /// binding things like push constants.
     ///
     /// If that's not the intention then check [`CommandQueue`].
-    pub fn dispatch(&self, workgroups: Range) {
+    pub fn dispatch(&self, workgroups: impl Into<Range>) {
         let command_queue = CommandQueue {
             device: Arc::clone(&self.device),
             cmd_queue: std::collections::VecDeque::new(),
@@ -111,4 +759,296 @@ impl Kernel {
             .enqueue_dispatch(workgroups)
             .execute();
     }
+
+    /// Executes a kernel with one invocation per pixel of `image` (see
+    /// `Kernel::workgroups_over_image()`), the excess invocations of the workgroups
+    /// at the edges must be discarded by the kernel.
+    ///
+    /// # Panics
+    ///
+    /// - if the program of the kernel wasn't created from wgsl.
+    ///
+    /// # Example wgsl syntax
+    /// ```cpp,ignore
+    /// @compute @workgroup_size(8, 8)
+    /// fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    ///     let size = textureDimensions(input);
+    ///     if id.x >= u32(size.x) || id.y >= u32(size.y) {
+    ///         return;
+    ///     }
+    /// }
+    /// ```
+    pub fn dispatch_over_image(&self, image: &Image) {
+        self.dispatch(self.workgroups_over_image(image));
+    }
+
+    /// Executes a kernel over `image` like `Kernel::dispatch_over_image()` but first writes
+    /// the size of the image as a `vec4<u32>` (width, height, depth and 0) in the push
+    /// constants range `offset`..`offset + 16`, so the bounds guard doesn't need to query
+    /// the image.
+    ///
+    /// The range is overwritten on every call, so it must not overlap the push constants
+    /// set by the caller. By convention the size goes at [`IMAGE_SIZE_OFFSET`].
+    ///
+    /// # Note
+    ///
+    /// Requires the `PUSH_CONSTANTS` feature, see `CommandQueue::enqueue_set_push_constants()`.
+    ///
+    /// # Panics
+    ///
+    /// - if the program of the kernel wasn't created from wgsl.
+    /// - if `offset`..`offset + 16` isn't part of the `push_constants_range` of the kernel.
+    ///
+    /// # Example wgsl syntax
+    /// ```cpp,ignore
+    /// struct Dispatch {
+    ///     base: vec4<u32>,
+    ///     size: vec4<u32>,
+    /// }
+    ///
+    /// var<push_constant> dispatch: Dispatch;
+    ///
+    /// @compute @workgroup_size(8, 8)
+    /// fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    ///     if any(id >= dispatch.size.xyz) {
+    ///         return;
+    ///     }
+    /// }
+    /// ```
+    pub fn dispatch_over_image_with_size(&self, image: &Image, offset: u32) {
+        let size = [image.width(), image.height(), image.depth(), 0];
+        assert!(
+            self.push_constants_range
+                .as_ref()
+                .is_some_and(|range| range.start <= offset && offset + 16 <= range.end),
+            "the image size at {}..{} isn't part of the push constants range {:?} of the kernel",
+            offset,
+            offset + 16,
+            self.push_constants_range,
+        );
+
+        CommandQueue {
+            device: Arc::clone(&self.device),
+            cmd_queue: std::collections::VecDeque::new(),
+        }
+        .enqueue_set_kernel(self)
+        .enqueue_set_push_constants(offset, bytemuck::cast_slice(&size))
+        .enqueue_dispatch(self.workgroups_over_image(image))
+        .execute();
+    }
+
+    /// Dispatches the kernel `warmup` times and then times `iterations` more dispatches,
+    /// useful to compare variants of a kernel.
+    ///
+    /// The dispatches are timed on the device with timestamp queries when the
+    /// `TIMESTAMP_QUERY` feature is enabled, otherwise each dispatch is timed on the host
+    /// waiting for the device to go idle after it (which includes the submission overhead).
+    ///
+    /// # Panics
+    ///
+    /// - if `iterations` is 0.
+    pub fn bench(&self, workgroups: impl Into<Range>, iterations: u32, warmup: u32) -> BenchResult {
+        bench::bench(self, workgroups.into(), iterations, warmup)
+    }
+
+    /// Dispatches the kernel right away and returns a future that resolves to the contents
+    /// of `output` once the dispatch is done, see `Buffer::read_to_vec_async()`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let results = kernel.dispatch_and_read(hac::Range::d1(n), &output).await;
+    /// ```
+    pub fn dispatch_and_read<'a, T: Pod>(
+        &self,
+        workgroups: impl Into<Range>,
+        output: &'a Buffer<T>,
+    ) -> impl Future<Output = Vec<T>> + 'a {
+        self.dispatch(workgroups);
+        output.read_to_vec_async()
+    }
+}
+
+/// Reason why `Kernel::validate()` rejected a dispatch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The number of bind groups doesn't match the ones of the kernel.
+    BindGroupCount { expected: usize, found: usize },
+
+    /// The bind group at `index` has a different layout than the one the kernel
+    /// was created with.
+    BindGroupLayout { index: u32 },
+
+    /// A binding doesn't match the program, the message describes the mismatch.
+    Binding(String),
+
+    /// A dimension of `workgroups` exceeds the `max_compute_workgroups_per_dimension` limit.
+    Workgroups {
+        workgroups: Range,
+        max_per_dimension: u32,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::BindGroupCount { expected, found } => {
+                write!(
+                    f,
+                    "the kernel expects {expected} bind groups but {found} were given"
+                )
+            }
+            ValidationError::BindGroupLayout { index } => write!(
+                f,
+                "the bind group {index} doesn't have the layout the kernel was created with"
+            ),
+            ValidationError::Binding(mismatch) => f.write_str(mismatch),
+            ValidationError::Workgroups {
+                workgroups,
+                max_per_dimension,
+            } => write!(
+                f,
+                "{workgroups} workgroups exceed the limit of {max_per_dimension} per dimension"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Error of a kernel that uses more workgroup memory than the device allows.
+#[derive(Debug)]
+struct WorkgroupStorageExceeded {
+    size: u32,
+    limit: u32,
+}
+
+impl fmt::Display for WorkgroupStorageExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the kernel uses {} bytes of workgroup memory but the \
+             max_compute_workgroup_storage_size limit is {}",
+            self.size, self.limit,
+        )
+    }
+}
+
+impl std::error::Error for WorkgroupStorageExceeded {}
+
+/// Error of a kernel whose workgroup size is larger than the device allows.
+#[derive(Debug)]
+struct WorkgroupSizeExceeded {
+    size: [u32; 3],
+    max_size: [u32; 3],
+    max_invocations: u32,
+}
+
+impl fmt::Display for WorkgroupSizeExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [x, y, z] = self.size;
+        write!(
+            f,
+            "the kernel has a workgroup size of {x}x{y}x{z} ({} invocations) but the \
+             max_compute_workgroup_size limits are {:?} and the \
+             max_compute_invocations_per_workgroup limit is {}",
+            x as u64 * y as u64 * z as u64,
+            self.max_size,
+            self.max_invocations,
+        )
+    }
+}
+
+impl std::error::Error for WorkgroupSizeExceeded {}
+
+/// Error of a kernel whose push constants range doesn't cover the ones of its entry point.
+#[derive(Debug)]
+struct PushConstantsMismatch {
+    size: u32,
+    range: Option<std::ops::Range<u32>>,
+}
+
+impl fmt::Display for PushConstantsMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the kernel uses {} bytes of push constants but the push constants range is {:?}, \
+             it must cover 0..{}",
+            self.size, self.range, self.size,
+        )
+    }
+}
+
+impl std::error::Error for PushConstantsMismatch {}
+
+/// Identifies the pipelines that can be shared between kernels.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct KernelKey {
+    program: u64,
+    entry_point: String,
+    layouts: Vec<Vec<wgpu::BindGroupLayoutEntry>>,
+    push_constants_range: Option<std::ops::Range<u32>>,
+}
+
+/// Maximum number of pipelines kept by the kernel cache of a context, see `Kernel::cached()`.
+pub const KERNEL_CACHE_CAPACITY: usize = 256;
+
+/// Pipelines of the kernels created with `Kernel::cached()`.
+#[derive(Debug, Default)]
+pub(crate) struct KernelCache {
+    pipelines: Mutex<HashMap<KernelKey, CachedPipeline>>,
+    /// Incremented on every lookup to find the least recently used pipeline.
+    clock: AtomicU64,
+}
+
+#[derive(Debug)]
+struct CachedPipeline {
+    pipeline: Arc<wgpu::ComputePipeline>,
+    last_used: u64,
+}
+
+impl KernelCache {
+    /// Returns the pipeline cached with `key`, creating it with `f` if there's none.
+    fn get_or_insert_with(
+        &self,
+        key: KernelKey,
+        f: impl FnOnce() -> wgpu::ComputePipeline,
+    ) -> Arc<wgpu::ComputePipeline> {
+        let now = self.clock.fetch_add(1, Ordering::Relaxed);
+        let mut pipelines = self.pipelines.lock().unwrap();
+
+        if !pipelines.contains_key(&key) && pipelines.len() >= KERNEL_CACHE_CAPACITY {
+            let least_recently_used = pipelines
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used)
+                .map(|(key, _)| key.clone());
+
+            if let Some(key) = least_recently_used {
+                pipelines.remove(&key);
+            }
+        }
+
+        let cached = pipelines.entry(key).or_insert_with(|| CachedPipeline {
+            pipeline: Arc::new(f()),
+            last_used: now,
+        });
+        cached.last_used = now;
+
+        Arc::clone(&cached.pipeline)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.pipelines.lock().unwrap().len()
+    }
+
+    pub(crate) fn clear(&self) {
+        self.pipelines.lock().unwrap().clear();
+    }
+
+    /// Drops the pipelines created from the program with id `program`.
+    fn remove_program(&self, program: u64) {
+        self.pipelines
+            .lock()
+            .unwrap()
+            .retain(|key, _| key.program != program);
+    }
 }
@@ -3,6 +3,23 @@
 //! Hardware Accelerated Computing API via the GPU, built on top of [wgpu](wgpu.rs/)
 //! for achieving great portability.
 //!
+//! ## Threading
+//!
+//! Every type of the crate is `Send + Sync` and the [`Context`] can be cloned cheaply,
+//! so resources can be created, written and read back from multiple threads at the
+//! same time (i.e. loading data from a thread pool). Reading back blocks the calling
+//! thread until all the work submitted before the read is done.
+//!
+//! A [`CommandQueue`] is built by value so it's only ever recorded by one thread,
+//! once executed its work is ordered with the work submitted from the other threads
+//! in the order the submissions happen (see the ordering section of [`CommandQueue`]).
+//!
+//! ## Validation
+//!
+//! The backend validation layers are enabled when `wgpu-core` is built with debug
+//! assertions, override `debug-assertions` for the `wgpu-core` package in the cargo
+//! profile to force them on or off (they're much slower but report driver misuse).
+//!
 //! ## Example: Add arrays
 //!
 //! ```rust
@@ -10,9 +27,9 @@
 //!
 //! // wgpu's default `max_workgroups_per_dimension`
 //! // can be changed using `hac::Limits` on Context creation
-//! const N: usize = 1 << 16 - 1;
+//! const N: usize = 1 << (16 - 1);
 //!
-//! const KERNEL_SOURCE: &'static str = r#"
+//! const KERNEL_SOURCE: &str = r#"
 //! struct ComputeInput {
 //!     // wgsl builtin variables can be found in the following link
 //!     // https://www.w3.org/TR/WGSL/#builtin-values
@@ -75,18 +92,38 @@
 //! }
 //! ```
 
+mod bench;
 mod bind_group;
 mod buffer;
+mod chunked_buffer;
 mod command_queue;
+mod compute_graph;
 mod context;
+mod fill;
+mod histogram;
 mod image;
 mod kernel;
+mod memory;
+mod mipmap;
+mod reduce;
+mod reflection;
+mod resize;
 mod sampler;
+mod scan;
+mod snapshot;
+mod sort;
+mod upload_buffer;
 
 pub use self::{
-    bind_group::*, buffer::*, command_queue::*, context::*, image::*, kernel::*, sampler::*,
+    bench::*, bind_group::*, buffer::*, chunked_buffer::*, command_queue::*, compute_graph::*,
+    context::*, image::*, kernel::*, memory::MemoryReport, reflection::*, sampler::*, scan::*,
+    snapshot::*, upload_buffer::*,
 };
 pub use bytemuck::cast_slice;
+use std::collections::HashMap;
+
+#[cfg(not(target_arch = "wasm32"))]
+use pollster::FutureExt as _;
 
 /// Handle of `wgpu::Device` and it's `wgpu::Queue`, atomically shared between
 /// all structs that need it.
@@ -94,6 +131,47 @@ pub use bytemuck::cast_slice;
 struct Device {
     pub(crate) handle: wgpu::Device,
     pub(crate) queue: wgpu::Queue,
+    pub(crate) adapter_features: wgpu::Features,
+    /// Adapter specific features of the storage formats, only queried when the device has
+    /// the `TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES` feature.
+    pub(crate) format_features: HashMap<ImageFormat, wgpu::TextureFormatFeatures>,
+    pub(crate) memory: memory::MemoryUsage,
+}
+
+impl Device {
+    /// Calls `f` capturing the validation and out of memory errors that it produces
+    /// instead of reporting them to the uncaptured error handler.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn capture_errors<R>(&self, f: impl FnOnce() -> R) -> Result<R, Error> {
+        self.handle.push_error_scope(wgpu::ErrorFilter::OutOfMemory);
+        self.handle.push_error_scope(wgpu::ErrorFilter::Validation);
+
+        let result = f();
+
+        let validation_error = self.handle.pop_error_scope().block_on();
+        let out_of_memory_error = self.handle.pop_error_scope().block_on();
+
+        match validation_error.or(out_of_memory_error) {
+            Some(error) => Err(error),
+            None => Ok(result),
+        }
+    }
+
+    /// Calls `f`, the errors it produces are reported to the uncaptured error handler
+    /// (logged to the console of the browser) since the error scopes can't be waited on.
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) fn capture_errors<R>(&self, f: impl FnOnce() -> R) -> Result<R, Error> {
+        Ok(f())
+    }
+
+    /// Features of `format` on this device, the adapter specific ones if they were queried
+    /// or the ones guaranteed by WebGPU otherwise.
+    pub(crate) fn format_features(&self, format: ImageFormat) -> wgpu::TextureFormatFeatures {
+        self.format_features
+            .get(&format)
+            .cloned()
+            .unwrap_or_else(|| format.describe().guaranteed_format_features)
+    }
 }
 
 /// 3 dimensional range used to specify workgroup sizes when dispatching a kernel.
@@ -105,6 +183,12 @@ pub struct Range {
 }
 
 impl Range {
+    /// Range with all dimensions set to 0.
+    pub const ZERO: Self = Self::splat(0);
+
+    /// Range with all dimensions set to 1.
+    pub const ONE: Self = Self::splat(1);
+
     /// Creates a 3d range.
     pub const fn new(x: u32, y: u32, z: u32) -> Self {
         Self { x, y, z }
@@ -124,4 +208,46 @@ impl Range {
     pub const fn d3(x: u32, y: u32, z: u32) -> Self {
         Self::new(x, y, z)
     }
+
+    /// Creates a 3d range with all dimensions set to `n`.
+    pub const fn splat(n: u32) -> Self {
+        Self::new(n, n, n)
+    }
+
+    /// Splits `groups` workgroups in a 2d range so that no dimension exceeds `max_per_dimension`.
+    ///
+    /// Kernels dispatched this way must flatten their workgroup id as
+    /// `workgroup_id.x + workgroup_id.y * num_workgroups.x` and discard the excess groups.
+    pub(crate) fn grid(groups: u64, max_per_dimension: u32) -> Self {
+        let x = groups.clamp(1, max_per_dimension as u64);
+        let y = groups.div_ceil(x);
+
+        Self::d2(x as u32, y as u32)
+    }
+}
+
+impl std::fmt::Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}x{}", self.x, self.y, self.z)
+    }
+}
+
+impl From<u32> for Range {
+    /// Equivalent to `Range::d1()`.
+    fn from(x: u32) -> Self {
+        Self::d1(x)
+    }
+}
+
+impl From<(u32, u32)> for Range {
+    /// Equivalent to `Range::d2()`.
+    fn from((x, y): (u32, u32)) -> Self {
+        Self::d2(x, y)
+    }
+}
+
+impl From<(u32, u32, u32)> for Range {
+    fn from((x, y, z): (u32, u32, u32)) -> Self {
+        Self::new(x, y, z)
+    }
 }
@@ -0,0 +1,84 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// Bytes of device memory held by the resources of a context, see `Context::memory_report()`.
+///
+/// The sizes are the ones requested when creating the resources, the driver may
+/// allocate some more for alignment and metadata.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryReport {
+    /// Bytes held by buffers (including upload buffers).
+    pub buffers: u64,
+    /// Bytes held by images, summing all their mip levels and samples.
+    pub images: u64,
+}
+
+impl MemoryReport {
+    /// Bytes held by all the resources.
+    pub fn total(&self) -> u64 {
+        self.buffers + self.images
+    }
+}
+
+/// Counters of the bytes allocated by the resources of a device.
+#[derive(Debug, Default)]
+pub(crate) struct MemoryUsage {
+    buffers: AtomicU64,
+    images: AtomicU64,
+}
+
+impl MemoryUsage {
+    pub(crate) fn report(&self) -> MemoryReport {
+        MemoryReport {
+            buffers: self.buffers.load(Ordering::Relaxed),
+            images: self.images.load(Ordering::Relaxed),
+        }
+    }
+
+    fn counter(&self, kind: ResourceKind) -> &AtomicU64 {
+        match kind {
+            ResourceKind::Buffer => &self.buffers,
+            ResourceKind::Image => &self.images,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ResourceKind {
+    Buffer,
+    Image,
+}
+
+/// Accounts `size` bytes to the memory usage of a device until it's dropped.
+#[derive(Debug)]
+pub(crate) struct Allocation {
+    device: Arc<crate::Device>,
+    kind: ResourceKind,
+    size: u64,
+}
+
+impl Allocation {
+    pub(crate) fn new(device: &Arc<crate::Device>, kind: ResourceKind, size: u64) -> Self {
+        device
+            .memory
+            .counter(kind)
+            .fetch_add(size, Ordering::Relaxed);
+
+        Self {
+            device: Arc::clone(device),
+            kind,
+            size,
+        }
+    }
+}
+
+impl Drop for Allocation {
+    fn drop(&mut self) {
+        self.device
+            .memory
+            .counter(self.kind)
+            .fetch_sub(self.size, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{
+    image::wgsl_storage_format, BindGroup, Context, Image, ImageDimension, KernelInfo, Range,
+    StorageImageAccess,
+};
+
+/// Fills the mip levels of `image` after the first one, each level is the result of
+/// downsampling the previous one with a box filter.
+pub(crate) fn generate_mipmaps(context: &Context, image: &Image) {
+    assert_eq!(
+        image.dimension,
+        ImageDimension::D2,
+        "mipmaps can only be generated for 2D images"
+    );
+
+    let is_float = matches!(
+        image.format.describe().sample_type,
+        wgpu::TextureSampleType::Float { .. }
+    );
+
+    let format = wgsl_storage_format(image.format)
+        .filter(|_| is_float)
+        .expect("mipmaps can only be generated for float formats that support storage");
+
+    if image.mip_level_count == 1 {
+        return;
+    }
+
+    let bind_groups = (1..image.mip_level_count)
+        .map(|level| {
+            context
+                .bind_group_descriptor()
+                .push_image_mip(image, level - 1)
+                .push_storage_image_mip(image, level, StorageImageAccess::WriteOnly)
+                .into_bind_group()
+        })
+        .collect::<Vec<BindGroup>>();
+
+    let source = format!(
+        "@group(0) @binding(1)\nvar output: texture_storage_2d<{format}, write>;\n{}",
+        include_str!("mipmap.wgsl")
+    );
+    let program = context.program_from_wgsl(&source);
+
+    let kernel = context.kernel(&KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_groups[0]],
+        push_constants_range: None,
+    });
+
+    bind_groups
+        .iter()
+        .zip(1..)
+        .fold(
+            context.command_queue().enqueue_set_kernel(&kernel),
+            |queue, (bind_group, level)| {
+                let size = image.mip_level_size(level);
+
+                queue
+                    .enqueue_set_bind_group(0, bind_group)
+                    .enqueue_dispatch(Range::d2(size.width.div_ceil(8), size.height.div_ceil(8)))
+            },
+        )
+        .execute();
+}
@@ -0,0 +1,25 @@
+// `output` is declared by `mipmap.rs` with the storage format of the image.
+
+@group(0) @binding(0)
+var input: texture_2d<f32>;
+
+// each texel of the output level is the average of a 2x2 block of the input level
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let size = textureDimensions(output);
+    let pos = vec2<i32>(id.xy);
+
+    if (pos.x >= size.x || pos.y >= size.y) {
+        return;
+    }
+
+    let last = textureDimensions(input) - vec2<i32>(1);
+    let src = 2 * pos;
+
+    let color = textureLoad(input, min(src, last), 0)
+        + textureLoad(input, min(src + vec2<i32>(1, 0), last), 0)
+        + textureLoad(input, min(src + vec2<i32>(0, 1), last), 0)
+        + textureLoad(input, min(src + vec2<i32>(1, 1), last), 0);
+
+    textureStore(output, pos, 0.25 * color);
+}
@@ -0,0 +1,108 @@
+use crate::{BindGroup, Buffer, BufferAccess, Context, KernelInfo, Range};
+
+/// Ammount of elements that a single workgroup of the reduction kernel collapses into one.
+///
+/// The workgroups reduce in workgroup memory, wgpu 0.14 has no subgroup feature and naga
+/// 0.10 can't parse the wgsl subgroup builtins.
+const ELEMENTS_PER_WORKGROUP: u64 = 512;
+
+/// Associative operation used to collapse a buffer into a single value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReduceOp {
+    Sum,
+    Min,
+    Max,
+}
+
+impl ReduceOp {
+    /// Value that leaves any other unchanged when combined with it.
+    fn identity(self) -> f32 {
+        match self {
+            ReduceOp::Sum => 0.0,
+            ReduceOp::Min => f32::INFINITY,
+            ReduceOp::Max => f32::NEG_INFINITY,
+        }
+    }
+
+    /// wgsl definitions of `identity()` and `combine(a, b)` required by `reduce.wgsl`.
+    fn prelude(self) -> &'static str {
+        match self {
+            ReduceOp::Sum => {
+                "fn identity() -> f32 { return 0.0; }\n\
+                 fn combine(a: f32, b: f32) -> f32 { return a + b; }\n"
+            }
+            ReduceOp::Min => {
+                "fn identity() -> f32 { return bitcast<f32>(0x7f800000u); }\n\
+                 fn combine(a: f32, b: f32) -> f32 { return min(a, b); }\n"
+            }
+            ReduceOp::Max => {
+                "fn identity() -> f32 { return bitcast<f32>(0xff800000u); }\n\
+                 fn combine(a: f32, b: f32) -> f32 { return max(a, b); }\n"
+            }
+        }
+    }
+}
+
+/// Collapses the contents of `buffer` into a single value with `op`.
+///
+/// The reduction is performed as a tree where every pass shrinks the data by a factor
+/// of `ELEMENTS_PER_WORKGROUP`, all passes are recorded in a single `CommandQueue`.
+pub(crate) fn reduce(context: &Context, buffer: &Buffer<f32>, op: ReduceOp) -> f32 {
+    if buffer.is_empty() {
+        return op.identity();
+    }
+
+    let mut outputs = Vec::new();
+    let mut len = buffer.len();
+    while len > 1 {
+        len = len.div_ceil(ELEMENTS_PER_WORKGROUP);
+        outputs.push(context.buffer::<f32>(len));
+    }
+
+    let Some(result) = outputs.last() else {
+        return buffer.read_to_vec()[0];
+    };
+
+    let bind_groups = std::iter::once(buffer)
+        .chain(&outputs)
+        .zip(&outputs)
+        .map(|(input, output)| {
+            context
+                .bind_group_descriptor()
+                .push_buffer(input, BufferAccess::ReadOnly)
+                .push_buffer(output, BufferAccess::ReadWrite)
+                .into_bind_group()
+        })
+        .collect::<Vec<BindGroup>>();
+
+    let source = format!("{}{}", op.prelude(), include_str!("reduce.wgsl"));
+    let program = context.program_from_wgsl(&source);
+
+    let kernel = context.kernel(&KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_groups[0]],
+        push_constants_range: None,
+    });
+
+    let max_per_dimension = context
+        .device
+        .handle
+        .limits()
+        .max_compute_workgroups_per_dimension;
+
+    bind_groups
+        .iter()
+        .zip(&outputs)
+        .fold(
+            context.command_queue().enqueue_set_kernel(&kernel),
+            |queue, (bind_group, output)| {
+                queue
+                    .enqueue_set_bind_group(0, bind_group)
+                    .enqueue_dispatch(Range::grid(output.len(), max_per_dimension))
+            },
+        )
+        .execute();
+
+    result.read_to_vec()[0]
+}
@@ -0,0 +1,44 @@
+// `identity()` and `combine(a, b)` are prepended by `reduce.rs` depending on
+// the reduction operation.
+
+struct ComputeInput {
+    @builtin(local_invocation_index) local_index: u32,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+    @builtin(num_workgroups) num_groups: vec3<u32>,
+}
+
+@group(0) @binding(0)
+var<storage, read> input: array<f32>;
+@group(0) @binding(1)
+var<storage, read_write> output: array<f32>;
+
+var<workgroup> partial: array<f32, 256>;
+
+fn load(i: u32) -> f32 {
+    if (i < arrayLength(&input)) {
+        return input[i];
+    }
+    return identity();
+}
+
+// each workgroup reduces 512 elements into a single one
+@compute @workgroup_size(256)
+fn main(in: ComputeInput) {
+    let group = in.group_id.x + in.group_id.y * in.num_groups.x;
+    let local = in.local_index;
+    let i = group * 512u + local;
+
+    partial[local] = combine(load(i), load(i + 256u));
+    workgroupBarrier();
+
+    for (var stride = 128u; stride > 0u; stride = stride >> 1u) {
+        if (local < stride) {
+            partial[local] = combine(partial[local], partial[local + stride]);
+        }
+        workgroupBarrier();
+    }
+
+    if (local == 0u && group < arrayLength(&output)) {
+        output[group] = partial[0];
+    }
+}
@@ -0,0 +1,529 @@
+use std::fmt;
+
+use crate::{
+    BindGroup, BufferAccess, ImageFormat, ShaderError, SourceLocation, StorageImageAccess,
+};
+
+/// Resource that a program expects at a binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpectedResource {
+    /// `var<storage, 'access'>`, see `BindGroupDescriptor::push_buffer()`.
+    Buffer { access: BufferAccess },
+
+    /// `var<uniform>`.
+    Uniform,
+
+    /// `texture_*`, see `BindGroupDescriptor::push_image()`.
+    Image {
+        dimension: wgpu::TextureViewDimension,
+    },
+
+    /// `texture_storage_*`, see `BindGroupDescriptor::push_storage_image()`.
+    StorageImage {
+        format: ImageFormat,
+        access: StorageImageAccess,
+        dimension: wgpu::TextureViewDimension,
+    },
+
+    /// `sampler` or `sampler_comparison`, see `BindGroupDescriptor::push_sampler()`.
+    Sampler { comparison: bool },
+}
+
+impl ExpectedResource {
+    /// The resource that matches a binding of type `ty`.
+    fn from_binding_type(ty: &wgpu::BindingType) -> Self {
+        match *ty {
+            wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                ..
+            } => Self::Buffer {
+                access: if read_only {
+                    BufferAccess::ReadOnly
+                } else {
+                    BufferAccess::ReadWrite
+                },
+            },
+            wgpu::BindingType::Buffer { .. } => Self::Uniform,
+            wgpu::BindingType::Texture { view_dimension, .. } => Self::Image {
+                dimension: view_dimension,
+            },
+            wgpu::BindingType::StorageTexture {
+                access,
+                format,
+                view_dimension,
+            } => Self::StorageImage {
+                format,
+                access,
+                dimension: view_dimension,
+            },
+            wgpu::BindingType::Sampler(ty) => Self::Sampler {
+                comparison: ty == wgpu::SamplerBindingType::Comparison,
+            },
+        }
+    }
+
+    fn from_naga(module: &naga::Module, var: &naga::GlobalVariable) -> Option<Self> {
+        let mut ty = &module.types[var.ty].inner;
+        if let naga::TypeInner::BindingArray { base, .. } = *ty {
+            ty = &module.types[base].inner;
+        }
+
+        let resource = match (var.space, ty) {
+            (naga::AddressSpace::Storage { access }, _) => Self::Buffer {
+                access: if access.contains(naga::StorageAccess::STORE) {
+                    BufferAccess::ReadWrite
+                } else {
+                    BufferAccess::ReadOnly
+                },
+            },
+            (naga::AddressSpace::Uniform, _) => Self::Uniform,
+            (_, &naga::TypeInner::Sampler { comparison }) => Self::Sampler { comparison },
+            (
+                _,
+                &naga::TypeInner::Image {
+                    dim,
+                    arrayed,
+                    class,
+                },
+            ) => {
+                let dimension = match (dim, arrayed) {
+                    (naga::ImageDimension::D1, _) => wgpu::TextureViewDimension::D1,
+                    (naga::ImageDimension::D2, false) => wgpu::TextureViewDimension::D2,
+                    (naga::ImageDimension::D2, true) => wgpu::TextureViewDimension::D2Array,
+                    (naga::ImageDimension::D3, _) => wgpu::TextureViewDimension::D3,
+                    (naga::ImageDimension::Cube, false) => wgpu::TextureViewDimension::Cube,
+                    (naga::ImageDimension::Cube, true) => wgpu::TextureViewDimension::CubeArray,
+                };
+
+                match class {
+                    naga::ImageClass::Storage { format, access } => Self::StorageImage {
+                        format: image_format(format),
+                        access: match (
+                            access.contains(naga::StorageAccess::LOAD),
+                            access.contains(naga::StorageAccess::STORE),
+                        ) {
+                            (true, true) => StorageImageAccess::ReadWrite,
+                            (true, false) => StorageImageAccess::ReadOnly,
+                            _ => StorageImageAccess::WriteOnly,
+                        },
+                        dimension,
+                    },
+                    _ => Self::Image { dimension },
+                }
+            }
+            _ => return None,
+        };
+
+        Some(resource)
+    }
+}
+
+impl fmt::Display for ExpectedResource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Buffer { access } => write!(f, "{access:?} buffer"),
+            Self::Uniform => write!(f, "uniform buffer"),
+            Self::Image { dimension } => write!(f, "{dimension:?} image"),
+            Self::StorageImage {
+                format,
+                access,
+                dimension,
+            } => write!(f, "{access:?} {dimension:?} storage image of {format:?}"),
+            Self::Sampler { comparison: false } => write!(f, "sampler"),
+            Self::Sampler { comparison: true } => write!(f, "comparison sampler"),
+        }
+    }
+}
+
+fn image_format(format: naga::StorageFormat) -> ImageFormat {
+    use naga::StorageFormat as Sf;
+
+    match format {
+        Sf::R8Unorm => ImageFormat::R8Unorm,
+        Sf::R8Snorm => ImageFormat::R8Snorm,
+        Sf::R8Uint => ImageFormat::R8Uint,
+        Sf::R8Sint => ImageFormat::R8Sint,
+        Sf::R16Uint => ImageFormat::R16Uint,
+        Sf::R16Sint => ImageFormat::R16Sint,
+        Sf::R16Float => ImageFormat::R16Float,
+        Sf::Rg8Unorm => ImageFormat::Rg8Unorm,
+        Sf::Rg8Snorm => ImageFormat::Rg8Snorm,
+        Sf::Rg8Uint => ImageFormat::Rg8Uint,
+        Sf::Rg8Sint => ImageFormat::Rg8Sint,
+        Sf::R32Uint => ImageFormat::R32Uint,
+        Sf::R32Sint => ImageFormat::R32Sint,
+        Sf::R32Float => ImageFormat::R32Float,
+        Sf::Rg16Uint => ImageFormat::Rg16Uint,
+        Sf::Rg16Sint => ImageFormat::Rg16Sint,
+        Sf::Rg16Float => ImageFormat::Rg16Float,
+        Sf::Rgba8Unorm => ImageFormat::Rgba8Unorm,
+        Sf::Rgba8Snorm => ImageFormat::Rgba8Snorm,
+        Sf::Rgba8Uint => ImageFormat::Rgba8Uint,
+        Sf::Rgba8Sint => ImageFormat::Rgba8Sint,
+        Sf::Rgb10a2Unorm => ImageFormat::Rgb10a2Unorm,
+        Sf::Rg11b10Float => ImageFormat::Rg11b10Float,
+        Sf::Rg32Uint => ImageFormat::Rg32Uint,
+        Sf::Rg32Sint => ImageFormat::Rg32Sint,
+        Sf::Rg32Float => ImageFormat::Rg32Float,
+        Sf::Rgba16Uint => ImageFormat::Rgba16Uint,
+        Sf::Rgba16Sint => ImageFormat::Rgba16Sint,
+        Sf::Rgba16Float => ImageFormat::Rgba16Float,
+        Sf::Rgba32Uint => ImageFormat::Rgba32Uint,
+        Sf::Rgba32Sint => ImageFormat::Rgba32Sint,
+        Sf::Rgba32Float => ImageFormat::Rgba32Float,
+    }
+}
+
+/// A binding used by the entry point of a program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedBinding {
+    /// Index of the bind group, the `X` in `@group(X)`.
+    pub group: u32,
+    /// Index of the binding inside its group, the `Y` in `@binding(Y)`.
+    pub binding: u32,
+    /// Name of the variable in the program.
+    pub name: Option<String>,
+    /// Kind of resource that the program expects to be bound.
+    pub resource: ExpectedResource,
+}
+
+impl fmt::Display for ExpectedBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "@group({}) @binding({})", self.group, self.binding)?;
+
+        if let Some(name) = &self.name {
+            write!(f, " {name}")?;
+        }
+
+        write!(f, ": {}", self.resource)
+    }
+}
+
+/// Bindings that the entry point of a kernel expects, sorted by group and binding.
+///
+/// The `Display` implementation lists one binding per line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutReport {
+    /// One entry per variable bound by the entry point.
+    pub bindings: Vec<ExpectedBinding>,
+}
+
+impl LayoutReport {
+    /// Checks that `bind_groups` provide the bindings that the kernel expects,
+    /// describing the first mismatch otherwise.
+    pub(crate) fn validate(&self, bind_groups: &[&BindGroup]) -> Result<(), LayoutMismatch> {
+        let layouts: Vec<_> = bind_groups
+            .iter()
+            .map(|bind_group| bind_group.layout_entries.as_slice())
+            .collect();
+
+        self.validate_layouts(&layouts)
+    }
+
+    /// Checks that the entries of the bind group `layouts` provide the bindings that
+    /// the kernel expects, describing the first mismatch otherwise.
+    pub(crate) fn validate_layouts(
+        &self,
+        layouts: &[&[wgpu::BindGroupLayoutEntry]],
+    ) -> Result<(), LayoutMismatch> {
+        self.bindings.iter().try_for_each(|expected| {
+            let entry = layouts.get(expected.group as usize).and_then(|entries| {
+                entries
+                    .iter()
+                    .find(|entry| entry.binding == expected.binding)
+            });
+
+            match entry.map(|entry| ExpectedResource::from_binding_type(&entry.ty)) {
+                Some(resource) if resource == expected.resource => Ok(()),
+                Some(resource) => Err(LayoutMismatch(format!(
+                    "{expected} but a {resource} was bound"
+                ))),
+                None => Err(LayoutMismatch(format!("{expected} but nothing was bound"))),
+            }
+        })
+    }
+}
+
+impl fmt::Display for LayoutReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.bindings
+            .iter()
+            .try_for_each(|binding| writeln!(f, "{binding}"))
+    }
+}
+
+/// Error of a kernel created with bind groups that don't match its program.
+#[derive(Debug)]
+pub(crate) struct LayoutMismatch(String);
+
+impl fmt::Display for LayoutMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the kernel expects {}", self.0)
+    }
+}
+
+impl std::error::Error for LayoutMismatch {}
+
+/// Layout of a member of a [`StructLayout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberLayout {
+    /// Name of the member in the program.
+    pub name: Option<String>,
+    /// Offset in bytes of the member from the start of the struct.
+    pub offset: u32,
+}
+
+/// Layout of a wgsl struct stored in a buffer, see `Buffer::from_slice_checked()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructLayout {
+    /// Distance in bytes between consecutive elements of an `array` of the struct, the
+    /// size that the Rust type must have.
+    pub stride: u32,
+    /// Members of the struct in declaration order.
+    pub members: Vec<MemberLayout>,
+}
+
+impl StructLayout {
+    /// Reflects the layout of the struct called `name` declared in the wgsl `source`,
+    /// `None` if the source is invalid or doesn't declare it.
+    pub fn from_wgsl(source: &str, name: &str) -> Option<Self> {
+        Reflection::from_wgsl(source)?.struct_layout(name)
+    }
+
+    /// Checks that `T` has the size of the struct and, unless `field_offsets` is empty,
+    /// that the fields of `T` that hold the members (in declaration order, leaving out
+    /// explicit padding fields) are at the offsets of the members.
+    ///
+    /// The offsets can be computed with `std::mem::offset_of!()` (or `bytemuck::offset_of!()`).
+    pub fn check<T>(&self, field_offsets: &[usize]) -> Result<(), StructLayoutMismatch> {
+        let size = std::mem::size_of::<T>();
+        if size != self.stride as usize {
+            return Err(StructLayoutMismatch::Size {
+                rust: size,
+                wgsl: self.stride,
+            });
+        }
+
+        if field_offsets.is_empty() {
+            return Ok(());
+        }
+
+        if field_offsets.len() != self.members.len() {
+            return Err(StructLayoutMismatch::FieldCount {
+                rust: field_offsets.len(),
+                wgsl: self.members.len(),
+            });
+        }
+
+        self.members
+            .iter()
+            .zip(field_offsets)
+            .enumerate()
+            .try_for_each(|(index, (member, &offset))| {
+                if offset == member.offset as usize {
+                    Ok(())
+                } else {
+                    Err(StructLayoutMismatch::FieldOffset {
+                        index,
+                        name: member.name.clone(),
+                        rust: offset,
+                        wgsl: member.offset,
+                    })
+                }
+            })
+    }
+}
+
+/// Difference between the layout of a Rust type and a wgsl struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StructLayoutMismatch {
+    /// The Rust type has `rust` bytes but elements of the struct take `wgsl` bytes.
+    Size { rust: usize, wgsl: u32 },
+
+    /// The Rust type has `rust` fields but the struct has `wgsl` members.
+    FieldCount { rust: usize, wgsl: usize },
+
+    /// The field at `index` is at offset `rust` but the member is at offset `wgsl`.
+    FieldOffset {
+        index: usize,
+        name: Option<String>,
+        rust: usize,
+        wgsl: u32,
+    },
+}
+
+impl fmt::Display for StructLayoutMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StructLayoutMismatch::Size { rust, wgsl } => write!(
+                f,
+                "the type has {rust} bytes but elements of the wgsl struct take {wgsl} bytes"
+            ),
+            StructLayoutMismatch::FieldCount { rust, wgsl } => write!(
+                f,
+                "the type has {rust} fields but the wgsl struct has {wgsl} members"
+            ),
+            StructLayoutMismatch::FieldOffset {
+                index,
+                name,
+                rust,
+                wgsl,
+            } => write!(
+                f,
+                "field {index} ({}) is at offset {rust} but the wgsl member is at offset {wgsl}",
+                name.as_deref().unwrap_or("unnamed"),
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StructLayoutMismatch {}
+
+/// Module of a wgsl program parsed by naga to inspect its bindings.
+#[derive(Debug)]
+pub(crate) struct Reflection {
+    module: naga::Module,
+    info: naga::valid::ModuleInfo,
+}
+
+impl Reflection {
+    /// Parses `source`, returns `None` if it's invalid to let wgpu report the errors.
+    pub(crate) fn from_wgsl(source: &str) -> Option<Self> {
+        Self::try_from_wgsl(source).ok()
+    }
+
+    /// Parses and validates `source`, describing the first error otherwise.
+    pub(crate) fn try_from_wgsl(source: &str) -> Result<Self, ShaderError> {
+        let module = naga::front::wgsl::parse_str(source).map_err(|error| ShaderError {
+            message: error.emit_to_string(source),
+            location: error.location(source).map(SourceLocation::from),
+        })?;
+
+        let info = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .map_err(|error| ShaderError {
+            message: error.emit_to_string(source),
+            location: error.location(source).map(SourceLocation::from),
+        })?;
+
+        Ok(Self { module, info })
+    }
+
+    /// Layout of the struct called `name`, `None` if it isn't declared.
+    pub(crate) fn struct_layout(&self, name: &str) -> Option<StructLayout> {
+        let (handle, ty) = self
+            .module
+            .types
+            .iter()
+            .find(|(_, ty)| ty.name.as_deref() == Some(name))?;
+
+        let naga::TypeInner::Struct { members, .. } = &ty.inner else {
+            return None;
+        };
+
+        let mut layouter = naga::proc::Layouter::default();
+        layouter
+            .update(&self.module.types, &self.module.constants)
+            .ok()?;
+
+        Some(StructLayout {
+            stride: layouter[handle].to_stride(),
+            members: members
+                .iter()
+                .map(|member| MemberLayout {
+                    name: member.name.clone(),
+                    offset: member.offset,
+                })
+                .collect(),
+        })
+    }
+
+    /// Index of the compute `entry_point`, `None` if it doesn't exist.
+    fn entry_point_index(&self, entry_point: &str) -> Option<usize> {
+        self.module.entry_points.iter().position(|entry| {
+            entry.name == entry_point && entry.stage == naga::ShaderStage::Compute
+        })
+    }
+
+    /// The `@workgroup_size` of the compute `entry_point`, `None` if it doesn't exist.
+    pub(crate) fn workgroup_size(&self, entry_point: &str) -> Option<[u32; 3]> {
+        let index = self.entry_point_index(entry_point)?;
+
+        Some(self.module.entry_points[index].workgroup_size)
+    }
+
+    /// Bytes of `var<workgroup>` memory used by the compute `entry_point` counted like
+    /// WebGPU does (each variable rounded up to 16 bytes), `None` if it doesn't exist.
+    pub(crate) fn workgroup_storage_size(&self, entry_point: &str) -> Option<u32> {
+        let index = self.entry_point_index(entry_point)?;
+        let uses = self.info.get_entry_point(index);
+
+        let mut layouter = naga::proc::Layouter::default();
+        layouter
+            .update(&self.module.types, &self.module.constants)
+            .ok()?;
+
+        let size = self
+            .module
+            .global_variables
+            .iter()
+            .filter(|&(handle, var)| {
+                var.space == naga::AddressSpace::WorkGroup && !uses[handle].is_empty()
+            })
+            .map(|(_, var)| layouter[var.ty].size.next_multiple_of(16))
+            .sum();
+
+        Some(size)
+    }
+
+    /// Bytes of the `var<push_constant>` used by the compute `entry_point`, `None` if it
+    /// doesn't exist or doesn't use push constants.
+    pub(crate) fn push_constant_size(&self, entry_point: &str) -> Option<u32> {
+        let index = self.entry_point_index(entry_point)?;
+        let uses = self.info.get_entry_point(index);
+
+        let mut layouter = naga::proc::Layouter::default();
+        layouter
+            .update(&self.module.types, &self.module.constants)
+            .ok()?;
+
+        self.module
+            .global_variables
+            .iter()
+            .find(|&(handle, var)| {
+                var.space == naga::AddressSpace::PushConstant && !uses[handle].is_empty()
+            })
+            .map(|(_, var)| layouter[var.ty].size)
+    }
+
+    /// Bindings used by the compute `entry_point`, `None` if it doesn't exist.
+    pub(crate) fn layout_report(&self, entry_point: &str) -> Option<LayoutReport> {
+        let index = self.entry_point_index(entry_point)?;
+
+        let uses = self.info.get_entry_point(index);
+
+        let mut bindings: Vec<_> = self
+            .module
+            .global_variables
+            .iter()
+            .filter(|&(handle, _)| !uses[handle].is_empty())
+            .filter_map(|(_, var)| {
+                let binding = var.binding.as_ref()?;
+
+                Some(ExpectedBinding {
+                    group: binding.group,
+                    binding: binding.binding,
+                    name: var.name.clone(),
+                    resource: ExpectedResource::from_naga(&self.module, var)?,
+                })
+            })
+            .collect();
+
+        bindings.sort_by_key(|binding| (binding.group, binding.binding));
+
+        Some(LayoutReport { bindings })
+    }
+}
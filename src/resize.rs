@@ -0,0 +1,69 @@
+use crate::{
+    image::wgsl_storage_format, Context, Extent3d, FilterMode, Image, ImageDimension, ImageInfo,
+    KernelInfo, Range, SamplerInfo, StorageImageAccess,
+};
+
+/// Creates a new image of `size` with the contents of `src` resampled with `filter`,
+/// upside down if `flip_y` is `true`.
+pub(crate) fn resize_image(
+    context: &Context,
+    src: &Image,
+    size: Extent3d,
+    filter: FilterMode,
+    flip_y: bool,
+) -> Image {
+    assert!(
+        src.dimension == ImageDimension::D2 && size.depth_or_array_layers == 1,
+        "only 2D images can be resized"
+    );
+
+    let is_float = matches!(
+        src.format.describe().sample_type,
+        wgpu::TextureSampleType::Float { .. }
+    );
+
+    let format = wgsl_storage_format(src.format)
+        .filter(|_| is_float)
+        .expect("images can only be resized with float formats that support storage");
+
+    let dst = context.image(&ImageInfo {
+        size,
+        format: src.format,
+        ..Default::default()
+    });
+
+    let sampler = context.sampler(&SamplerInfo {
+        mag_filter: filter,
+        min_filter: filter,
+        ..Default::default()
+    });
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_image(src)
+        .push_storage_image(&dst, StorageImageAccess::WriteOnly)
+        .push_sampler_auto(&sampler)
+        .into_bind_group();
+
+    let source = format!(
+        "@group(0) @binding(1)\nvar output: texture_storage_2d<{format}, write>;\n\
+         fn flip_y() -> bool {{ return {flip_y}; }}\n{}",
+        include_str!("resize.wgsl")
+    );
+    let program = context.program_from_wgsl(&source);
+
+    let kernel = context.kernel(&KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    context
+        .command_queue()
+        .enqueue_set_kernel(&kernel)
+        .enqueue_dispatch(Range::d2(size.width.div_ceil(8), size.height.div_ceil(8)))
+        .execute();
+
+    dst
+}
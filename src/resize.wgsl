@@ -0,0 +1,25 @@
+// `output` is declared by `resize.rs` with the storage format of the image, along with
+// `flip_y()` which tells whether the rows of the output are flipped.
+
+@group(0) @binding(0)
+var input: texture_2d<f32>;
+@group(0) @binding(2)
+var input_sampler: sampler;
+
+// each texel of the output is sampled at its center from the same relative position of the input
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let size = textureDimensions(output);
+    let pos = vec2<i32>(id.xy);
+
+    if (pos.x >= size.x || pos.y >= size.y) {
+        return;
+    }
+
+    var uv = (vec2<f32>(pos) + 0.5) / vec2<f32>(size);
+    if (flip_y()) {
+        uv.y = 1.0 - uv.y;
+    }
+
+    textureStore(output, pos, textureSampleLevel(input, input_sampler, uv, 0.0));
+}
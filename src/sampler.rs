@@ -1,9 +1,20 @@
-pub use wgpu::{AddressMode, FilterMode, SamplerBindingType, SamplerBorderColor};
+use std::num::NonZeroU8;
+
+pub use wgpu::{AddressMode, CompareFunction, FilterMode, SamplerBindingType, SamplerBorderColor};
 
 use crate::Context;
 
 /// Information to create a sampler.
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+///
+/// # Note
+///
+/// The sampler must be pushed with the binding type returned by
+/// `SamplerInfo::binding_type()`:
+/// - any `FilterMode::Linear` filter requires `SamplerBindingType::Filtering` and
+///   the sampled images to be filterable (`ImageSampleType::Float { filterable: true }`).
+/// - a `compare` function requires `SamplerBindingType::Comparison` and a depth image.
+/// - `anisotropy_clamp` requires every filter to be `FilterMode::Linear`.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct SamplerInfo {
     /// What to do when sampling out of bounds in the u direction.
     pub address_mode_u: AddressMode,
@@ -15,10 +26,58 @@ pub struct SamplerInfo {
     pub mag_filter: FilterMode,
     /// How to filter when the image has to be minified.
     pub min_filter: FilterMode,
+    /// How to filter between mip levels.
+    pub mipmap_filter: FilterMode,
+    /// Minimum level of detail (i.e. mip level) to use.
+    pub lod_min_clamp: f32,
+    /// Maximum level of detail (i.e. mip level) to use.
+    pub lod_max_clamp: f32,
+    /// If this is enabled, this is a comparison sampler using the given comparison function.
+    pub compare: Option<CompareFunction>,
+    /// Valid values are 1, 2, 4, 8, and 16.
+    pub anisotropy_clamp: Option<NonZeroU8>,
     /// Color of the border if `AddressMode::ClampToBorder` was chosen.
     pub border_color: Option<SamplerBorderColor>,
 }
 
+impl SamplerInfo {
+    /// Creates the info from a `wgpu::SamplerDescriptor`, the label is ignored.
+    pub fn from_wgpu(desc: &wgpu::SamplerDescriptor) -> Self {
+        Self {
+            address_mode_u: desc.address_mode_u,
+            address_mode_v: desc.address_mode_v,
+            address_mode_w: desc.address_mode_w,
+            mag_filter: desc.mag_filter,
+            min_filter: desc.min_filter,
+            mipmap_filter: desc.mipmap_filter,
+            lod_min_clamp: desc.lod_min_clamp,
+            lod_max_clamp: desc.lod_max_clamp,
+            compare: desc.compare,
+            anisotropy_clamp: desc.anisotropy_clamp,
+            border_color: desc.border_color,
+        }
+    }
+
+    /// The binding type that a sampler created with this info must be pushed with.
+    pub fn binding_type(&self) -> SamplerBindingType {
+        let filters = [self.mag_filter, self.min_filter, self.mipmap_filter];
+
+        if self.compare.is_some() {
+            SamplerBindingType::Comparison
+        } else if filters.contains(&FilterMode::Linear) {
+            SamplerBindingType::Filtering
+        } else {
+            SamplerBindingType::NonFiltering
+        }
+    }
+}
+
+impl Default for SamplerInfo {
+    fn default() -> Self {
+        Self::from_wgpu(&wgpu::SamplerDescriptor::default())
+    }
+}
+
 /// Encodes information to determine the appropiate color that should be
 /// returned when sampling an image.
 ///
@@ -29,6 +88,7 @@ pub struct SamplerInfo {
 #[derive(Debug)]
 pub struct Sampler {
     pub(crate) handle: wgpu::Sampler,
+    pub(crate) binding_type: SamplerBindingType,
 }
 
 impl Sampler {
@@ -44,10 +104,23 @@ impl Sampler {
                 address_mode_w: info.address_mode_w,
                 mag_filter: info.mag_filter,
                 min_filter: info.min_filter,
+                mipmap_filter: info.mipmap_filter,
+                lod_min_clamp: info.lod_min_clamp,
+                lod_max_clamp: info.lod_max_clamp,
+                compare: info.compare,
+                anisotropy_clamp: info.anisotropy_clamp,
                 border_color: info.border_color,
-                ..Default::default()
             });
 
-        Self { handle: sampler }
+        Self {
+            handle: sampler,
+            binding_type: info.binding_type(),
+        }
+    }
+
+    /// The binding type the sampler must be pushed with, determined by the info
+    /// it was created with (see `SamplerInfo::binding_type()`).
+    pub fn binding_type(&self) -> SamplerBindingType {
+        self.binding_type
     }
 }
@@ -0,0 +1,202 @@
+use bytemuck::Pod;
+
+use crate::{BindGroup, Buffer, BufferAccess, CommandQueue, Context, Kernel, KernelInfo, Range};
+
+/// Ammount of elements that a single workgroup of the scan kernel processes.
+const ELEMENTS_PER_WORKGROUP: u64 = 512;
+
+/// Element types that can be scanned on the device (`u32`, `i32` and `f32`).
+///
+/// Integer scans wrap on overflow, `f32` scans may differ in the last bits from a
+/// sequential prefix sum since the additions are performed in a different order.
+pub trait ScanElement: Pod {
+    /// Name of the type in wgsl.
+    const WGSL_TYPE: &'static str;
+}
+
+impl ScanElement for u32 {
+    const WGSL_TYPE: &'static str = "u32";
+}
+
+impl ScanElement for i32 {
+    const WGSL_TYPE: &'static str = "i32";
+}
+
+impl ScanElement for f32 {
+    const WGSL_TYPE: &'static str = "f32";
+}
+
+/// Buffers, bind groups and kernels required to scan a (non empty) buffer.
+///
+/// Each level performs a Blelloch scan of blocks of `ELEMENTS_PER_WORKGROUP` elements
+/// and the totals of the blocks are scanned by the next level, until a single block
+/// is left. The scanned totals are then added back to every block from the last level
+/// to the first. Lengths that aren't a multiple of the block size are padded with zeros.
+#[derive(Debug)]
+pub(crate) struct Scan<T> {
+    outputs: Vec<Buffer<T>>,
+    block_sums: Vec<Buffer<T>>,
+    scan_bind_groups: Vec<BindGroup>,
+    add_bind_groups: Vec<BindGroup>,
+    first_kernel: Kernel,
+    block_kernel: Kernel,
+    add_kernel: Option<Kernel>,
+    max_per_dimension: u32,
+}
+
+impl<T: ScanElement> Scan<T> {
+    /// Prepares the scan of `input`, the result is written to `Scan::output()`
+    /// every time that the scan is executed.
+    pub(crate) fn new(context: &Context, input: &Buffer<T>, inclusive: bool) -> Self {
+        let mut outputs = vec![Buffer::empty_like(input)];
+        let mut block_sums = Vec::new();
+        loop {
+            let blocks = outputs[outputs.len() - 1]
+                .len()
+                .div_ceil(ELEMENTS_PER_WORKGROUP);
+
+            block_sums.push(context.buffer::<T>(blocks));
+
+            if blocks == 1 {
+                break;
+            }
+
+            outputs.push(context.buffer::<T>(blocks));
+        }
+
+        let inputs = std::iter::once(input).chain(&block_sums);
+
+        let scan_bind_groups = inputs
+            .zip(&outputs)
+            .zip(&block_sums)
+            .map(|((input, output), sums)| {
+                context
+                    .bind_group_descriptor()
+                    .push_buffer(input, BufferAccess::ReadOnly)
+                    .push_buffer(output, BufferAccess::ReadWrite)
+                    .push_buffer(sums, BufferAccess::ReadWrite)
+                    .into_bind_group()
+            })
+            .collect::<Vec<BindGroup>>();
+
+        let add_bind_groups = outputs
+            .iter()
+            .zip(&outputs[1..])
+            .map(|(values, block_offsets)| {
+                context
+                    .bind_group_descriptor()
+                    .push_buffer(values, BufferAccess::ReadWrite)
+                    .push_buffer(block_offsets, BufferAccess::ReadOnly)
+                    .into_bind_group()
+            })
+            .collect::<Vec<BindGroup>>();
+
+        let prelude = format!(
+            "type Element = {0};\nfn zero() -> Element {{ return {0}(0); }}\n",
+            T::WGSL_TYPE
+        );
+        let scan_program =
+            context.program_from_wgsl(&(prelude.clone() + include_str!("scan.wgsl")));
+        let add_program = context.program_from_wgsl(&(prelude + include_str!("scan_add.wgsl")));
+
+        let first_kernel = context.kernel(&KernelInfo {
+            program: &scan_program,
+            entry_point: if inclusive {
+                "inclusive_scan"
+            } else {
+                "exclusive_scan"
+            },
+            bind_groups: &[&scan_bind_groups[0]],
+            push_constants_range: None,
+        });
+
+        // the block totals are always exclusively scanned to get the offset of each block
+        let block_kernel = context.kernel(&KernelInfo {
+            program: &scan_program,
+            entry_point: "exclusive_scan",
+            bind_groups: &[&scan_bind_groups[0]],
+            push_constants_range: None,
+        });
+
+        let add_kernel = add_bind_groups.first().map(|bind_group| {
+            context.kernel(&KernelInfo {
+                program: &add_program,
+                entry_point: "main",
+                bind_groups: &[bind_group],
+                push_constants_range: None,
+            })
+        });
+
+        let max_per_dimension = context
+            .device
+            .handle
+            .limits()
+            .max_compute_workgroups_per_dimension;
+
+        Self {
+            outputs,
+            block_sums,
+            scan_bind_groups,
+            add_bind_groups,
+            first_kernel,
+            block_kernel,
+            add_kernel,
+            max_per_dimension,
+        }
+    }
+
+    /// Buffer where the result of the scan is written.
+    pub(crate) fn output(&self) -> &Buffer<T> {
+        &self.outputs[0]
+    }
+
+    /// Takes the buffer where the result of the scan is written.
+    pub(crate) fn into_output(mut self) -> Buffer<T> {
+        self.outputs.swap_remove(0)
+    }
+
+    /// Records the commands that perform the scan at the end of `queue`.
+    pub(crate) fn enqueue<'a>(&'a self, queue: CommandQueue<'a>) -> CommandQueue<'a> {
+        let max_per_dimension = self.max_per_dimension;
+
+        let mut queue = queue
+            .enqueue_set_kernel(&self.first_kernel)
+            .enqueue_dispatch(Range::grid(self.block_sums[0].len(), max_per_dimension))
+            .enqueue_set_kernel(&self.block_kernel);
+
+        let scan_levels = self.scan_bind_groups.iter().zip(&self.block_sums);
+        for (bind_group, sums) in scan_levels.skip(1) {
+            queue = queue
+                .enqueue_set_bind_group(0, bind_group)
+                .enqueue_dispatch(Range::grid(sums.len(), max_per_dimension));
+        }
+
+        if let Some(add_kernel) = &self.add_kernel {
+            queue = queue.enqueue_set_kernel(add_kernel);
+
+            let add_levels = self.add_bind_groups.iter().zip(&self.block_sums);
+            for (bind_group, sums) in add_levels.rev() {
+                queue = queue
+                    .enqueue_set_bind_group(0, bind_group)
+                    .enqueue_dispatch(Range::grid(sums.len(), max_per_dimension));
+            }
+        }
+
+        queue
+    }
+}
+
+/// Computes the prefix sum of `buffer` into a new buffer.
+pub(crate) fn scan<T: ScanElement>(
+    context: &Context,
+    buffer: &Buffer<T>,
+    inclusive: bool,
+) -> Buffer<T> {
+    if buffer.is_empty() {
+        return context.buffer(0);
+    }
+
+    let scan = Scan::new(context, buffer, inclusive);
+    scan.enqueue(context.command_queue()).execute();
+    scan.into_output()
+}
@@ -0,0 +1,97 @@
+// `Element` and `zero()` are defined by `scan.rs` for the element type of the
+// buffer being scanned.
+
+struct ComputeInput {
+    @builtin(local_invocation_index) local_index: u32,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+    @builtin(num_workgroups) num_groups: vec3<u32>,
+}
+
+@group(0) @binding(0)
+var<storage, read> input: array<Element>;
+@group(0) @binding(1)
+var<storage, read_write> output: array<Element>;
+@group(0) @binding(2)
+var<storage, read_write> block_sums: array<Element>;
+
+var<workgroup> temp: array<Element, 512>;
+
+fn load(i: u32) -> Element {
+    if (i < arrayLength(&input)) {
+        return input[i];
+    }
+    return zero();
+}
+
+fn store(i: u32, value: Element) {
+    if (i < arrayLength(&output)) {
+        output[i] = value;
+    }
+}
+
+// Blelloch scan of the 512 elements of a block, the total of the block is
+// stored in `block_sums` to be scanned and added in a later pass
+fn scan_block(in: ComputeInput, inclusive: bool) {
+    let group = in.group_id.x + in.group_id.y * in.num_groups.x;
+    let local = in.local_index;
+
+    let a = group * 512u + local;
+    let b = a + 256u;
+    let value_a = load(a);
+    let value_b = load(b);
+
+    temp[local] = value_a;
+    temp[local + 256u] = value_b;
+
+    // up-sweep (reduce) phase
+    var offset = 1u;
+    for (var d = 256u; d > 0u; d = d >> 1u) {
+        workgroupBarrier();
+        if (local < d) {
+            let ai = offset * (2u * local + 1u) - 1u;
+            let bi = offset * (2u * local + 2u) - 1u;
+            temp[bi] = temp[bi] + temp[ai];
+        }
+        offset = offset << 1u;
+    }
+
+    workgroupBarrier();
+    if (local == 0u) {
+        if (group < arrayLength(&block_sums)) {
+            block_sums[group] = temp[511];
+        }
+        temp[511] = zero();
+    }
+
+    // down-sweep phase
+    for (var d = 1u; d < 512u; d = d << 1u) {
+        offset = offset >> 1u;
+        workgroupBarrier();
+        if (local < d) {
+            let ai = offset * (2u * local + 1u) - 1u;
+            let bi = offset * (2u * local + 2u) - 1u;
+            let t = temp[ai];
+            temp[ai] = temp[bi];
+            temp[bi] = temp[bi] + t;
+        }
+    }
+
+    workgroupBarrier();
+    if (inclusive) {
+        store(a, temp[local] + value_a);
+        store(b, temp[local + 256u] + value_b);
+    } else {
+        store(a, temp[local]);
+        store(b, temp[local + 256u]);
+    }
+}
+
+@compute @workgroup_size(256)
+fn exclusive_scan(in: ComputeInput) {
+    scan_block(in, false);
+}
+
+@compute @workgroup_size(256)
+fn inclusive_scan(in: ComputeInput) {
+    scan_block(in, true);
+}
@@ -0,0 +1,32 @@
+// `Element` is aliased by `scan.rs` to the element type of the buffer being scanned.
+
+struct ComputeInput {
+    @builtin(local_invocation_index) local_index: u32,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+    @builtin(num_workgroups) num_groups: vec3<u32>,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> values: array<Element>;
+@group(0) @binding(1)
+var<storage, read> block_offsets: array<Element>;
+
+// adds the scanned total of the previous blocks to every element of a block
+@compute @workgroup_size(256)
+fn main(in: ComputeInput) {
+    let group = in.group_id.x + in.group_id.y * in.num_groups.x;
+    if (group >= arrayLength(&block_offsets)) {
+        return;
+    }
+
+    let offset = block_offsets[group];
+    let a = group * 512u + in.local_index;
+    let b = a + 256u;
+
+    if (a < arrayLength(&values)) {
+        values[a] = values[a] + offset;
+    }
+    if (b < arrayLength(&values)) {
+        values[b] = values[b] + offset;
+    }
+}
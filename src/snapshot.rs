@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use crate::{Buffer, Context};
+
+/// Contents of a set of buffers read back at the same point, see `Context::snapshot()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    buffers: Vec<Vec<u8>>,
+}
+
+/// Differences between a buffer of two snapshots, see `Snapshot::diff()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BufferDiff {
+    /// Index of the buffer in the snapshots.
+    pub buffer: usize,
+    /// Offsets of the bytes that differ, up to the length of the shortest buffer.
+    pub offsets: Vec<usize>,
+    /// Lengths of the buffer in both snapshots.
+    pub lens: (usize, usize),
+}
+
+impl Snapshot {
+    /// Reads the contents of `buffers` in a single submission.
+    pub(crate) fn new(context: &Context, buffers: &[&Buffer<u8>]) -> Self {
+        assert!(
+            buffers
+                .iter()
+                .all(|buffer| Arc::ptr_eq(&buffer.device, &context.device)),
+            "only buffers of the same context can be snapshotted"
+        );
+
+        let mut encoder =
+            context
+                .device
+                .handle
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("Snapshot command encoder"),
+                });
+
+        let staging: Vec<_> = buffers
+            .iter()
+            .map(|buffer| {
+                let size = buffer.len().next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT);
+                (!buffer.is_empty()).then(|| buffer.encode_copy_to_staging(&mut encoder, 0, size))
+            })
+            .collect();
+
+        context
+            .device
+            .queue
+            .submit(std::iter::once(encoder.finish()));
+
+        staging
+            .iter()
+            .flatten()
+            .for_each(|staging| staging.slice(..).map_async(wgpu::MapMode::Read, |_| {}));
+        context.device.handle.poll(wgpu::Maintain::Wait);
+
+        let buffers = buffers
+            .iter()
+            .zip(&staging)
+            .map(|(buffer, staging)| match staging {
+                Some(staging) => {
+                    staging.slice(..).get_mapped_range()[..buffer.len() as usize].to_vec()
+                }
+                None => Vec::new(),
+            })
+            .collect();
+
+        Self { buffers }
+    }
+
+    /// Bytes of every buffer, in the order they were snapshotted.
+    pub fn buffers(&self) -> &[Vec<u8>] {
+        &self.buffers
+    }
+
+    /// Compares the buffers of the snapshot with the ones of `other` (i.e. a golden
+    /// snapshot), returning the buffers that differ.
+    ///
+    /// # Panics
+    ///
+    /// - if the snapshots have a different number of buffers.
+    pub fn diff(&self, other: &Snapshot) -> Vec<BufferDiff> {
+        assert!(
+            self.buffers.len() == other.buffers.len(),
+            "a snapshot of {} buffers can't be compared with one of {}",
+            self.buffers.len(),
+            other.buffers.len(),
+        );
+
+        self.buffers
+            .iter()
+            .zip(&other.buffers)
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+            .map(|(buffer, (a, b))| BufferDiff {
+                buffer,
+                offsets: a
+                    .iter()
+                    .zip(b)
+                    .enumerate()
+                    .filter_map(|(offset, (a, b))| (a != b).then_some(offset))
+                    .collect(),
+                lens: (a.len(), b.len()),
+            })
+            .collect()
+    }
+
+    /// Writes the contents of the snapshot back into `buffers`, which must be the ones
+    /// that were snapshotted (or have the same lengths).
+    ///
+    /// # Panics
+    ///
+    /// - if the number or the lengths of `buffers` don't match the snapshot.
+    /// - if any of `buffers` was created without `BufferUsage::copy_dst`.
+    pub fn restore(&self, buffers: &[&Buffer<u8>]) {
+        assert!(
+            self.buffers.len() == buffers.len(),
+            "a snapshot of {} buffers can't be restored into {} buffers",
+            self.buffers.len(),
+            buffers.len(),
+        );
+
+        for (index, (data, buffer)) in self.buffers.iter().zip(buffers).enumerate() {
+            assert!(
+                data.len() as wgpu::BufferAddress == buffer.len(),
+                "the buffer {index} of the snapshot has {} bytes but the buffer has {}",
+                data.len(),
+                buffer.len(),
+            );
+            buffer.assert_copy_dst();
+
+            if !data.is_empty() {
+                // writes must be a multiple of 4 bytes, the padding of the allocation
+                // is never read back
+                let mut padded = data.clone();
+                padded.resize(
+                    data.len()
+                        .next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT as usize),
+                    0,
+                );
+
+                buffer.device.queue.write_buffer(&buffer.handle, 0, &padded);
+            }
+        }
+    }
+}
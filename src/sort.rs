@@ -0,0 +1,137 @@
+use crate::{scan::Scan, BindGroup, Buffer, BufferAccess, Context, KernelInfo, Range};
+
+/// Bits of the keys sorted by each pass.
+const RADIX_BITS: u32 = 4;
+
+/// Ammount of keys that a single workgroup of the sort kernels processes.
+const KEYS_PER_WORKGROUP: u64 = 256;
+
+/// Sorts `keys` in place with a LSD radix sort, reordering `values` along with them.
+///
+/// Every pass builds the histogram of the digits of each block of keys, scans it to
+/// find where the keys of each block go and scatters them into a temporary buffer.
+/// There's an even number of passes, so the sorted keys end up in the original buffer.
+pub(crate) fn sort_u32(context: &Context, keys: &Buffer<u32>, values: Option<&Buffer<u32>>) {
+    if let Some(values) = values {
+        assert_eq!(
+            keys.len(),
+            values.len(),
+            "keys and values must have the same length"
+        );
+    }
+
+    if keys.is_empty() {
+        return;
+    }
+
+    let blocks = keys.len().div_ceil(KEYS_PER_WORKGROUP);
+    let histograms = context.buffer::<u32>(blocks << RADIX_BITS);
+    let scan = Scan::new(context, &histograms, false);
+
+    let keys_tmp = Buffer::empty_like(keys);
+    let values_tmp = values.map(Buffer::empty_like);
+
+    let shifts = (0..u32::BITS)
+        .step_by(RADIX_BITS as usize)
+        .map(|shift| context.buffer_from_slice(&[shift]))
+        .collect::<Vec<_>>();
+
+    // even passes sort from the original buffers into the temporary ones
+    // and odd passes the other way around
+    let ping_pong = |pass: usize, buffer, tmp| {
+        if pass % 2 == 0 {
+            (buffer, tmp)
+        } else {
+            (tmp, buffer)
+        }
+    };
+
+    let histogram_bind_groups = shifts
+        .iter()
+        .enumerate()
+        .map(|(pass, shift)| {
+            let (src, _) = ping_pong(pass, keys, &keys_tmp);
+
+            context
+                .bind_group_descriptor()
+                .push_buffer(src, BufferAccess::ReadOnly)
+                .push_buffer(shift, BufferAccess::ReadOnly)
+                .push_buffer(&histograms, BufferAccess::ReadWrite)
+                .into_bind_group()
+        })
+        .collect::<Vec<BindGroup>>();
+
+    let scatter_bind_groups = shifts
+        .iter()
+        .enumerate()
+        .map(|(pass, shift)| {
+            let (src, dst) = ping_pong(pass, keys, &keys_tmp);
+
+            let descriptor = context
+                .bind_group_descriptor()
+                .push_buffer(src, BufferAccess::ReadOnly)
+                .push_buffer(shift, BufferAccess::ReadOnly)
+                .push_buffer(scan.output(), BufferAccess::ReadOnly)
+                .push_buffer(dst, BufferAccess::ReadWrite);
+
+            match (values, &values_tmp) {
+                (Some(values), Some(values_tmp)) => {
+                    let (src, dst) = ping_pong(pass, values, values_tmp);
+
+                    descriptor
+                        .push_buffer(src, BufferAccess::ReadOnly)
+                        .push_buffer(dst, BufferAccess::ReadWrite)
+                }
+                _ => descriptor,
+            }
+            .into_bind_group()
+        })
+        .collect::<Vec<BindGroup>>();
+
+    let histogram_program = context.program_from_wgsl(include_str!("sort.wgsl"));
+    let scatter_program = context.program_from_wgsl(include_str!("sort_scatter.wgsl"));
+
+    let histogram_kernel = context.kernel(&KernelInfo {
+        program: &histogram_program,
+        entry_point: "histogram",
+        bind_groups: &[&histogram_bind_groups[0]],
+        push_constants_range: None,
+    });
+
+    let scatter_kernel = context.kernel(&KernelInfo {
+        program: &scatter_program,
+        entry_point: if values.is_some() {
+            "scatter_pairs"
+        } else {
+            "scatter_keys"
+        },
+        bind_groups: &[&scatter_bind_groups[0]],
+        push_constants_range: None,
+    });
+
+    let max_per_dimension = context
+        .device
+        .handle
+        .limits()
+        .max_compute_workgroups_per_dimension;
+    let workgroups = Range::grid(blocks, max_per_dimension);
+
+    let passes = histogram_bind_groups.iter().zip(&scatter_bind_groups);
+
+    passes
+        .fold(
+            context.command_queue(),
+            |queue, (histogram_bind_group, scatter_bind_group)| {
+                let queue = queue
+                    .enqueue_set_kernel(&histogram_kernel)
+                    .enqueue_set_bind_group(0, histogram_bind_group)
+                    .enqueue_dispatch(workgroups);
+
+                scan.enqueue(queue)
+                    .enqueue_set_kernel(&scatter_kernel)
+                    .enqueue_set_bind_group(0, scatter_bind_group)
+                    .enqueue_dispatch(workgroups)
+            },
+        )
+        .execute();
+}
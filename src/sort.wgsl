@@ -0,0 +1,43 @@
+// LSD radix sort of 4 bit digits, each workgroup processes a block of 256 keys.
+//
+// The histograms of the blocks are stored digit-major, such that the exclusive
+// scan of `histograms` yields the position of the first key of each digit in
+// each block.
+
+struct ComputeInput {
+    @builtin(local_invocation_index) local_index: u32,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+    @builtin(num_workgroups) num_groups: vec3<u32>,
+}
+
+@group(0) @binding(0)
+var<storage, read> keys: array<u32>;
+@group(0) @binding(1)
+var<storage, read> shift: u32;
+@group(0) @binding(2)
+var<storage, read_write> histograms: array<u32>;
+
+var<workgroup> counts: array<atomic<u32>, 16>;
+
+@compute @workgroup_size(256)
+fn histogram(in: ComputeInput) {
+    let block = in.group_id.x + in.group_id.y * in.num_groups.x;
+    let num_blocks = arrayLength(&histograms) / 16u;
+    let local = in.local_index;
+    let i = block * 256u + local;
+
+    if (local < 16u) {
+        atomicStore(&counts[local], 0u);
+    }
+    workgroupBarrier();
+
+    if (block < num_blocks && i < arrayLength(&keys)) {
+        let digit = (keys[i] >> shift) & 15u;
+        atomicAdd(&counts[digit], 1u);
+    }
+    workgroupBarrier();
+
+    if (block < num_blocks && local < 16u) {
+        histograms[local * num_blocks + block] = atomicLoad(&counts[local]);
+    }
+}
@@ -0,0 +1,72 @@
+// Stable scatter of the keys (and values) of a block of 256 keys into
+// the position given by the scanned histograms of `sort.wgsl`.
+
+struct ComputeInput {
+    @builtin(local_invocation_index) local_index: u32,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+    @builtin(num_workgroups) num_groups: vec3<u32>,
+}
+
+@group(0) @binding(0)
+var<storage, read> keys_in: array<u32>;
+@group(0) @binding(1)
+var<storage, read> shift: u32;
+@group(0) @binding(2)
+var<storage, read> offsets: array<u32>;
+@group(0) @binding(3)
+var<storage, read_write> keys_out: array<u32>;
+@group(0) @binding(4)
+var<storage, read> values_in: array<u32>;
+@group(0) @binding(5)
+var<storage, read_write> values_out: array<u32>;
+
+var<workgroup> digits: array<u32, 256>;
+
+// position of the key `i` in the sorted output, or `arrayLength(&keys_out)`
+// if the thread doesn't hold a key
+fn destination(in: ComputeInput) -> u32 {
+    let block = in.group_id.x + in.group_id.y * in.num_groups.x;
+    let num_blocks = arrayLength(&offsets) / 16u;
+    let local = in.local_index;
+    let i = block * 256u + local;
+
+    // 16 is not a valid digit, it marks the threads out of range
+    var digit = 16u;
+    if (block < num_blocks && i < arrayLength(&keys_in)) {
+        digit = (keys_in[i] >> shift) & 15u;
+    }
+
+    digits[local] = digit;
+    workgroupBarrier();
+
+    if (digit == 16u) {
+        return arrayLength(&keys_out);
+    }
+
+    // keys with the same digit keep their relative order
+    var rank = 0u;
+    for (var j = 0u; j < local; j++) {
+        rank = rank + select(0u, 1u, digits[j] == digit);
+    }
+
+    return offsets[digit * num_blocks + block] + rank;
+}
+
+@compute @workgroup_size(256)
+fn scatter_keys(in: ComputeInput) {
+    let dst = destination(in);
+    if (dst < arrayLength(&keys_out)) {
+        let i = in.local_index + 256u * (in.group_id.x + in.group_id.y * in.num_groups.x);
+        keys_out[dst] = keys_in[i];
+    }
+}
+
+@compute @workgroup_size(256)
+fn scatter_pairs(in: ComputeInput) {
+    let dst = destination(in);
+    if (dst < arrayLength(&keys_out)) {
+        let i = in.local_index + 256u * (in.group_id.x + in.group_id.y * in.num_groups.x);
+        keys_out[dst] = keys_in[i];
+        values_out[dst] = values_in[i];
+    }
+}
@@ -0,0 +1,155 @@
+use std::{marker::PhantomData, mem, sync::Arc};
+
+use bytemuck::Pod;
+
+use crate::{
+    memory::{Allocation, ResourceKind},
+    Buffer, Context,
+};
+
+/// Host visible buffer meant for streaming uploads (i.e. new inputs every frame), it's
+/// written through a mapping and then copied into a [`Buffer`] on the device.
+///
+/// Unlike `Buffer::write()`, which goes through a staging allocation of wgpu on every
+/// call, the same upload buffer can be reused for every upload.
+///
+/// # Mapping lifecycle
+///
+/// 1. The buffer starts mapped and zero-initialized.
+/// 2. `UploadBuffer::map_write()` returns its elements, waiting for the previous copy
+///    out of the buffer to finish if it's not mapped.
+/// 3. `UploadBuffer::copy_into()` unmaps it and copies its elements into a buffer,
+///    the elements that weren't overwritten keep the values of the previous upload.
+#[derive(Debug)]
+pub struct UploadBuffer<T> {
+    // borrows `handle`, so it must be dropped first
+    view: Option<wgpu::BufferViewMut<'static>>,
+    handle: Box<wgpu::Buffer>,
+    _allocation: Allocation,
+    device: Arc<crate::Device>,
+    len: wgpu::BufferAddress,
+    _marker: PhantomData<Vec<T>>,
+}
+
+impl<T: Pod> UploadBuffer<T> {
+    /// Allocates a mapped upload buffer of `capacity` **elements of T**.
+    ///
+    /// # Panics
+    ///
+    /// - if capacity exceeds the limit of `max_buffer_size` (with a default
+    ///   value of **2^30 bytes** that can be configured in `ContextInfo`).
+    /// - if `capacity * std::mem::size_of::<T>()` overflows a `u64`.
+    pub fn new(context: &Context, capacity: wgpu::BufferAddress) -> Self {
+        let size = capacity
+            .checked_mul(mem::size_of::<T>() as wgpu::BufferAddress)
+            .and_then(|size| size.checked_next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT))
+            .unwrap_or_else(|| panic!("capacity {capacity} overflows buffer size"));
+
+        let handle = Box::new(
+            context
+                .device
+                .handle
+                .create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("Upload buffer"),
+                    size,
+                    usage: wgpu::BufferUsages::MAP_WRITE | wgpu::BufferUsages::COPY_SRC,
+                    mapped_at_creation: true,
+                }),
+        );
+
+        let mut self_ = Self {
+            view: None,
+            _allocation: Allocation::new(&context.device, ResourceKind::Buffer, size),
+            handle,
+            device: Arc::clone(&context.device),
+            len: capacity,
+            _marker: PhantomData,
+        };
+
+        self_.view = Some(self_.mapped_view());
+        self_
+    }
+
+    /// Returns the elements of the buffer to write them, mapping it if it was unmapped
+    /// by `UploadBuffer::copy_into()`.
+    ///
+    /// # Note
+    ///
+    /// Mapping the buffer again blocks until the copies out of it are done, to keep the
+    /// device busy alternate between a couple of upload buffers.
+    pub fn map_write(&mut self) -> &mut [T] {
+        if self.view.is_none() {
+            let slice = self.handle.slice(..);
+            slice.map_async(wgpu::MapMode::Write, move |_| {});
+            self.device.handle.poll(wgpu::Maintain::Wait);
+
+            self.view = Some(self.mapped_view());
+        }
+
+        let size = self.len as usize * mem::size_of::<T>();
+        match &mut self.view {
+            Some(view) => bytemuck::cast_slice_mut(&mut view[..size]),
+            None => unreachable!("the buffer was just mapped"),
+        }
+    }
+
+    /// Unmaps the buffer and copies its elements into the first elements of `dst`.
+    ///
+    /// It takes `&mut self` since the buffer has to be unmapped to be copied, call
+    /// `UploadBuffer::map_write()` again for the next upload.
+    ///
+    /// # Panics
+    ///
+    /// - if `dst` is shorter than the upload buffer.
+    /// - if `dst` was created without `copy_dst` usage.
+    /// - if `dst` was created by another context.
+    pub fn copy_into(&mut self, dst: &Buffer<T>) {
+        assert!(
+            dst.len() >= self.len,
+            "an upload buffer of {} elements can't be copied into a buffer of {} elements",
+            self.len,
+            dst.len(),
+        );
+        assert!(
+            Arc::ptr_eq(&self.device, &dst.device),
+            "upload buffers can only be copied into buffers of the same context"
+        );
+        dst.assert_copy_dst();
+
+        self.view = None;
+        self.handle.unmap();
+
+        if self.len == 0 {
+            return;
+        }
+
+        let mut encoder =
+            self.device
+                .handle
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("Upload command encoder"),
+                });
+
+        encoder.copy_buffer_to_buffer(&self.handle, 0, &dst.handle, 0, self.handle.size());
+        self.device.queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    /// Number of elements of the buffer.
+    pub fn len(&self) -> wgpu::BufferAddress {
+        self.len
+    }
+
+    /// Returns `true` if the buffer has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// View of the whole buffer, which must be mapped.
+    fn mapped_view(&self) -> wgpu::BufferViewMut<'static> {
+        let view = self.handle.slice(..).get_mapped_range_mut();
+
+        // SAFETY: the view borrows the buffer which is boxed so it doesn't move along
+        // with `self`, the view is always dropped before the buffer.
+        unsafe { mem::transmute::<wgpu::BufferViewMut<'_>, wgpu::BufferViewMut<'static>>(view) }
+    }
+}
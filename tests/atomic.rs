@@ -0,0 +1,58 @@
+const HISTOGRAM: &str = r#"
+@group(0) @binding(0)
+var<storage, read> values: array<u32>;
+
+@group(0) @binding(1)
+var<storage, read_write> bins: array<atomic<u32>>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x < arrayLength(&values) {
+        atomicAdd(&bins[values[id.x] % arrayLength(&bins)], 1u);
+    }
+}
+"#;
+
+#[test]
+fn atomic_histogram() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let values: Vec<u32> = (0..10_000u32)
+        .map(|i| i.wrapping_mul(2_654_435_761) >> 7)
+        .collect();
+    let input = context.buffer_from_slice(&values);
+    let bins = context.atomic_buffer::<u32>(16);
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&input, hac::BufferAccess::ReadOnly)
+        .push_buffer(&bins, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(HISTOGRAM);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    kernel.dispatch(hac::Range::d1((values.len() as u32).div_ceil(64)));
+
+    let mut expected = vec![0u32; 16];
+    values
+        .iter()
+        .for_each(|&v| expected[(v % 16) as usize] += 1);
+
+    assert_eq!(bins.read_to_vec(), expected);
+    assert_eq!(bins.read_element(3), expected[3]);
+    assert_eq!(bins.read_element(15), expected[15]);
+}
+
+#[test]
+fn atomic_buffers_start_zeroed() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let counters = context.atomic_buffer::<i32>(5);
+    assert_eq!(counters.read_to_vec(), vec![0; 5]);
+}
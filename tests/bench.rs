@@ -0,0 +1,81 @@
+const DOUBLE: &str = r#"
+@group(0) @binding(0)
+var<storage, read_write> data: array<f32>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    data[id.x] = 2.0 * data[id.x];
+}
+"#;
+
+fn bench_double(info: &hac::ContextInfo) -> (hac::BenchResult, Vec<f32>) {
+    let context = hac::Context::new(info);
+
+    let buffer = context.buffer_from_slice(&[1.0f32; 256]);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(DOUBLE);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    (kernel.bench(4, 5, 2), buffer.read_to_vec())
+}
+
+#[test]
+fn bench_on_the_host() {
+    let (result, data) = bench_double(&hac::ContextInfo::default());
+
+    assert_eq!(result.iterations, 5);
+    assert!(!result.device_timestamps);
+    assert!(result.min <= result.mean && result.mean <= result.max);
+
+    // the warmup dispatches run too
+    assert_eq!(data, vec![128.0; 256]);
+}
+
+#[test]
+fn bench_with_timestamp_queries() {
+    let info = hac::ContextInfo::builder()
+        .features(hac::Features::TIMESTAMP_QUERY)
+        .build();
+
+    if !hac::Context::probe(&info).contains(hac::Features::TIMESTAMP_QUERY) {
+        eprintln!("skipping timestamp queries, not supported by the adapter");
+        return;
+    }
+
+    let (result, data) = bench_double(&info);
+
+    assert_eq!(result.iterations, 5);
+    assert!(result.device_timestamps);
+    assert!(result.min <= result.mean && result.mean <= result.max);
+    assert_eq!(data, vec![128.0; 256]);
+}
+
+#[test]
+#[should_panic(expected = "at least one dispatch")]
+fn bench_without_iterations() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let buffer = context.buffer::<f32>(64);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(DOUBLE);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    kernel.bench(1, 0, 0);
+}
@@ -0,0 +1,166 @@
+const SCALE: &str = r#"
+@group(0) @binding(0)
+var<storage, read> factor: u32;
+@group(0) @binding(1)
+var<storage, read_write> data: array<u32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    data[id.x] = data[id.x] * factor;
+}
+"#;
+
+#[test]
+fn update_buffers_of_a_bind_group() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let factor = context.buffer_from_slice(&[2u32]);
+    let data = context.buffer_from_slice(&[1u32; 128]);
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&factor, hac::BufferAccess::ReadOnly)
+        .push_buffer_slice(&data, 64, 4, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(SCALE);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    kernel.dispatch(hac::Range::d1(4));
+
+    bind_group.update_buffer(0, &[3u32]);
+    bind_group.update_buffer(1, &[1u32, 2]);
+    kernel.dispatch(hac::Range::d1(4));
+
+    let data = data.read_to_vec();
+    assert_eq!(data[63], 1);
+    assert_eq!(data[64..68], [3, 6, 6, 6]);
+    assert_eq!(data[68], 1);
+}
+
+#[test]
+#[should_panic(expected = "overrun the 8 bytes bound at binding 0")]
+fn update_overruns_the_bound_slice() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let data = context.buffer::<u32>(128);
+
+    context
+        .bind_group_descriptor()
+        .push_buffer_slice(&data, 64, 2, hac::BufferAccess::ReadWrite)
+        .into_bind_group()
+        .update_buffer(0, &[0u32; 3]);
+}
+
+#[test]
+#[should_panic(expected = "binding 0 doesn't hold a single buffer")]
+fn update_a_binding_without_buffer() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let sampler = context.sampler(&hac::SamplerInfo::default());
+
+    context
+        .bind_group_descriptor()
+        .push_sampler_auto(&sampler)
+        .into_bind_group()
+        .update_buffer(0, &[0u32]);
+}
+
+#[test]
+fn bind_group_from_entries() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let image = context.image(&hac::ImageInfo::d2(3, 2, hac::ImageFormat::Rgba8Unorm));
+    let sampler = context.sampler(&hac::SamplerInfo::default());
+    let counts = context.buffer_from_slice(&[1u32, 2, 3]);
+    let output = context.buffer::<f32>(3);
+
+    let entries = vec![
+        hac::BindEntry::Image(&image),
+        hac::BindEntry::Sampler(&sampler),
+        hac::BindEntry::buffer(&counts, hac::BufferAccess::ReadOnly),
+        hac::BindEntry::buffer(&output, hac::BufferAccess::ReadWrite),
+    ];
+    let bind_group = hac::BindGroupDescriptor::from_entries(&context, entries).into_bind_group();
+
+    let program = context.program_from_wgsl(
+        r#"
+        @group(0) @binding(0)
+        var image: texture_2d<f32>;
+        @group(0) @binding(1)
+        var i_sampler: sampler;
+        @group(0) @binding(2)
+        var<storage, read> counts: array<u32>;
+        @group(0) @binding(3)
+        var<storage, read_write> output: array<f32>;
+
+        @compute @workgroup_size(1)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            let size = textureDimensions(image);
+            let pixel = textureSampleLevel(image, i_sampler, vec2<f32>(0.5, 0.5), 0.0);
+            output[id.x] = f32(counts[id.x] * u32(size.x)) + pixel.r;
+        }
+        "#,
+    );
+
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    kernel.dispatch(hac::Range::d1(3));
+
+    assert_eq!(output.read_to_vec(), vec![3.0, 6.0, 9.0]);
+}
+
+#[test]
+fn share_a_bind_group_between_kernels() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let factor = context.buffer_from_slice(&[3u32]);
+    let first = context.buffer_from_slice(&[1u32, 2]);
+    let second = context.buffer_from_slice(&[5u32, 7]);
+
+    let shared = context
+        .bind_group_descriptor()
+        .push_buffer(&factor, hac::BufferAccess::ReadOnly)
+        .push_buffer(&first, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+    let other = context
+        .bind_group_descriptor()
+        .push_buffer(&factor, hac::BufferAccess::ReadOnly)
+        .push_buffer(&second, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+    let mismatched = context
+        .bind_group_descriptor()
+        .push_buffer(&second, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(SCALE);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&shared],
+        push_constants_range: None,
+    });
+
+    assert!(shared.is_compatible_with(&kernel, 0));
+    assert!(other.is_compatible_with(&kernel, 0));
+    assert!(!other.is_compatible_with(&kernel, 1));
+    assert!(!mismatched.is_compatible_with(&kernel, 0));
+
+    context
+        .command_queue()
+        .enqueue_set_kernel(&kernel)
+        .enqueue_set_bind_group(0, &other)
+        .enqueue_dispatch(2)
+        .execute();
+
+    assert_eq!(first.read_to_vec(), vec![1, 2]);
+    assert_eq!(second.read_to_vec(), vec![15, 21]);
+}
@@ -0,0 +1,363 @@
+#[test]
+fn len_ignores_the_alignment_padding() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffer = context.buffer_from_slice(&[1u8, 2, 3, 4, 5]);
+    assert_eq!(buffer.len(), 5);
+    assert_eq!(buffer.read_to_vec(), vec![1, 2, 3, 4, 5]);
+
+    let empty = hac::Buffer::empty_like(&buffer);
+    assert_eq!(empty.len(), 5);
+
+    let buffer = context.buffer_from_slice(&[7u16, 8, 9]);
+    assert_eq!(buffer.len(), 3);
+    assert_eq!(buffer.read_to_vec(), vec![7, 8, 9]);
+    assert_eq!(buffer.read_element(2), 9);
+
+    let buffer = context.zeroed_buffer::<u8>(3);
+    assert_eq!(buffer.read_to_vec(), vec![0; 3]);
+}
+
+#[test]
+fn cast_uses_the_logical_size() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffer = context.buffer_from_slice(&[1u16, 2, 3, 4]);
+    let view = buffer.cast::<u32>();
+    assert_eq!(view.len(), 2);
+    assert_eq!(view.read_to_vec(), vec![1 | 2 << 16, 3 | 4 << 16]);
+}
+
+#[test]
+#[should_panic]
+fn cast_to_a_larger_element_must_divide_the_size() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    // 6 bytes padded to 8 on the device
+    let buffer = context.buffer_from_slice(&[1u16, 2, 3]);
+    buffer.cast::<u32>();
+}
+
+#[test]
+fn run_wgsl_1to1_returns_one_output_per_input() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let output = context.run_wgsl_1to1(
+        r#"
+@group(0) @binding(0)
+var<storage, read> input: array<f32>;
+
+@group(0) @binding(1)
+var<storage, read_write> output: array<f32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    output[id.x] = input[id.x] * 2.0;
+}
+"#,
+        "main",
+        &[1.0f32, 2.0, 3.5],
+    );
+
+    assert_eq!(output, vec![2.0, 4.0, 7.0]);
+}
+
+#[test]
+fn read_back_asynchronously() {
+    use pollster::FutureExt as _;
+
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffer = context.buffer_from_slice(&[1u8, 2, 3, 4, 5]);
+    assert_eq!(buffer.read_to_vec_async().block_on(), vec![1, 2, 3, 4, 5]);
+
+    let empty = context.buffer::<f32>(0);
+    assert!(empty.read_to_vec_async().block_on().is_empty());
+}
+
+#[test]
+fn checked_writes() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let buffer = context.buffer::<u32>(4);
+
+    assert_eq!(buffer.try_write(&[1, 2], 2), Ok(()));
+    assert_eq!(
+        buffer.try_write(&[1, 2], 3),
+        Err(hac::WriteError::BufferOverrun {
+            index: 3,
+            len: 2,
+            capacity: 4,
+        })
+    );
+    assert_eq!(buffer.read_to_vec(), vec![0, 0, 1, 2]);
+
+    let bytes = context.buffer::<u8>(8);
+    assert_eq!(
+        bytes.try_write(&[1, 2], 1),
+        Err(hac::WriteError::Unaligned { offset: 1, size: 2 })
+    );
+}
+
+#[test]
+#[should_panic(expected = "overruns a buffer of 4 elements")]
+fn overrunning_writes_panic() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    context.buffer::<u32>(4).write(&[1; 5], 0);
+}
+
+#[test]
+fn map_read_without_copying_into_a_vec() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffer = context.buffer_from_slice(&[1u16, 2, 3]);
+    let mut mapped = buffer.map_read();
+
+    // the copy was taken when mapping
+    buffer.write(&[7, 7], 0);
+
+    let view = mapped.view();
+    assert_eq!(&*view, &[1, 2, 3]);
+    assert_eq!(view.iter().sum::<u16>(), 6);
+    drop(view);
+
+    assert_eq!(&*mapped.view(), &[1, 2, 3]);
+    drop(mapped);
+
+    assert_eq!(&*buffer.map_read().view(), &[7, 7, 3]);
+    assert!(context.buffer::<f32>(0).map_read().view().is_empty());
+}
+
+#[test]
+fn read_back_in_chunks() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let data: Vec<u16> = (0..11).collect();
+    let buffer = context.buffer_from_slice(&data);
+
+    let chunks: Vec<Vec<u16>> = buffer.read_chunks(3).collect();
+    assert_eq!(chunks.len(), 4);
+    assert_eq!(chunks[1], vec![3, 4, 5]);
+    assert_eq!(chunks[3], vec![9, 10]);
+    assert_eq!(chunks.concat(), data);
+
+    assert_eq!(context.buffer::<f32>(0).read_chunks(4).count(), 0);
+}
+
+#[test]
+fn read_back_a_prefix() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffer = context.buffer::<u8>(16);
+    buffer.write(&[1, 2, 3, 4], 0);
+
+    assert_eq!(buffer.read_prefix(3), vec![1, 2, 3]);
+    assert_eq!(buffer.read_prefix(16).len(), 16);
+    assert!(buffer.read_prefix(0).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "can't read 5 elements")]
+fn read_a_prefix_longer_than_the_buffer() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    context.buffer::<u32>(4).read_prefix(5);
+}
+
+#[test]
+fn read_back_an_exact_length() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffer = context.buffer_from_slice(&[1u32, 2, 3]);
+    assert_eq!(buffer.read_exact(3), vec![1, 2, 3]);
+}
+
+#[test]
+#[should_panic(expected = "expected to read 4 elements but the buffer has length 3")]
+fn read_back_an_unexpected_length() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    context.buffer::<u32>(3).read_exact(4);
+}
+
+#[test]
+fn scratch_buffers() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let scratch = context.buffer_with_usage::<u32>(4, hac::BufferUsage::SCRATCH);
+    assert_eq!(scratch.usage(), hac::BufferUsage::SCRATCH);
+    assert_eq!(
+        hac::Buffer::empty_like(&scratch).usage(),
+        hac::BufferUsage::SCRATCH
+    );
+    assert_eq!(
+        scratch.try_write(&[1, 2], 0),
+        Err(hac::WriteError::NotWritable)
+    );
+
+    let upload_only = context.buffer_with_usage::<u32>(
+        4,
+        hac::BufferUsage {
+            copy_src: false,
+            copy_dst: true,
+        },
+    );
+    assert_eq!(upload_only.try_write(&[1, 2], 0), Ok(()));
+    assert_eq!(
+        context.buffer::<u32>(4).usage(),
+        hac::BufferUsage::default()
+    );
+}
+
+#[test]
+#[should_panic(expected = "without copy_src usage")]
+fn read_back_a_scratch_buffer() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    context
+        .buffer_with_usage::<u32>(4, hac::BufferUsage::SCRATCH)
+        .read_to_vec();
+}
+
+#[test]
+#[should_panic(expected = "overflows buffer size")]
+fn capacity_overflowing_the_size() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    context.buffer::<u32>(u64::MAX / 2);
+}
+
+#[test]
+fn output_buffers_can_be_read_through_their_views() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let output = context.buffer_from_slice(&[1u32, 2]).mark_as_output();
+    let view = output.cast::<u16>();
+    drop(output);
+
+    assert_eq!(view.read_to_vec(), vec![1, 0, 2, 0]);
+}
+
+#[test]
+fn buffer_from_an_iterator() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffer = context.buffer_from_iter((0..5u8).map(|i| i * i));
+    assert_eq!(buffer.len(), 5);
+    assert_eq!(buffer.read_to_vec(), vec![0, 1, 4, 9, 16]);
+
+    assert!(context
+        .buffer_from_iter(std::iter::empty::<f32>())
+        .is_empty());
+}
+
+/// Iterator that reports a length of 3 but yields 4 elements.
+struct WrongLength(u32);
+
+impl Iterator for WrongLength {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        self.0 += 1;
+        (self.0 <= 4).then_some(self.0)
+    }
+}
+
+impl ExactSizeIterator for WrongLength {
+    fn len(&self) -> usize {
+        3
+    }
+}
+
+#[test]
+#[should_panic(expected = "the iterator yielded 4 elements but its length was 3")]
+fn buffer_from_an_iterator_with_the_wrong_length() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    context.buffer_from_iter(WrongLength(0));
+}
+
+#[test]
+fn labels_are_shared_with_views() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffer = context.buffer::<u32>(4);
+    assert_eq!(buffer.label(), None);
+
+    let view = buffer.cast::<u8>();
+    buffer.set_label("histogram");
+    assert_eq!(view.label().as_deref(), Some("histogram"));
+
+    view.set_label("prefix sums");
+    assert_eq!(buffer.label().as_deref(), Some("prefix sums"));
+    assert_eq!(buffer.read_to_vec(), vec![0; 4]);
+}
+
+#[test]
+#[should_panic(expected = "the buffer \"scratch\" was created without copy_src usage")]
+fn labels_name_the_buffer_in_panics() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffer = context.buffer_with_usage::<u32>(4, hac::BufferUsage::SCRATCH);
+    buffer.set_label("scratch");
+
+    buffer.read_to_vec();
+}
+
+#[test]
+fn bytes_round_trip() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let state = context.buffer_from_slice(&[[1.0f32, -2.0, 0.5], [3.0, 4.0, -0.25]]);
+    let bytes = state.read_bytes();
+    assert_eq!(bytes.len(), 2 * 12);
+
+    let restored = context.buffer_from_bytes::<[f32; 3]>(&bytes);
+    assert_eq!(restored.len(), 2);
+    assert_eq!(restored.read_to_vec(), state.read_to_vec());
+
+    // the bytes of a slice at an odd offset aren't aligned for u16
+    let unaligned = [0u8, 1, 0, 2, 0];
+    let halves = context.buffer_from_bytes::<u16>(&unaligned[1..]);
+    assert_eq!(halves.read_to_vec(), vec![1, 2]);
+    assert_eq!(halves.read_bytes(), vec![1, 0, 2, 0]);
+}
+
+#[test]
+#[should_panic(expected = "7 bytes can't be split in elements of 4 bytes")]
+fn bytes_must_hold_whole_elements() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    context.buffer_from_bytes::<u32>(&[0; 7]);
+}
+
+#[test]
+fn poll_a_read_until_it_is_ready() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let buffer = context.buffer_from_slice(&[1u32, 2, 3]);
+
+    let poll_read = |mut read: hac::PendingRead<u32>| {
+        (0..1000)
+            .find_map(|_| {
+                let elements = read.try_read();
+                if elements.is_none() {
+                    std::thread::sleep(std::time::Duration::from_millis(1));
+                }
+                elements
+            })
+            .expect("the read back never finished")
+    };
+
+    let mut read = buffer.begin_read();
+    buffer.write(&[4, 5, 6], 0);
+    let later = buffer.begin_read();
+
+    assert_eq!(poll_read(later), vec![4, 5, 6]);
+    assert_eq!(read.try_read(), Some(vec![1, 2, 3]));
+    assert_eq!(read.try_read(), Some(vec![1, 2, 3]));
+
+    assert_eq!(
+        context.buffer::<u32>(0).begin_read().try_read(),
+        Some(vec![])
+    );
+}
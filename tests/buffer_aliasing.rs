@@ -0,0 +1,140 @@
+const ACCUMULATE: &str = r#"
+@group(0) @binding(0)
+var<storage, read_write> input: array<u32>;
+@group(0) @binding(1)
+var<storage, read_write> output: array<u32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    output[id.x] = output[id.x] + input[id.x];
+}
+"#;
+
+const SUM_READ_ONLY: &str = r#"
+@group(0) @binding(0)
+var<storage, read> a: array<u32>;
+@group(0) @binding(1)
+var<storage, read> b: array<u32>;
+@group(0) @binding(2)
+var<storage, read_write> output: array<u32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    output[id.x] = a[id.x] + b[id.x];
+}
+"#;
+
+const SPLIT_GROUPS: &str = r#"
+@group(0) @binding(0)
+var<storage, read> input: array<u32>;
+@group(1) @binding(0)
+var<storage, read_write> output: array<u32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    output[id.x] = input[id.x] * 2u;
+}
+"#;
+
+#[test]
+fn same_buffer_read_write_twice() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let buffer = context.buffer_from_slice(&[1u32, 2, 3]);
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(ACCUMULATE);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    context
+        .command_queue()
+        .enqueue_set_kernel(&kernel)
+        .enqueue_dispatch(3)
+        .execute();
+
+    assert_eq!(buffer.read_to_vec(), vec![2, 4, 6]);
+}
+
+#[test]
+fn same_buffer_read_only_twice() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let input = context.buffer_from_slice(&[1u32, 2, 3]);
+    let output = context.buffer::<u32>(3);
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&input, hac::BufferAccess::ReadOnly)
+        .push_buffer(&input, hac::BufferAccess::ReadOnly)
+        .push_buffer(&output, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(SUM_READ_ONLY);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    context
+        .command_queue()
+        .enqueue_set_kernel(&kernel)
+        .enqueue_dispatch(3)
+        .execute();
+
+    assert_eq!(output.read_to_vec(), vec![2, 4, 6]);
+}
+
+#[test]
+#[should_panic(expected = "read only and read write")]
+fn mixed_access_in_bind_group() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let buffer = context.buffer_from_slice(&[1u32, 2, 3]);
+
+    context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadOnly)
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+}
+
+#[test]
+fn mixed_access_across_bind_groups() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let buffer = context.buffer_from_slice(&[1u32, 2, 3]);
+
+    let input_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadOnly)
+        .into_bind_group();
+    let output_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(SPLIT_GROUPS);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&input_group, &output_group],
+        push_constants_range: None,
+    });
+
+    let result = context
+        .command_queue()
+        .enqueue_set_kernel(&kernel)
+        .enqueue_dispatch(3)
+        .try_execute();
+
+    assert!(result.is_err());
+    assert_eq!(buffer.read_to_vec(), vec![1, 2, 3]);
+}
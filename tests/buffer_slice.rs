@@ -0,0 +1,64 @@
+const DOUBLE: &str = r#"
+@group(0) @binding(0)
+var<storage, read_write> region: array<u32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x < arrayLength(&region) {
+        region[id.x] *= 2u;
+    }
+}
+"#;
+
+#[test]
+fn kernels_only_see_the_bound_slice() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    // 64 u32s = 256 bytes, the largest `min_storage_buffer_offset_alignment`
+    let data: Vec<u32> = (0..192).collect();
+    let buffer = context.buffer_from_slice(&data);
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer_slice(&buffer, 64, 64, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(DOUBLE);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    kernel.dispatch(128);
+
+    let expected: Vec<u32> = data
+        .iter()
+        .map(|&x| if (64..128).contains(&x) { x * 2 } else { x })
+        .collect();
+
+    assert_eq!(buffer.read_to_vec(), expected);
+}
+
+#[test]
+#[should_panic(expected = "out of the bounds")]
+fn slices_must_be_in_bounds() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let buffer = context.buffer::<u32>(128);
+
+    context
+        .bind_group_descriptor()
+        .push_buffer_slice(&buffer, 64, 65, hac::BufferAccess::ReadOnly);
+}
+
+#[test]
+#[should_panic(expected = "must be a multiple of")]
+fn slices_must_be_aligned() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let buffer = context.buffer::<u32>(128);
+
+    context
+        .bind_group_descriptor()
+        .push_buffer_slice(&buffer, 1, 4, hac::BufferAccess::ReadOnly);
+}
@@ -0,0 +1,74 @@
+const ADD_INDEX: &str = r#"
+struct Chunk {
+    offset: u32,
+    len: u32,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> data: array<u32>;
+@group(0) @binding(1)
+var<storage, read> chunk: Chunk;
+
+@compute @workgroup_size(64)
+fn main(
+    @builtin(workgroup_id) wid: vec3<u32>,
+    @builtin(num_workgroups) nwg: vec3<u32>,
+    @builtin(local_invocation_index) lid: u32,
+) {
+    let i = (wid.x + wid.y * nwg.x) * 64u + lid;
+    if i >= chunk.len {
+        return;
+    }
+
+    data[i] = data[i] * 2u + chunk.offset + i;
+}
+"#;
+
+#[test]
+fn read_back_stitches_the_chunks() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let data: Vec<u32> = (0..2500).collect();
+    let buffer = hac::ChunkedBuffer::from_slice(&context, &data, 1000);
+
+    assert_eq!(buffer.len(), 2500);
+    assert_eq!(buffer.chunks().len(), 3);
+    assert_eq!(buffer.chunks()[2].len(), 500);
+    assert_eq!(buffer.read_to_vec(), data);
+}
+
+#[test]
+fn dispatch_every_chunk_with_its_offset() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let data: Vec<u32> = (0..2500).collect();
+    let buffer = hac::ChunkedBuffer::from_slice(&context, &data, 1000);
+
+    let program = context.program_from_wgsl(ADD_INDEX);
+    buffer.dispatch(
+        &context,
+        &hac::ChunkKernelInfo {
+            program: &program,
+            entry_point: "main",
+            access: hac::BufferAccess::ReadWrite,
+            bind_groups: &[],
+            workgroup_size: 64,
+        },
+    );
+
+    let expected: Vec<u32> = data.iter().map(|&x| x * 3).collect();
+    assert_eq!(buffer.read_to_vec(), expected);
+}
+
+#[test]
+fn default_chunks_fit_the_limits() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffer = context.chunked_buffer::<f32>(10);
+    assert_eq!(buffer.chunks().len(), 1);
+    assert_eq!(
+        buffer.chunk_len(),
+        hac::ChunkedBuffer::<f32>::max_chunk_len(&context)
+    );
+    assert_eq!(buffer.read_to_vec(), vec![0.0; 10]);
+}
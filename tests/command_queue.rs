@@ -0,0 +1,251 @@
+const INCREMENT: &str = r#"
+@group(0) @binding(0)
+var<storage, read_write> data: array<u32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    data[id.x] = data[id.x] * 2u + 1u;
+}
+"#;
+
+#[test]
+fn queues_run_in_execution_order() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffer = context.buffer_from_slice(&[0u32; 4]);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(INCREMENT);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    let first = context
+        .command_queue()
+        .enqueue_set_kernel(&kernel)
+        .enqueue_dispatch(4)
+        .execute();
+
+    context
+        .command_queue()
+        .enqueue_set_kernel(&kernel)
+        .enqueue_dispatch(4)
+        .execute_after(first);
+
+    context
+        .command_queue()
+        .enqueue_set_kernel(&kernel)
+        .enqueue_dispatch(4)
+        .execute();
+
+    assert_eq!(buffer.read_to_vec(), vec![7; 4]);
+}
+
+#[test]
+fn dispatch_and_await_the_output() {
+    use pollster::FutureExt as _;
+
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffer = context.buffer_from_slice(&[1u32, 2, 3]);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(INCREMENT);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    let results = kernel.dispatch_and_read(3, &buffer).block_on();
+    assert_eq!(results, vec![3, 5, 7]);
+}
+
+#[test]
+fn writes_are_ordered_with_dispatches() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffer = context.buffer::<u32>(4);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(INCREMENT);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    // written before the dispatch, so it sees the values
+    buffer.write(&[1, 2, 3, 4], 0);
+    kernel.dispatch(4);
+
+    // written after it, so the dispatch can't overwrite them
+    buffer.write(&[10, 20], 2);
+
+    assert_eq!(buffer.read_to_vec(), vec![3, 5, 10, 20]);
+
+    buffer.write(&[0], 0);
+    kernel.dispatch(4);
+
+    assert_eq!(buffer.read_to_vec(), vec![1, 11, 21, 41]);
+}
+
+#[test]
+fn queues_in_parallel_see_the_results_of_the_previous_ones() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let program = context.program_from_wgsl(INCREMENT);
+
+    let buffers = [
+        context.buffer_from_slice(&[0u32; 4]),
+        context.buffer_from_slice(&[1u32; 4]),
+    ];
+    let kernels: Vec<_> = buffers
+        .iter()
+        .map(|buffer| {
+            let bind_group = context
+                .bind_group_descriptor()
+                .push_buffer(buffer, hac::BufferAccess::ReadWrite)
+                .into_bind_group();
+
+            context.kernel(&hac::KernelInfo {
+                program: &program,
+                entry_point: "main",
+                bind_groups: &[&bind_group],
+                push_constants_range: None,
+            })
+        })
+        .collect();
+
+    // the last queue uses the same buffer as the first one so it sees its result
+    context.command_queue_parallel([
+        context
+            .command_queue()
+            .enqueue_set_kernel(&kernels[0])
+            .enqueue_dispatch(4),
+        context
+            .command_queue()
+            .enqueue_set_kernel(&kernels[1])
+            .enqueue_dispatch(4),
+        context
+            .command_queue()
+            .enqueue_set_kernel(&kernels[0])
+            .enqueue_dispatch(4),
+    ]);
+
+    assert_eq!(buffers[0].read_to_vec(), vec![3; 4]);
+    assert_eq!(buffers[1].read_to_vec(), vec![3; 4]);
+}
+
+#[test]
+fn independent_queues_in_parallel() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let program = context.program_from_wgsl(INCREMENT);
+
+    let a = context.buffer_from_slice(&[0u32; 4]);
+    let b = context.buffer_from_slice(&[5u32; 8]);
+    let kernels: Vec<_> = [&a, &b]
+        .into_iter()
+        .map(|buffer| {
+            let bind_group = context
+                .bind_group_descriptor()
+                .push_buffer(buffer, hac::BufferAccess::ReadWrite)
+                .into_bind_group();
+
+            context.kernel(&hac::KernelInfo {
+                program: &program,
+                entry_point: "main",
+                bind_groups: &[&bind_group],
+                push_constants_range: None,
+            })
+        })
+        .collect();
+
+    context.command_queue_parallel([
+        context
+            .command_queue()
+            .enqueue_set_kernel(&kernels[0])
+            .enqueue_dispatch(4),
+        context
+            .command_queue()
+            .enqueue_set_kernel(&kernels[1])
+            .enqueue_dispatch(8),
+    ]);
+
+    assert_eq!(a.read_to_vec(), vec![1; 4]);
+    assert_eq!(b.read_to_vec(), vec![11; 8]);
+}
+
+#[test]
+fn wait_until_the_device_is_idle() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffer = context.buffer_from_slice(&[0u32; 4]);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(INCREMENT);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    (0..8).for_each(|_| kernel.dispatch(4));
+    context.wait_idle();
+
+    // nothing is left in flight
+    assert!(context.poll(false));
+    assert_eq!(buffer.read_to_vec(), vec![255; 4]);
+}
+
+#[test]
+fn record_many_queues_in_one_submission() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffer = context.buffer_from_slice(&[0u32; 4]);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(INCREMENT);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    let recorder = (0..3).fold(context.begin_recording(), |recorder, _| {
+        recorder.record(
+            context
+                .command_queue()
+                .enqueue_set_kernel(&kernel)
+                .enqueue_dispatch(4),
+        )
+    });
+
+    // nothing runs until the recorder is finished
+    assert_eq!(buffer.read_to_vec(), vec![0; 4]);
+
+    recorder.finish();
+
+    assert_eq!(buffer.read_to_vec(), vec![7; 4]);
+}
@@ -0,0 +1,129 @@
+const SQUARE: &str = r#"
+@group(0) @binding(0)
+var<storage, read> input: array<u32>;
+
+@group(0) @binding(1)
+var<storage, read_write> output: array<u32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    output[id.x] = input[id.x] * input[id.x];
+}
+"#;
+
+const ADD_ONE: &str = r#"
+@group(0) @binding(0)
+var<storage, read> input: array<u32>;
+
+@group(0) @binding(1)
+var<storage, read_write> output: array<u32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    output[id.x] = input[id.x] + 1u;
+}
+"#;
+
+fn kernel(
+    context: &hac::Context,
+    source: &str,
+    input: &hac::Buffer<u32>,
+    output: &hac::Buffer<u32>,
+) -> hac::Kernel {
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(input, hac::BufferAccess::ReadOnly)
+        .push_buffer(output, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(source);
+    context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    })
+}
+
+#[test]
+fn nodes_run_in_dependency_order() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let input = context.buffer_from_slice(&[1u32, 2, 3, 4]);
+    let squared = context.buffer::<u32>(4);
+    let output = context.buffer::<u32>(4);
+
+    let square = kernel(&context, SQUARE, &input, &squared);
+    let add_one = kernel(&context, ADD_ONE, &squared, &output);
+
+    // declared in reverse order, the graph runs `square` first
+    let graph = context
+        .compute_graph()
+        .add_node(
+            hac::GraphNode::new(&add_one, 4)
+                .reads(&squared)
+                .writes(&output),
+        )
+        .add_node(
+            hac::GraphNode::new(&square, 4)
+                .reads(&input)
+                .writes(&squared),
+        );
+
+    assert_eq!(graph.passes(), vec![vec![1], vec![0]]);
+    graph.execute();
+
+    assert_eq!(output.read_to_vec(), vec![2, 5, 10, 17]);
+}
+
+#[test]
+fn independent_nodes_share_a_pass() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let input = context.buffer_from_slice(&[1u32, 2, 3]);
+    let squared = context.buffer::<u32>(3);
+    let incremented = context.buffer::<u32>(3);
+
+    let square = kernel(&context, SQUARE, &input, &squared);
+    let add_one = kernel(&context, ADD_ONE, &input, &incremented);
+
+    let graph = context
+        .compute_graph()
+        .add_node(
+            hac::GraphNode::new(&square, 3)
+                .reads(&input)
+                .writes(&squared),
+        )
+        .add_node(
+            hac::GraphNode::new(&add_one, 3)
+                .reads(&input)
+                .writes(&incremented),
+        );
+
+    assert_eq!(graph.passes(), vec![vec![0, 1]]);
+    graph.execute();
+
+    assert_eq!(squared.read_to_vec(), vec![1, 4, 9]);
+    assert_eq!(incremented.read_to_vec(), vec![2, 3, 4]);
+}
+
+#[test]
+#[should_panic(expected = "circular dependencies")]
+fn circular_dependencies_panic() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let a = context.buffer::<u32>(1);
+    let b = context.buffer::<u32>(1);
+    let c = context.buffer::<u32>(1);
+
+    let first = kernel(&context, ADD_ONE, &a, &b);
+    let second = kernel(&context, ADD_ONE, &b, &c);
+    let third = kernel(&context, ADD_ONE, &c, &a);
+
+    context
+        .compute_graph()
+        .add_node(hac::GraphNode::new(&first, 1).reads(&a).writes(&b))
+        .add_node(hac::GraphNode::new(&second, 1).reads(&b).writes(&c))
+        .add_node(hac::GraphNode::new(&third, 1).reads(&c).writes(&a))
+        .passes();
+}
@@ -0,0 +1,233 @@
+use pollster::FutureExt as _;
+
+#[test]
+fn create_a_context_asynchronously() {
+    let context = hac::Context::new_async(&hac::ContextInfo::default())
+        .block_on()
+        .unwrap();
+
+    let buffer = context.buffer_from_slice(&[1u32, 2, 3]);
+    assert_eq!(buffer.read_to_vec(), vec![1, 2, 3]);
+}
+
+#[test]
+fn report_missing_adapters() {
+    let info = hac::ContextInfo::builder()
+        .backends(hac::Backends::empty())
+        .build();
+
+    let result = hac::Context::new_async(&info).block_on();
+    assert!(matches!(result, Err(hac::ContextError::NoAdapter)));
+}
+
+#[test]
+fn probe_the_adapter_features() {
+    let info = hac::ContextInfo::default();
+    let supported = hac::Context::probe(&info);
+
+    let context = hac::Context::new(&info);
+    assert_eq!(context.adapter_features(), supported);
+    assert!(supported.contains(context.features()));
+
+    let none = hac::ContextInfo::builder()
+        .backends(hac::Backends::empty())
+        .build();
+    assert_eq!(hac::Context::probe(&none), hac::Features::empty());
+}
+
+#[test]
+fn clones_share_the_device() {
+    const DOUBLE: &str = r#"
+    @group(0) @binding(0)
+    var<storage, read_write> data: array<u32>;
+
+    @compute @workgroup_size(1)
+    fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+        data[id.x] *= 2u;
+    }
+    "#;
+
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let clone = context.clone();
+
+    let buffer = context.buffer_from_slice(&[1u32, 2, 3]);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let worker = std::thread::spawn(move || {
+        let program = clone.program_from_wgsl(DOUBLE);
+        let kernel = clone.kernel(&hac::KernelInfo {
+            program: &program,
+            entry_point: "main",
+            bind_groups: &[&bind_group],
+            push_constants_range: None,
+        });
+
+        kernel.dispatch(3);
+    });
+
+    worker.join().unwrap();
+    assert_eq!(buffer.read_to_vec(), vec![2, 4, 6]);
+}
+
+#[test]
+#[cfg(feature = "trace")]
+fn record_a_trace() {
+    let trace_dir = std::env::temp_dir().join("hac-trace");
+    std::fs::create_dir_all(&trace_dir).unwrap();
+
+    let info = hac::ContextInfo::builder().trace_path(&trace_dir).build();
+    assert_eq!(info.trace_path.as_deref(), Some(trace_dir.as_path()));
+
+    let context = hac::Context::new(&info);
+    assert_eq!(context.buffer_from_slice(&[1u32]).read_to_vec(), vec![1]);
+    assert!(trace_dir.join("trace.ron").exists());
+}
+
+#[test]
+fn create_a_context_from_an_adapter_index() {
+    let adapters = hac::Context::enumerate_adapters(hac::Backends::all());
+    assert!(!adapters.is_empty());
+    assert!(adapters
+        .iter()
+        .enumerate()
+        .all(|(index, adapter)| adapter.index == index));
+
+    let result = hac::Context::from_adapter_index(
+        hac::Backends::all(),
+        adapters.len(),
+        &wgpu::DeviceDescriptor::default(),
+    );
+    assert!(matches!(
+        result,
+        Err(hac::ContextError::AdapterIndex { index, count }) if index == count
+    ));
+
+    let context = hac::Context::from_adapter_index(
+        hac::Backends::all(),
+        adapters[0].index,
+        &wgpu::DeviceDescriptor::default(),
+    )
+    .unwrap();
+
+    let buffer = context.buffer_from_slice(&[4u32, 2]);
+    assert_eq!(buffer.read_to_vec(), vec![4, 2]);
+}
+
+#[test]
+fn query_format_features() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let rgba8 = context.format_features(hac::ImageFormat::Rgba8Unorm);
+    assert!(rgba8
+        .flags
+        .contains(hac::ImageFormatFeatureFlags::FILTERABLE));
+    assert!(rgba8
+        .allowed_usages
+        .contains(wgpu::TextureUsages::STORAGE_BINDING));
+
+    // without adapter specific features only what WebGPU guarantees is reported
+    let rgba32 = context.format_features(hac::ImageFormat::Rgba32Float);
+    assert!(!rgba32
+        .flags
+        .contains(hac::ImageFormatFeatureFlags::FILTERABLE));
+
+    let srgb = context.format_features(hac::ImageFormat::Rgba8UnormSrgb);
+    assert_eq!(
+        srgb.allowed_usages
+            .contains(wgpu::TextureUsages::STORAGE_BINDING),
+        context.supports_storage(hac::ImageFormat::Rgba8UnormSrgb)
+    );
+}
+
+#[test]
+fn new_buffers_are_zeroed() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    assert_eq!(context.buffer::<u32>(4).read_to_vec(), vec![0; 4]);
+}
+
+#[test]
+fn memory_report_tracks_live_resources() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    assert_eq!(context.memory_report(), hac::MemoryReport::default());
+
+    let buffer = context.buffer::<u32>(64);
+    let view = buffer.cast::<u8>();
+    let image = context.image(
+        &hac::ImageInfo::builder()
+            .size(hac::Extent3d {
+                width: 8,
+                height: 4,
+                depth_or_array_layers: 1,
+            })
+            .format(hac::ImageFormat::Rgba8Unorm)
+            .mip_level_count(2)
+            .build(),
+    );
+
+    let report = context.memory_report();
+    assert_eq!(report.buffers, 256);
+    assert_eq!(report.images, (8 * 4 + 4 * 2) * 4);
+    assert_eq!(report.total(), report.buffers + report.images);
+
+    drop(buffer);
+    assert_eq!(context.memory_report().buffers, 256);
+
+    drop(view);
+    drop(image);
+    assert_eq!(context.memory_report(), hac::MemoryReport::default());
+}
+
+#[test]
+fn best_effort_keeps_the_supported_request() {
+    let (context, fallback) = hac::Context::new_best_effort(&hac::ContextInfo::default()).unwrap();
+
+    assert!(fallback.is_empty());
+    assert_eq!(context.limits(), hac::Limits::default());
+}
+
+#[test]
+fn best_effort_drops_unsupported_features_and_limits() {
+    let info = hac::ContextInfo::builder()
+        .features(hac::Features::all())
+        .limits(hac::Limits {
+            max_buffer_size: u64::MAX,
+            ..Default::default()
+        })
+        .build();
+
+    let (context, fallback) = hac::Context::new_best_effort(&info).unwrap();
+
+    assert_eq!(
+        fallback.disabled_features,
+        hac::Features::all() - context.features()
+    );
+    assert!(!fallback.disabled_features.is_empty());
+    assert!(fallback.clamped_limits.contains(&"max_buffer_size"));
+    assert!(context.limits().max_buffer_size < u64::MAX);
+}
+
+#[test]
+fn wait_idle_with_a_timeout() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffer = context.buffer_from_slice(&[1u32; 64]);
+    buffer.write(&[2; 32], 0);
+
+    assert_eq!(
+        context.wait_idle_timeout(std::time::Duration::from_secs(10)),
+        Ok(())
+    );
+    assert!(context.poll(false));
+
+    let error = hac::TimeoutError {
+        timeout: std::time::Duration::from_millis(250),
+    };
+    assert_eq!(
+        error.to_string(),
+        "the device didn't finish its work within 250ms"
+    );
+}
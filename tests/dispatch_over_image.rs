@@ -0,0 +1,160 @@
+#[test]
+fn dispatch_over_image_covers_every_pixel() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let image = context.image(&hac::ImageInfo::d2(10, 6, hac::ImageFormat::Rgba8Unorm));
+    let covered = context.buffer::<u32>(10 * 6);
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_image(&image)
+        .push_buffer(&covered, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(
+        r#"
+        @group(0) @binding(0)
+        var image: texture_2d<f32>;
+        @group(0) @binding(1)
+        var<storage, read_write> covered: array<u32>;
+
+        @compute @workgroup_size(8, 8)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            let size = textureDimensions(image);
+            if id.x >= u32(size.x) || id.y >= u32(size.y) {
+                return;
+            }
+
+            covered[id.x + id.y * u32(size.x)] += 1u;
+        }
+        "#,
+    );
+
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    assert_eq!(kernel.workgroup_size(), Some([8, 8, 1]));
+    assert_eq!(kernel.workgroups_over_image(&image), hac::Range::d2(2, 1));
+
+    kernel.dispatch_over_image(&image);
+
+    assert_eq!(covered.read_to_vec(), vec![1; 10 * 6]);
+}
+
+#[test]
+fn workgroups_over_3d_images() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let volume = context.image(&hac::ImageInfo::d3(5, 4, 9, hac::ImageFormat::Rgba8Unorm));
+    let covered = context.buffer::<u32>(5 * 4 * 9);
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&covered, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(
+        r#"
+        @group(0) @binding(0)
+        var<storage, read_write> covered: array<u32>;
+
+        @compute @workgroup_size(4, 4, 4)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            if id.x >= 5u || id.y >= 4u || id.z >= 9u {
+                return;
+            }
+
+            covered[id.x + (id.y + id.z * 4u) * 5u] += 1u;
+        }
+        "#,
+    );
+
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    assert_eq!(
+        kernel.workgroups_over_image(&volume),
+        hac::Range::d3(2, 1, 3)
+    );
+
+    kernel.dispatch_over_image(&volume);
+
+    assert_eq!(covered.read_to_vec(), vec![1; 5 * 4 * 9]);
+}
+
+#[test]
+fn dispatch_over_image_pushes_its_size() {
+    let info = hac::ContextInfo::builder()
+        .features(hac::Features::PUSH_CONSTANTS)
+        .limits(hac::Limits {
+            max_push_constant_size: 16,
+            ..Default::default()
+        })
+        .build();
+
+    if !hac::Context::probe(&info).contains(hac::Features::PUSH_CONSTANTS) {
+        eprintln!("skipping: the adapter doesn't support push constants");
+        return;
+    }
+
+    let context = hac::Context::new(&info);
+
+    let image = context.image(&hac::ImageInfo::d2(10, 6, hac::ImageFormat::Rgba8Unorm));
+    let covered = context.buffer::<u32>(10 * 6);
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&covered, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    // signed since the gl backend can't set unsigned push constants, the layout is the same
+    let program = context.program_from_wgsl(
+        r#"
+        struct Dispatch {
+            size: vec4<i32>,
+        }
+
+        var<push_constant> dispatch: Dispatch;
+
+        @group(0) @binding(0)
+        var<storage, read_write> covered: array<u32>;
+
+        @compute @workgroup_size(8, 8)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            let size = vec3<u32>(dispatch.size.xyz);
+            if any(id >= size) {
+                return;
+            }
+
+            covered[id.x + id.y * size.x] += 1u;
+        }
+        "#,
+    );
+
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    // the size is the only push constant of the kernel
+    kernel.dispatch_over_image_with_size(&image, 0);
+
+    assert_eq!(covered.read_to_vec(), vec![1; 10 * 6]);
+
+    // the conventional offset is past the push constants range of the kernel
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        kernel.dispatch_over_image_with_size(&image, hac::IMAGE_SIZE_OFFSET);
+    }));
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,56 @@
+const SCALE: &str = r#"
+@group(0) @binding(0)
+var<storage, read_write> window: array<u32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    window[id.x] = window[id.x] * 10u;
+}
+"#;
+
+#[test]
+fn dispatch_each_window_of_a_dynamic_buffer() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    // three windows of 64 u32s = 256 bytes, the largest `min_storage_buffer_offset_alignment`
+    let data: Vec<u32> = (0..192).collect();
+    let buffer = context.buffer_from_slice(&data);
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_dynamic_buffer(&buffer, 64, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(SCALE);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    let offsets = [[256], [512]];
+
+    offsets
+        .iter()
+        .fold(
+            // the kernel sets the window at offset 0
+            context
+                .command_queue()
+                .enqueue_set_kernel(&kernel)
+                .enqueue_dispatch(64),
+            |queue, offset| {
+                queue
+                    .enqueue_set_bind_group_with_offsets(0, &bind_group, offset)
+                    .enqueue_dispatch(32)
+            },
+        )
+        .execute();
+
+    let expected: Vec<u32> = data
+        .iter()
+        .map(|&x| if x < 64 || x % 64 < 32 { x * 10 } else { x })
+        .collect();
+
+    assert_eq!(buffer.read_to_vec(), expected);
+}
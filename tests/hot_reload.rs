@@ -0,0 +1,71 @@
+const KERNEL: &str = r#"
+@group(0) @binding(0)
+var<storage, read_write> data: array<u32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    data[id.x] = data[id.x] + 1u;
+}
+"#;
+
+#[test]
+fn reload_a_program_and_rebuild_its_kernel() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffer = context.buffer::<u32>(3);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let mut program = context.program_from_wgsl(KERNEL);
+    let mut kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    kernel.dispatch(3);
+
+    // an invalid edit keeps the previous program
+    assert!(program.reload_from_wgsl(&context, "fn main( {").is_err());
+    kernel.rebuild(&context, &program).unwrap();
+    kernel.dispatch(3);
+
+    program
+        .reload_from_wgsl(&context, &KERNEL.replace("+ 1u", "* 10u"))
+        .unwrap();
+    kernel.rebuild(&context, &program).unwrap();
+    kernel.dispatch(3);
+
+    assert_eq!(buffer.read_to_vec(), vec![20; 3]);
+}
+
+#[test]
+fn rebuilding_with_other_bindings_fails() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffer = context.buffer::<u32>(3);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let mut program = context.program_from_wgsl(KERNEL);
+    let mut kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    program
+        .reload_from_wgsl(&context, &KERNEL.replace("@binding(0)", "@binding(1)"))
+        .unwrap();
+
+    assert!(kernel.rebuild(&context, &program).is_err());
+
+    kernel.dispatch(3);
+    assert_eq!(buffer.read_to_vec(), vec![1; 3]);
+}
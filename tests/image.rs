@@ -0,0 +1,553 @@
+use pollster::FutureExt as _;
+
+#[test]
+fn empty_image_with_another_format() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let original = context.image(
+        &hac::ImageInfo::builder()
+            .size(hac::Extent3d {
+                width: 8,
+                height: 4,
+                depth_or_array_layers: 1,
+            })
+            .format(hac::ImageFormat::Rgba8Unorm)
+            .mip_level_count(2)
+            .build(),
+    );
+
+    let scratch = hac::Image::empty_with_format(&original, hac::ImageFormat::Rgba16Float);
+
+    assert_eq!(scratch.format(), hac::ImageFormat::Rgba16Float);
+    assert_eq!(scratch.size(), original.size());
+    assert_eq!(scratch.dimension(), original.dimension());
+    assert_eq!(scratch.mip_level_count(), 2);
+    assert_eq!(scratch.read_to_vec(), vec![0; 8 * 4 * 8]);
+}
+
+#[test]
+#[should_panic(expected = "require the")]
+fn formats_without_their_features_are_rejected() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let original = context.image(&hac::ImageInfo::default());
+
+    hac::Image::empty_with_format(&original, hac::ImageFormat::Bc1RgbaUnorm);
+}
+
+fn multisampled_image(context: &hac::Context) -> hac::Image {
+    context.image(
+        &hac::ImageInfo::builder()
+            .size(hac::Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            })
+            .sample_count(4)
+            .build(),
+    )
+}
+
+#[test]
+fn resolve_multisampled_image() {
+    let instance = wgpu::Instance::new(wgpu::Backends::all());
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions::default())
+        .block_on()
+        .unwrap();
+
+    // wgpu-hal 0.14 doesn't blit the samples of render passes without draws on gles
+    if adapter.get_info().backend == wgpu::Backend::Gl {
+        eprintln!("skipping multisampled image resolve, not supported on the gl backend");
+        return;
+    }
+
+    let context = hac::Context::from_wgpu_adapter(&adapter, &wgpu::DeviceDescriptor::default());
+    let multisampled = multisampled_image(&context);
+    assert_eq!(multisampled.sample_count(), 4);
+
+    let resolved = context.image(&hac::ImageInfo::builder().size(multisampled.size()).build());
+    resolved.write(
+        &[255; 4 * 4 * 4],
+        hac::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: std::num::NonZeroU32::new(4 * 4),
+            rows_per_image: None,
+        },
+        resolved.size(),
+    );
+
+    // the samples of the multisampled image are zero-initialized
+    multisampled.resolve_to(&resolved);
+
+    assert_eq!(resolved.read_to_vec(), vec![0; 4 * 4 * 4]);
+}
+
+#[test]
+fn multisampled_images_keep_their_sample_count() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let multisampled = multisampled_image(&context);
+
+    let scratch = hac::Image::empty_like(&multisampled);
+
+    assert_eq!(multisampled.sample_count(), 4);
+    assert_eq!(scratch.sample_count(), 4);
+}
+
+#[test]
+#[should_panic(expected = "resolve them first")]
+fn multisampled_images_cant_be_read() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    multisampled_image(&context).read_to_vec();
+}
+
+#[test]
+fn load_multisampled_image_in_kernel() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let multisampled = multisampled_image(&context);
+    let output = context.buffer::<f32>(4);
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_image(&multisampled)
+        .push_buffer(&output, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(
+        r#"
+        @group(0) @binding(0)
+        var image: texture_multisampled_2d<f32>;
+        @group(0) @binding(1)
+        var<storage, read_write> output: array<f32>;
+
+        @compute @workgroup_size(4)
+        fn main(@builtin(local_invocation_index) i: u32) {
+            output[i] = textureLoad(image, vec2<i32>(0, 0), i32(i)).r;
+        }
+        "#,
+    );
+
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    kernel.dispatch(hac::Range::d1(1));
+
+    assert_eq!(output.read_to_vec(), vec![0.0; 4]);
+}
+
+#[test]
+fn checked_image_writes() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let image = context.image(
+        &hac::ImageInfo::builder()
+            .size(hac::Extent3d {
+                width: 2,
+                height: 2,
+                depth_or_array_layers: 1,
+            })
+            .build(),
+    );
+
+    let layout = hac::ImageDataLayout {
+        offset: 0,
+        bytes_per_row: std::num::NonZeroU32::new(2 * 4),
+        rows_per_image: None,
+    };
+
+    assert_eq!(image.try_write(&[7; 16], layout, image.size()), Ok(()));
+    assert_eq!(
+        image.try_write(&[7; 15], layout, image.size()),
+        Err(hac::WriteError::DataOverrun {
+            len: 15,
+            required: 16,
+        })
+    );
+    assert!(matches!(
+        image.try_write_region(
+            &[7; 16],
+            layout,
+            0,
+            hac::Origin3d { x: 1, y: 0, z: 0 },
+            image.size()
+        ),
+        Err(hac::WriteError::RegionOutOfBounds { .. })
+    ));
+    assert!(matches!(
+        image.try_write_region(&[7; 16], layout, 1, hac::Origin3d::ZERO, image.size()),
+        Err(hac::WriteError::MipLevel {
+            mip_level: 1,
+            mip_level_count: 1,
+        })
+    ));
+
+    assert_eq!(image.read_to_vec(), vec![7; 16]);
+}
+
+#[test]
+fn size_helpers() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let flat = context.image(&hac::ImageInfo::d2(8, 4, hac::ImageFormat::R32Float));
+    assert_eq!((flat.width(), flat.height(), flat.depth()), (8, 4, 1));
+    assert_eq!(flat.dimension(), hac::ImageDimension::D2);
+    assert_eq!(flat.format(), hac::ImageFormat::R32Float);
+
+    let volume = context.image(&hac::ImageInfo::d3(4, 2, 3, hac::ImageFormat::Rgba8Unorm));
+    assert_eq!((volume.width(), volume.height(), volume.depth()), (4, 2, 3));
+    assert_eq!(volume.dimension(), hac::ImageDimension::D3);
+}
+
+#[test]
+fn read_bgra_as_rgba() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let bgra = context.image(&hac::ImageInfo::d2(2, 1, hac::ImageFormat::Bgra8Unorm));
+    let layout = hac::ImageDataLayout {
+        offset: 0,
+        bytes_per_row: std::num::NonZeroU32::new(8),
+        rows_per_image: None,
+    };
+    bgra.write(&[1, 2, 3, 4, 5, 6, 7, 8], layout, bgra.size());
+
+    assert_eq!(bgra.read_to_vec(), vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(
+        bgra.read_to_vec_as(hac::ChannelOrder::Bgra),
+        vec![1, 2, 3, 4, 5, 6, 7, 8]
+    );
+    assert_eq!(
+        bgra.read_to_vec_as(hac::ChannelOrder::Rgba),
+        vec![3, 2, 1, 4, 7, 6, 5, 8]
+    );
+
+    let rgba = context.image(&hac::ImageInfo::d2(1, 1, hac::ImageFormat::Rgba16Uint));
+    let layout = hac::ImageDataLayout {
+        offset: 0,
+        bytes_per_row: std::num::NonZeroU32::new(8),
+        rows_per_image: None,
+    };
+    rgba.write(&[1, 0, 2, 0, 3, 0, 4, 0], layout, rgba.size());
+
+    assert_eq!(
+        rgba.read_to_vec_as(hac::ChannelOrder::Bgra),
+        vec![3, 0, 2, 0, 1, 0, 4, 0]
+    );
+}
+
+#[test]
+#[should_panic(expected = "don't have 4 channels")]
+fn read_two_channels_as_rgba() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    context
+        .image(&hac::ImageInfo::d2(1, 1, hac::ImageFormat::Rg8Unorm))
+        .read_to_vec_as(hac::ChannelOrder::Rgba);
+}
+
+#[test]
+fn read_mip_levels() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let image = context.image(
+        &hac::ImageInfo::builder()
+            .size(hac::Extent3d {
+                width: 5,
+                height: 3,
+                depth_or_array_layers: 1,
+            })
+            .format(hac::ImageFormat::R32Uint)
+            .mip_level_count(3)
+            .build(),
+    );
+
+    // level 1 is 2x1 and level 2 is 1x1 (halved rounding down, at least 1)
+    let level1: Vec<u8> = bytemuck::cast_slice(&[7u32, 8]).to_vec();
+    let level2: Vec<u8> = bytemuck::cast_slice(&[9u32]).to_vec();
+    let layout = hac::ImageDataLayout {
+        offset: 0,
+        bytes_per_row: std::num::NonZeroU32::new(8),
+        rows_per_image: None,
+    };
+    image.write_region(
+        &level1,
+        layout,
+        1,
+        hac::Origin3d::ZERO,
+        image.mip_level_size(1),
+    );
+    image.write_region(
+        &level2,
+        layout,
+        2,
+        hac::Origin3d::ZERO,
+        image.mip_level_size(2),
+    );
+
+    assert_eq!(image.read_mip_to_vec(0), vec![0; 5 * 3 * 4]);
+    assert_eq!(image.read_mip_to_vec(1), level1);
+    assert_eq!(image.read_mip_to_vec(2), level2);
+}
+
+#[test]
+fn duplicate_every_mip_level() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let image = context.image(
+        &hac::ImageInfo::builder()
+            .size(hac::Extent3d {
+                width: 2,
+                height: 2,
+                depth_or_array_layers: 1,
+            })
+            .format(hac::ImageFormat::R32Uint)
+            .mip_level_count(2)
+            .build(),
+    );
+
+    let level0: Vec<u8> = bytemuck::cast_slice(&[1u32, 2, 3, 4]).to_vec();
+    let level1: Vec<u8> = bytemuck::cast_slice(&[5u32]).to_vec();
+    let layout = hac::ImageDataLayout {
+        offset: 0,
+        bytes_per_row: std::num::NonZeroU32::new(8),
+        rows_per_image: None,
+    };
+    image.write(&level0, layout, image.size());
+    image.write_region(
+        &level1,
+        layout,
+        1,
+        hac::Origin3d::ZERO,
+        image.mip_level_size(1),
+    );
+
+    let copy = hac::Image::duplicate(&image);
+    image.write(&[0; 16], layout, image.size());
+
+    assert_eq!(copy.mip_level_count(), 2);
+    assert_eq!(copy.read_mip_to_vec(0), level0);
+    assert_eq!(copy.read_mip_to_vec(1), level1);
+    assert_eq!(image.read_to_vec(), vec![0; 16]);
+}
+
+#[test]
+#[should_panic(expected = "mip level 2 is out of bounds")]
+fn read_a_missing_mip_level() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    context
+        .image(
+            &hac::ImageInfo::builder()
+                .size(hac::Extent3d {
+                    width: 4,
+                    height: 4,
+                    depth_or_array_layers: 1,
+                })
+                .format(hac::ImageFormat::R32Uint)
+                .mip_level_count(2)
+                .build(),
+        )
+        .read_mip_to_vec(2);
+}
+
+#[test]
+fn resize_image_with_nearest_filtering() {
+    let instance = wgpu::Instance::new(wgpu::Backends::all());
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions::default())
+        .block_on()
+        .unwrap();
+
+    // wgpu-hal 0.14 doesn't support storage images in compute shaders on gles
+    if adapter.get_info().backend == wgpu::Backend::Gl {
+        eprintln!("skipping image resize, not supported on the gl backend");
+        return;
+    }
+
+    let context = hac::Context::from_wgpu_adapter(&adapter, &wgpu::DeviceDescriptor::default());
+
+    let pixels = [
+        [255, 0, 0, 255],
+        [0, 255, 0, 255],
+        [0, 0, 255, 255],
+        [255; 4],
+    ];
+    let image = context.image(&hac::ImageInfo::d2(2, 2, hac::ImageFormat::Rgba8Unorm));
+    image.write(
+        pixels.concat().as_slice(),
+        hac::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: std::num::NonZeroU32::new(2 * 4),
+            rows_per_image: None,
+        },
+        image.size(),
+    );
+
+    let size = |side| hac::Extent3d {
+        width: side,
+        height: side,
+        depth_or_array_layers: 1,
+    };
+
+    let upscaled = context.resize_image(&image, size(4), hac::FilterMode::Nearest, false);
+    assert_eq!((upscaled.width(), upscaled.height()), (4, 4));
+
+    let expected = [0, 0, 1, 1, 0, 0, 1, 1, 2, 2, 3, 3, 2, 2, 3, 3]
+        .map(|i: usize| pixels[i])
+        .concat();
+    assert_eq!(upscaled.read_to_vec(), expected);
+
+    let downscaled = context.resize_image(&upscaled, size(2), hac::FilterMode::Nearest, false);
+    assert_eq!(downscaled.read_to_vec(), pixels.concat());
+
+    let flipped = context.resize_image(&image, size(2), hac::FilterMode::Nearest, true);
+    assert_eq!(
+        flipped.read_to_vec(),
+        [2, 3, 0, 1].map(|i| pixels[i]).concat()
+    );
+}
+
+#[test]
+#[should_panic(expected = "images can only be resized with float formats")]
+fn resize_image_without_storage_support() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let image = context.image(&hac::ImageInfo::d2(2, 2, hac::ImageFormat::Bgra8Unorm));
+
+    context.resize_image(&image, image.size(), hac::FilterMode::Linear, false);
+}
+
+#[test]
+fn image_from_rows_with_a_stride() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    // 2x2 pixels with rows aligned to 12 bytes, the last 4 bytes of every row are padding
+    let data = [
+        1, 2, 3, 4, 5, 6, 7, 8, 0, 0, 0, 0, 9, 10, 11, 12, 13, 14, 15, 16, 0, 0, 0, 0,
+    ];
+    let image = context.image_from_raw(
+        &data,
+        hac::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: std::num::NonZeroU32::new(12),
+            rows_per_image: None,
+        },
+        hac::Extent3d {
+            width: 2,
+            height: 2,
+            depth_or_array_layers: 1,
+        },
+        hac::ImageFormat::Rgba8Uint,
+    );
+
+    assert_eq!(image.read_to_vec(), (1..=16).collect::<Vec<u8>>());
+}
+
+#[test]
+fn luminance_histogram() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let layout = |bytes_per_pixel: u32| hac::ImageDataLayout {
+        offset: 0,
+        bytes_per_row: std::num::NonZeroU32::new(2 * bytes_per_pixel),
+        rows_per_image: None,
+    };
+
+    // black, white, gray and red have luminances of 0, 1, ~0.5 and ~0.21
+    let colors = [
+        [0, 0, 0, 255],
+        [255; 4],
+        [128, 128, 128, 255],
+        [255, 0, 0, 255],
+    ];
+    let image = context.image(&hac::ImageInfo::d2(2, 2, hac::ImageFormat::Rgba8Unorm));
+    image.write(&colors.concat(), layout(4), image.size());
+
+    assert_eq!(context.image_histogram(&image, 4), vec![2, 0, 1, 1]);
+    assert_eq!(context.image_histogram(&image, 1), vec![4]);
+
+    // integer values are normalized by the largest value of the channel
+    let image = context.image(&hac::ImageInfo::d2(2, 2, hac::ImageFormat::R8Uint));
+    image.write(&[0, 64, 200, 255], layout(1), image.size());
+
+    assert_eq!(context.image_histogram(&image, 4), vec![1, 1, 0, 2]);
+}
+
+#[test]
+#[should_panic(expected = "at least one bin")]
+fn histogram_without_bins() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let image = context.image(&hac::ImageInfo::d2(2, 2, hac::ImageFormat::Rgba8Unorm));
+
+    context.image_histogram(&image, 0);
+}
+
+#[test]
+#[should_panic(expected = "the image \"albedo\" has 1 levels")]
+fn labels_name_the_image_in_panics() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let image = context.image(&hac::ImageInfo::d2(4, 4, hac::ImageFormat::Rgba8Unorm));
+    assert_eq!(image.label(), None);
+
+    image.set_label("albedo");
+    assert_eq!(image.label().as_deref(), Some("albedo"));
+
+    image.read_mip_to_vec(1);
+}
+
+#[test]
+fn filled_images() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let rgba = context.image_filled(
+        hac::Extent3d {
+            width: 3,
+            height: 2,
+            depth_or_array_layers: 1,
+        },
+        hac::ImageFormat::Rgba8Unorm,
+        [1.0, 0.0, 1.0, 1.0],
+    );
+    assert_eq!(rgba.read_to_vec(), [255, 0, 255, 255].repeat(3 * 2));
+
+    let red = context.image_filled(
+        hac::Extent3d {
+            width: 64,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        hac::ImageFormat::R32Float,
+        [0.25, 0.0, 0.0, 0.0],
+    );
+    let pixels: Vec<f32> = bytemuck::cast_slice(&red.read_to_vec()).to_vec();
+    assert_eq!(pixels, vec![0.25; 64]);
+}
+
+#[test]
+fn filled_3d_images() {
+    let instance = wgpu::Instance::new(wgpu::Backends::all());
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions::default())
+        .block_on()
+        .unwrap();
+
+    if adapter.get_info().backend == wgpu::Backend::Gl {
+        eprintln!("skipping filled 3D image, the gl backend can't write storage images");
+        return;
+    }
+
+    let context = hac::Context::from_wgpu_adapter(&adapter, &wgpu::DeviceDescriptor::default());
+
+    let volume = context.image_filled(
+        hac::Extent3d {
+            width: 5,
+            height: 3,
+            depth_or_array_layers: 6,
+        },
+        hac::ImageFormat::Rgba8Uint,
+        [1.0, 2.0, 3.0, 4.0],
+    );
+
+    assert_eq!(volume.read_to_vec(), [1, 2, 3, 4].repeat(5 * 3 * 6));
+}
@@ -0,0 +1,49 @@
+use pollster::FutureExt as _;
+
+#[test]
+fn read_back_every_layer_of_3d_images() {
+    let instance = wgpu::Instance::new(wgpu::Backends::all());
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions::default())
+        .block_on()
+        .unwrap();
+
+    // wgpu-hal 0.14 ignores the z origin of texture copies on gles so only the first
+    // layer of 3D images can be read back there.
+    if adapter.get_info().backend == wgpu::Backend::Gl {
+        eprintln!("skipping 3D image read back, not supported on the gl backend");
+        return;
+    }
+
+    let context = hac::Context::from_wgpu_adapter(&adapter, &wgpu::DeviceDescriptor::default());
+
+    let size = hac::Extent3d {
+        width: 3,
+        height: 2,
+        depth_or_array_layers: 4,
+    };
+
+    let image = context.image(
+        &hac::ImageInfo::builder()
+            .size(size)
+            .format(hac::ImageFormat::R32Uint)
+            .build(),
+    );
+
+    let texels: Vec<u32> = (0..3 * 2 * 4).collect();
+    image.write(
+        hac::cast_slice(&texels),
+        hac::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: std::num::NonZeroU32::new(3 * 4),
+            rows_per_image: std::num::NonZeroU32::new(2),
+        },
+        size,
+    );
+
+    assert_eq!(image.read_to_vec(), hac::cast_slice::<u32, u8>(&texels));
+
+    let copy = hac::Image::duplicate(&image);
+    assert_eq!(copy.size(), size);
+    assert_eq!(copy.read_to_vec(), hac::cast_slice::<u32, u8>(&texels));
+}
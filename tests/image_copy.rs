@@ -0,0 +1,144 @@
+fn image_3x2(context: &hac::Context) -> hac::Image {
+    let size = hac::Extent3d {
+        width: 3,
+        height: 2,
+        depth_or_array_layers: 1,
+    };
+
+    let image = context.image(
+        &hac::ImageInfo::builder()
+            .size(size)
+            .format(hac::ImageFormat::R32Uint)
+            .build(),
+    );
+
+    let texels: Vec<u32> = (1..=6).collect();
+    image.write(
+        hac::cast_slice(&texels),
+        hac::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: std::num::NonZeroU32::new(3 * 4),
+            rows_per_image: std::num::NonZeroU32::new(2),
+        },
+        size,
+    );
+
+    image
+}
+
+fn unpad(image: &hac::Image, padded: &[u8]) -> Vec<u32> {
+    let stride = image.padded_layout().bytes_per_row.unwrap().get() as usize;
+
+    padded
+        .chunks_exact(stride)
+        .flat_map(|row| hac::cast_slice::<u8, u32>(&row[..3 * 4]).to_vec())
+        .collect()
+}
+
+#[test]
+fn copy_an_image_into_a_buffer() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let image = image_3x2(&context);
+
+    assert_eq!(image.padded_size(), 2 * 256);
+
+    let buffer = context.buffer::<u8>(image.padded_size());
+    image.copy_to_buffer(&buffer);
+
+    assert_eq!(
+        unpad(&image, &buffer.read_to_vec()),
+        (1..=6).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn enqueue_an_image_copy() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let image = image_3x2(&context);
+
+    let buffer = context.buffer::<u32>(image.padded_size() / 4);
+    let bytes = buffer.cast::<u8>();
+
+    context
+        .command_queue()
+        .enqueue_copy_image_to_buffer(&image, &bytes)
+        .execute();
+
+    assert_eq!(
+        unpad(&image, &bytes.read_to_vec()),
+        (1..=6).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+#[should_panic(expected = "can't hold an image")]
+fn the_buffer_must_fit_the_padded_image() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let image = image_3x2(&context);
+
+    let buffer = context.buffer::<u8>(6 * 4);
+    image.copy_to_buffer(&buffer);
+}
+
+fn padded_pixels() -> Vec<u8> {
+    // two rows of 3 u32s padded to 256 bytes
+    let mut padded = vec![0u8; 2 * 256];
+    padded[..12].copy_from_slice(hac::cast_slice(&[1u32, 2, 3]));
+    padded[256..268].copy_from_slice(hac::cast_slice(&[4u32, 5, 6]));
+    padded
+}
+
+#[test]
+fn write_an_image_from_a_buffer() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let image = image_3x2(&context);
+    let empty = hac::Image::empty_like(&image);
+
+    let buffer = context.buffer_from_slice(&padded_pixels());
+    empty.write_from_buffer(&buffer, empty.padded_layout());
+
+    assert_eq!(empty.read_to_vec(), image.read_to_vec());
+}
+
+#[test]
+fn enqueue_a_buffer_copy_into_an_image() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let image = image_3x2(&context);
+    let empty = hac::Image::empty_like(&image);
+
+    let buffer = context.buffer_from_slice(&padded_pixels());
+
+    context
+        .command_queue()
+        .enqueue_copy_buffer_to_image(&buffer, empty.padded_layout(), &empty)
+        .execute();
+
+    assert_eq!(empty.read_to_vec(), image.read_to_vec());
+}
+
+#[test]
+#[should_panic(expected = "too small for the image")]
+fn the_buffer_must_hold_the_whole_image() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let image = image_3x2(&context);
+
+    let buffer = context.buffer::<u8>(256 + 8);
+    image.write_from_buffer(&buffer, image.padded_layout());
+}
+
+#[test]
+fn reinterpret_the_format_through_a_buffer() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let linear = context.image(&hac::ImageInfo::d2(2, 1, hac::ImageFormat::Rgba8Unorm));
+    let layout = linear.padded_layout();
+    let texels = [10u8, 20, 30, 40, 50, 60, 70, 80];
+    linear.write(&texels, layout, linear.size());
+
+    let srgb = hac::Image::empty_with_format(&linear, hac::ImageFormat::Rgba8UnormSrgb);
+    let buffer = context.buffer::<u8>(linear.padded_size());
+    linear.copy_to_buffer(&buffer);
+    srgb.write_from_buffer(&buffer, layout);
+
+    assert_eq!(srgb.read_to_vec(), texels);
+}
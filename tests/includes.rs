@@ -0,0 +1,79 @@
+fn resolve(path: &str) -> String {
+    match path {
+        "main.wgsl" => r#"
+            #include "double.wgsl"
+            #include "square.wgsl"
+
+            @group(0) @binding(0)
+            var<storage, read_write> data: array<u32>;
+
+            @compute @workgroup_size(1)
+            fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+                data[id.x] = square(double(data[id.x]));
+            }
+        "#
+        .into(),
+        "double.wgsl" => r#"
+            #include "common.wgsl"
+
+            fn double(x: u32) -> u32 {
+                return x * TWO;
+            }
+        "#
+        .into(),
+        "square.wgsl" => r#"
+            #include "common.wgsl"
+
+            fn square(x: u32) -> u32 {
+                return x * x;
+            }
+        "#
+        .into(),
+        "common.wgsl" => "let TWO: u32 = 2u;".into(),
+        "a.wgsl" => "#include \"b.wgsl\"".into(),
+        "b.wgsl" => "#include \"a.wgsl\"".into(),
+        "bad.wgsl" => "#include common.wgsl".into(),
+        _ => panic!("unknown file {path}"),
+    }
+}
+
+#[test]
+fn shared_includes_are_expanded_once() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let data = context.buffer_from_slice(&[1u32, 2, 3]);
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&data, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl_with_includes("main.wgsl", resolve);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    context
+        .command_queue()
+        .enqueue_set_kernel(&kernel)
+        .enqueue_dispatch(3)
+        .execute();
+
+    assert_eq!(data.read_to_vec(), vec![4, 16, 36]);
+}
+
+#[test]
+#[should_panic(expected = "circular #include of \"a.wgsl\"")]
+fn circular_includes() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    context.program_from_wgsl_with_includes("a.wgsl", resolve);
+}
+
+#[test]
+#[should_panic(expected = "malformed include")]
+fn malformed_include() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    context.program_from_wgsl_with_includes("bad.wgsl", resolve);
+}
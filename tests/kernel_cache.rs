@@ -0,0 +1,58 @@
+const SOURCE: &str = "@compute @workgroup_size(1) fn main() {}";
+
+#[test]
+fn cached_kernels_share_the_pipeline() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let program = context.program_from_wgsl(SOURCE);
+
+    let info = hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[],
+        push_constants_range: None,
+    };
+
+    context.kernel_cached(&info);
+    context.kernel_cached(&info);
+    assert_eq!(context.kernel_cache_len(), 1);
+
+    context.clear_kernel_cache();
+    assert_eq!(context.kernel_cache_len(), 0);
+}
+
+#[test]
+fn kernel_cache_is_bounded() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    for _ in 0..hac::KERNEL_CACHE_CAPACITY + 8 {
+        let program = context.program_from_wgsl(SOURCE);
+
+        context.kernel_cached(&hac::KernelInfo {
+            program: &program,
+            entry_point: "main",
+            bind_groups: &[],
+            push_constants_range: None,
+        });
+    }
+
+    assert_eq!(context.kernel_cache_len(), hac::KERNEL_CACHE_CAPACITY);
+}
+
+#[test]
+fn reloading_a_program_drops_its_cached_pipelines() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let mut program = context.program_from_wgsl(SOURCE);
+
+    for _ in 0..4 {
+        context.kernel_cached(&hac::KernelInfo {
+            program: &program,
+            entry_point: "main",
+            bind_groups: &[],
+            push_constants_range: None,
+        });
+
+        program.reload_from_wgsl(&context, SOURCE).unwrap();
+    }
+
+    assert_eq!(context.kernel_cache_len(), 0);
+}
@@ -0,0 +1,343 @@
+use pollster::FutureExt as _;
+
+const SCALE: &str = r#"
+struct Params {
+    scale: f32,
+    offset: f32,
+}
+
+var<push_constant> params: Params;
+
+@group(0) @binding(0)
+var<storage, read_write> data: array<f32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    data[id.x] = data[id.x] * params.scale + params.offset;
+}
+"#;
+
+fn push_constants_context() -> Option<hac::Context> {
+    let info = hac::ContextInfo::builder()
+        .features(hac::Features::PUSH_CONSTANTS)
+        .limits(hac::Limits {
+            max_push_constant_size: 16,
+            ..Default::default()
+        })
+        .build();
+
+    if !hac::Context::probe(&info).contains(hac::Features::PUSH_CONSTANTS) {
+        eprintln!("skipping push constants, not supported by the adapter");
+        return None;
+    }
+
+    Some(hac::Context::new(&info))
+}
+
+#[test]
+fn set_typed_push_constants() {
+    let Some(context) = push_constants_context() else {
+        return;
+    };
+
+    let buffer = context.buffer_from_slice(&[1.0f32, 2.0, 3.0]);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(SCALE);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: Some(0..8),
+    });
+
+    context
+        .command_queue()
+        .enqueue_set_kernel(&kernel)
+        .enqueue_set_push_constants_typed(&[2.0f32, 1.0])
+        .enqueue_dispatch(3)
+        .execute();
+
+    assert_eq!(buffer.read_to_vec(), vec![3.0, 5.0, 7.0]);
+}
+
+#[test]
+fn reject_push_constants_larger_than_the_range() {
+    let Some(context) = push_constants_context() else {
+        return;
+    };
+
+    let buffer = context.buffer::<f32>(1);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(SCALE);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: Some(0..8),
+    });
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        context
+            .command_queue()
+            .enqueue_set_kernel(&kernel)
+            .enqueue_set_push_constants_typed(&[1.0f32; 3]);
+    }));
+
+    assert!(result.is_err());
+}
+
+#[test]
+#[should_panic(expected = "a kernel must be set")]
+fn push_constants_need_a_kernel() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    context
+        .command_queue()
+        .enqueue_set_push_constants_typed(&[1.0f32]);
+}
+
+#[test]
+fn push_constants_range_must_cover_the_program() {
+    let Some(context) = push_constants_context() else {
+        return;
+    };
+
+    let program = context.program_from_wgsl(SCALE);
+    assert_eq!(program.push_constant_size("main"), Some(8));
+    assert_eq!(program.push_constant_size("missing"), None);
+
+    let buffer = context.buffer::<f32>(4);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    for range in [Some(0..4), Some(4..12)] {
+        let error = context
+            .try_kernel(&hac::KernelInfo {
+                program: &program,
+                entry_point: "main",
+                bind_groups: &[&bind_group],
+                push_constants_range: range,
+            })
+            .unwrap_err();
+
+        assert!(error.to_string().contains("uses 8 bytes of push constants"));
+    }
+
+    assert!(context
+        .try_kernel(&hac::KernelInfo {
+            program: &program,
+            entry_point: "main",
+            bind_groups: &[&bind_group],
+            push_constants_range: Some(0..8),
+        })
+        .is_ok());
+}
+
+#[test]
+fn reflect_the_push_constants_range() {
+    let Some(context) = push_constants_context() else {
+        return;
+    };
+
+    let buffer = context.buffer_from_slice(&[1.0f32, 2.0]);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(SCALE);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    context
+        .command_queue()
+        .enqueue_set_kernel(&kernel)
+        .enqueue_set_push_constants_typed(&[3.0f32, 1.0])
+        .enqueue_dispatch(2)
+        .execute();
+
+    assert_eq!(buffer.read_to_vec(), vec![4.0, 7.0]);
+}
+
+#[test]
+fn dispatch_with_a_base_workgroup() {
+    const FILL: &str = r#"
+// the gl backend doesn't support unsigned push constants, same bits for small bases
+struct Dispatch {
+    base: vec4<i32>,
+}
+
+var<push_constant> dispatch: Dispatch;
+
+@group(0) @binding(0)
+var<storage, read_write> data: array<u32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let id = gid + vec3<u32>(dispatch.base.xyz);
+    data[id.x] = id.x + 1u;
+}
+"#;
+
+    let Some(context) = push_constants_context() else {
+        return;
+    };
+
+    let buffer = context.buffer_from_slice(&[0u32; 8]);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(FILL);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: Some(0..16),
+    });
+
+    context
+        .command_queue()
+        .enqueue_set_kernel(&kernel)
+        .enqueue_dispatch_offset(3, 4, hac::BASE_WORKGROUP_OFFSET)
+        .execute();
+
+    assert_eq!(buffer.read_to_vec(), vec![0, 0, 0, 0, 5, 6, 7, 0]);
+}
+
+#[test]
+fn dispatch_with_a_base_workgroup_after_other_push_constants() {
+    const FILL: &str = r#"
+struct Dispatch {
+    value: vec4<i32>,
+    base: vec4<i32>,
+}
+
+var<push_constant> dispatch: Dispatch;
+
+@group(0) @binding(0)
+var<storage, read_write> data: array<i32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let id = gid + vec3<u32>(dispatch.base.xyz);
+    data[id.x] = dispatch.value.x;
+}
+"#;
+
+    let instance = wgpu::Instance::new(wgpu::Backends::all());
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions::default())
+        .block_on()
+        .unwrap();
+
+    // wgpu-hal 0.14 reads the push constants set at a non-zero offset from the wrong
+    // place on gles
+    if adapter.get_info().backend == wgpu::Backend::Gl
+        || !adapter.features().contains(hac::Features::PUSH_CONSTANTS)
+    {
+        eprintln!("skipping push constants at a non-zero offset, not supported by the adapter");
+        return;
+    }
+
+    let context = hac::Context::from_wgpu_adapter(
+        &adapter,
+        &wgpu::DeviceDescriptor {
+            features: hac::Features::PUSH_CONSTANTS,
+            limits: hac::Limits {
+                max_push_constant_size: 32,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = context.buffer_from_slice(&[0i32; 8]);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(FILL);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: Some(0..32),
+    });
+
+    context
+        .command_queue()
+        .enqueue_set_kernel(&kernel)
+        .enqueue_set_push_constants_typed(&[7i32, 0, 0, 0])
+        .enqueue_dispatch_offset(2, 5, 16)
+        .execute();
+
+    assert_eq!(buffer.read_to_vec(), vec![0, 0, 0, 0, 0, 7, 7, 0]);
+}
+
+#[test]
+fn dispatch_large_past_the_workgroup_limit() {
+    const FILL: &str = r#"
+// the gl backend doesn't support unsigned push constants, same bits for small offsets
+struct Tile {
+    offset: vec4<i32>,
+}
+
+var<push_constant> tile: Tile;
+
+@group(0) @binding(0)
+var<storage, read_write> data: array<u32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(workgroup_id) wid: vec3<u32>) {
+    let id = wid + vec3<u32>(tile.offset.xyz);
+    data[id.x] = id.x + 1u;
+}
+"#;
+
+    let Some(context) = push_constants_context() else {
+        return;
+    };
+
+    // split in a tile of 65535 workgroups (the default limit) and another one of 4465
+    let n = 70_000;
+    assert!(n > context.limits().max_compute_workgroups_per_dimension);
+
+    let buffer = context.zeroed_buffer::<u32>(n as u64);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(FILL);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: Some(0..16),
+    });
+
+    context
+        .command_queue()
+        .enqueue_set_kernel(&kernel)
+        .enqueue_dispatch_large(n, 0)
+        .execute();
+
+    assert_eq!(buffer.read_to_vec(), (1..=n).collect::<Vec<_>>());
+}
@@ -0,0 +1,48 @@
+// sizes around the 512 elements reduced by every workgroup and its multiple passes
+const SIZES: [usize; 8] = [1, 511, 512, 513, 262144, 262145, 300000, 0];
+
+fn values(n: usize) -> Vec<f32> {
+    (0..n).map(|i| (i % 7) as f32).collect()
+}
+
+#[test]
+fn reduce_sum() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    for n in SIZES {
+        let data = values(n);
+        let buffer = context.buffer_from_slice(&data);
+
+        assert_eq!(
+            context.reduce_sum(&buffer),
+            data.iter().sum::<f32>(),
+            "n = {n}"
+        );
+    }
+}
+
+#[test]
+fn reduce_min_max() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    for n in SIZES.into_iter().filter(|&n| n > 0) {
+        // the extremes are placed in the last element to catch a missed tail
+        let mut data = values(n);
+        data[n - 1] = -3.0;
+        let buffer = context.buffer_from_slice(&data);
+        assert_eq!(context.reduce_min(&buffer), -3.0, "n = {n}");
+
+        data[n - 1] = 42.0;
+        let buffer = context.buffer_from_slice(&data);
+        assert_eq!(context.reduce_max(&buffer), 42.0, "n = {n}");
+    }
+}
+
+#[test]
+fn reduce_empty() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let buffer = context.buffer::<f32>(0);
+
+    assert_eq!(context.reduce_min(&buffer), f32::INFINITY);
+    assert_eq!(context.reduce_max(&buffer), f32::NEG_INFINITY);
+}
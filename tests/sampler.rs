@@ -0,0 +1,79 @@
+#[test]
+fn binding_type_follows_the_info() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let nearest = context.sampler(&hac::SamplerInfo::default());
+    let linear = context.sampler(&hac::SamplerInfo {
+        min_filter: hac::FilterMode::Linear,
+        ..Default::default()
+    });
+    let comparison = context.sampler(&hac::SamplerInfo {
+        compare: Some(hac::CompareFunction::Less),
+        ..Default::default()
+    });
+
+    assert_eq!(
+        nearest.binding_type(),
+        hac::SamplerBindingType::NonFiltering
+    );
+    assert_eq!(linear.binding_type(), hac::SamplerBindingType::Filtering);
+    assert_eq!(
+        comparison.binding_type(),
+        hac::SamplerBindingType::Comparison
+    );
+}
+
+#[test]
+fn push_sampler_auto_matches_the_kernel() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let sampler = context.sampler(&hac::SamplerInfo {
+        mag_filter: hac::FilterMode::Linear,
+        min_filter: hac::FilterMode::Linear,
+        ..Default::default()
+    });
+    let image = context.image(&hac::ImageInfo::default());
+    let output = context.buffer::<f32>(4);
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_image(&image)
+        .push_sampler_auto(&sampler)
+        .push_buffer(&output, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(
+        r#"
+        @group(0) @binding(0)
+        var image: texture_2d<f32>;
+        @group(0) @binding(1)
+        var i_sampler: sampler;
+        @group(0) @binding(2)
+        var<storage, read_write> output: array<f32>;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            let pixel = textureSampleLevel(image, i_sampler, vec2<f32>(0.5, 0.5), 0.0);
+            output[0] = pixel.r;
+            output[1] = pixel.g;
+            output[2] = pixel.b;
+            output[3] = pixel.a;
+        }
+        "#,
+    );
+
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    context
+        .command_queue()
+        .enqueue_set_kernel(&kernel)
+        .enqueue_dispatch(1)
+        .execute();
+
+    assert_eq!(output.read_to_vec(), vec![0.0; 4]);
+}
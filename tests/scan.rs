@@ -0,0 +1,62 @@
+// sizes around the 512 elements scanned by every workgroup and the recursive block sums
+const SIZES: [usize; 8] = [0, 1, 511, 512, 513, 262144, 262145, 300000];
+
+fn exclusive(data: &[u32]) -> Vec<u32> {
+    data.iter()
+        .scan(0, |sum, &x| {
+            let prev = *sum;
+            *sum += x;
+            Some(prev)
+        })
+        .collect()
+}
+
+#[test]
+fn exclusive_scan() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    for n in SIZES {
+        let data: Vec<u32> = (0..n as u32).map(|i| i % 13).collect();
+        let buffer = context.buffer_from_slice(&data);
+
+        let result = context.exclusive_scan(&buffer).read_to_vec();
+        assert_eq!(result, exclusive(&data), "n = {n}");
+    }
+}
+
+#[test]
+fn inclusive_scan() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    for n in SIZES {
+        let data: Vec<i32> = (0..n as i32).map(|i| i % 5 - 2).collect();
+        let buffer = context.buffer_from_slice(&data);
+
+        let expected: Vec<i32> = data
+            .iter()
+            .scan(0, |sum, &x| {
+                *sum += x;
+                Some(*sum)
+            })
+            .collect();
+
+        let result = context.inclusive_scan(&buffer).read_to_vec();
+        assert_eq!(result, expected, "n = {n}");
+    }
+}
+
+#[test]
+fn float_scan() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    // small integers are exact in f32 so the result doesn't depend on the order of the sums
+    let data: Vec<f32> = (0..1000).map(|i| (i % 3) as f32).collect();
+    let buffer = context.buffer_from_slice(&data);
+
+    let expected: Vec<f32> = exclusive(&data.iter().map(|&x| x as u32).collect::<Vec<_>>())
+        .into_iter()
+        .map(|x| x as f32)
+        .collect();
+
+    assert_eq!(context.exclusive_scan(&buffer).read_to_vec(), expected);
+}
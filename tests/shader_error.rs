@@ -0,0 +1,61 @@
+#[test]
+fn report_syntax_errors_with_their_location() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let source = "@compute @workgroup_size(1)\nfn main() {\n    let x = ;\n}\n";
+    let error = context.try_program_from_wgsl(source).unwrap_err();
+
+    let location = error.location.unwrap();
+    assert_eq!(location.line, 3);
+    assert!(!error.message.is_empty());
+}
+
+#[test]
+fn report_validation_errors() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let source = r#"
+    @compute @workgroup_size(1)
+    fn main() {
+        let x: u32 = 1.0;
+    }
+    "#;
+
+    assert!(context.try_program_from_wgsl(source).is_err());
+}
+
+#[test]
+fn valid_programs_can_be_used_in_kernels() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let buffer = context.buffer::<u32>(2);
+
+    let program = context
+        .try_program_from_wgsl(
+            r#"
+            @group(0) @binding(0)
+            var<storage, read_write> data: array<u32>;
+
+            @compute @workgroup_size(1)
+            fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+                data[id.x] = id.x + 5u;
+            }
+            "#,
+        )
+        .unwrap();
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    context
+        .kernel(&hac::KernelInfo {
+            program: &program,
+            entry_point: "main",
+            bind_groups: &[&bind_group],
+            push_constants_range: None,
+        })
+        .dispatch(2);
+
+    assert_eq!(buffer.read_to_vec(), vec![5, 6]);
+}
@@ -0,0 +1,63 @@
+const INCREMENT_ODD: &str = r#"
+@group(0) @binding(0)
+var<storage, read_write> data: array<u32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x % 2u == 1u {
+        data[id.x] += 1u;
+    }
+}
+"#;
+
+#[test]
+fn diff_a_kernel_against_a_snapshot() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let data = context.buffer_from_slice(&[0u32; 4]);
+    let bytes = context.buffer_from_slice(&[1u8, 2, 3]);
+    let empty = context.buffer::<u8>(0);
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&data, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(INCREMENT_ODD);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    let view = data.cast::<u8>();
+    let before = context.snapshot(&[&view, &bytes, &empty]);
+    assert_eq!(before.buffers(), [vec![0; 16], vec![1, 2, 3], vec![]]);
+    assert!(before.diff(&before).is_empty());
+
+    kernel.dispatch(4);
+
+    let after = context.snapshot(&[&view, &bytes, &empty]);
+    assert_eq!(
+        after.diff(&before),
+        vec![hac::BufferDiff {
+            buffer: 0,
+            offsets: vec![4, 12],
+            lens: (16, 16),
+        }]
+    );
+
+    before.restore(&[&view, &bytes, &empty]);
+    assert_eq!(data.read_to_vec(), vec![0; 4]);
+    assert_eq!(bytes.read_to_vec(), vec![1, 2, 3]);
+}
+
+#[test]
+#[should_panic(expected = "the buffer 0 of the snapshot has 4 bytes but the buffer has 8")]
+fn restore_into_buffers_of_other_lengths() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let snapshot = context.snapshot(&[&context.buffer::<u8>(4)]);
+    snapshot.restore(&[&context.buffer::<u8>(8)]);
+}
@@ -0,0 +1,53 @@
+// sizes around the 256 keys of every workgroup and the 512 elements of the scans
+const SIZES: [usize; 9] = [0, 1, 255, 256, 511, 512, 513, 262145, 300000];
+
+/// Deterministic pseudo random keys covering every bit.
+fn keys(n: usize) -> Vec<u32> {
+    let mut state = 0x2545_f491_u32;
+    (0..n)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        })
+        .collect()
+}
+
+#[test]
+fn sort_u32() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    for n in SIZES {
+        let mut data = keys(n);
+        let buffer = context.buffer_from_slice(&data);
+
+        context.sort_u32(&buffer);
+        data.sort_unstable();
+
+        assert_eq!(buffer.read_to_vec(), data, "n = {n}");
+    }
+}
+
+#[test]
+fn sort_u32_by_key_is_stable() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    for n in SIZES {
+        // few distinct keys so that the stability of the values is checked
+        let data: Vec<u32> = keys(n).into_iter().map(|k| k % 16).collect();
+        let values: Vec<u32> = (0..n as u32).collect();
+
+        let keys_buffer = context.buffer_from_slice(&data);
+        let values_buffer = context.buffer_from_slice(&values);
+
+        context.sort_u32_by_key(&keys_buffer, &values_buffer);
+
+        let mut expected: Vec<(u32, u32)> = data.into_iter().zip(values).collect();
+        expected.sort_by_key(|&(key, _)| key);
+        let (expected_keys, expected_values): (Vec<_>, Vec<_>) = expected.into_iter().unzip();
+
+        assert_eq!(keys_buffer.read_to_vec(), expected_keys, "n = {n}");
+        assert_eq!(values_buffer.read_to_vec(), expected_values, "n = {n}");
+    }
+}
@@ -0,0 +1,54 @@
+#[test]
+fn srgb_formats_are_not_storage() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    assert!(context.supports_storage(hac::ImageFormat::Rgba8Unorm));
+    assert!(context.supports_storage(hac::ImageFormat::R32Float));
+    assert!(!context.supports_storage(hac::ImageFormat::Rgba8UnormSrgb));
+}
+
+#[test]
+#[should_panic(expected = "can't be used for storage")]
+fn push_srgb_storage_image() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let image = context.image(
+        &hac::ImageInfo::builder()
+            .format(hac::ImageFormat::Rgba8UnormSrgb)
+            .build(),
+    );
+
+    context
+        .bind_group_descriptor()
+        .push_storage_image(&image, hac::StorageImageAccess::WriteOnly);
+}
+
+#[test]
+fn push_storage_image_with_its_own_format() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let image = context.image(&hac::ImageInfo::default());
+
+    context
+        .bind_group_descriptor()
+        .push_storage_image_with_format(
+            &image,
+            hac::StorageImageAccess::WriteOnly,
+            Some(hac::ImageFormat::Rgba8Unorm),
+        )
+        .into_bind_group();
+}
+
+#[test]
+#[should_panic(expected = "can't be bound for storage as R32Uint")]
+fn push_storage_image_with_incompatible_format() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let image = context.image(&hac::ImageInfo::default());
+
+    context
+        .bind_group_descriptor()
+        .push_storage_image_with_format(
+            &image,
+            hac::StorageImageAccess::WriteOnly,
+            Some(hac::ImageFormat::R32Uint),
+        );
+}
@@ -0,0 +1,118 @@
+const PARTICLES: &str = r#"
+struct Particle {
+    position: vec3<f32>,
+    velocity: vec3<f32>,
+    mass: f32,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> particles: array<Particle>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let particle = particles[id.x];
+    particles[id.x].position = particle.position + particle.velocity * particle.mass;
+}
+"#;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Particle {
+    position: [f32; 3],
+    _padding: f32,
+    velocity: [f32; 3],
+    mass: f32,
+}
+
+unsafe impl bytemuck::Zeroable for Particle {}
+unsafe impl bytemuck::Pod for Particle {}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct PackedParticle {
+    position: [f32; 3],
+    velocity: [f32; 3],
+    mass: f32,
+    _padding: f32,
+}
+
+const PARTICLE_OFFSETS: [usize; 3] = [0, 16, 28];
+const PACKED_OFFSETS: [usize; 3] = [0, 12, 24];
+
+#[test]
+fn reflect_struct_layouts() {
+    let layout = hac::StructLayout::from_wgsl(PARTICLES, "Particle").unwrap();
+
+    assert_eq!(layout.stride, 32);
+    assert_eq!(
+        layout
+            .members
+            .iter()
+            .map(|member| member.offset)
+            .collect::<Vec<_>>(),
+        vec![0, 16, 28]
+    );
+    assert_eq!(layout.members[2].name.as_deref(), Some("mass"));
+
+    assert!(hac::StructLayout::from_wgsl(PARTICLES, "Missing").is_none());
+}
+
+#[test]
+fn check_buffers_against_the_program() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let program = context.program_from_wgsl(PARTICLES);
+    let layout = program.struct_layout("Particle").unwrap();
+
+    let particle = Particle {
+        position: [1.0, 2.0, 3.0],
+        _padding: 0.0,
+        velocity: [1.0, 1.0, 1.0],
+        mass: 2.0,
+    };
+
+    let particles =
+        hac::Buffer::from_slice_checked(&context, &[particle; 2], &layout, &PARTICLE_OFFSETS)
+            .unwrap();
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&particles, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    kernel.dispatch(hac::Range::d1(2));
+
+    let moved = particles.read_to_vec();
+    assert_eq!(moved[1].position, [3.0, 4.0, 5.0]);
+    assert_eq!(moved[1].mass, 2.0);
+}
+
+#[test]
+fn report_layout_mismatches() {
+    let layout = hac::StructLayout::from_wgsl(PARTICLES, "Particle").unwrap();
+
+    // same size but the members are packed without the padding of vec3
+    assert_eq!(
+        layout.check::<PackedParticle>(&PACKED_OFFSETS),
+        Err(hac::StructLayoutMismatch::FieldOffset {
+            index: 1,
+            name: Some("velocity".into()),
+            rust: 12,
+            wgsl: 16,
+        })
+    );
+    assert_eq!(
+        layout.check::<[f32; 7]>(&[]),
+        Err(hac::StructLayoutMismatch::Size { rust: 28, wgsl: 32 })
+    );
+    assert_eq!(
+        layout.check::<Particle>(&[0, 16]),
+        Err(hac::StructLayoutMismatch::FieldCount { rust: 2, wgsl: 3 })
+    );
+    assert_eq!(layout.check::<PackedParticle>(&[]), Ok(()));
+}
@@ -0,0 +1,37 @@
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn resources_are_send_and_sync() {
+    assert_send_sync::<hac::Context>();
+    assert_send_sync::<hac::Buffer<f32>>();
+    assert_send_sync::<hac::ChunkedBuffer<f32>>();
+    assert_send_sync::<hac::UploadBuffer<f32>>();
+    assert_send_sync::<hac::Image>();
+    assert_send_sync::<hac::Sampler>();
+    assert_send_sync::<hac::BindGroup>();
+    assert_send_sync::<hac::Program>();
+    assert_send_sync::<hac::Kernel>();
+    assert_send_sync::<hac::CommandQueue>();
+}
+
+#[test]
+fn create_and_read_buffers_concurrently() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffers: Vec<hac::Buffer<u32>> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..4u32)
+            .map(|i| {
+                let context = &context;
+                scope.spawn(move || context.buffer_from_slice(&[i; 16]))
+            })
+            .collect();
+
+        workers.into_iter().map(|w| w.join().unwrap()).collect()
+    });
+
+    std::thread::scope(|scope| {
+        buffers.iter().enumerate().for_each(|(i, buffer)| {
+            scope.spawn(move || assert_eq!(buffer.read_to_vec(), vec![i as u32; 16]));
+        });
+    });
+}
@@ -0,0 +1,28 @@
+#[test]
+fn stream_uploads_through_one_buffer() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let mut upload = context.upload_buffer::<u32>(4);
+    let buffer = context.buffer::<u32>(6);
+
+    // the buffer starts mapped and zero-initialized
+    assert_eq!(upload.map_write(), &[0; 4]);
+
+    upload.map_write().copy_from_slice(&[1, 2, 3, 4]);
+    upload.copy_into(&buffer);
+    assert_eq!(buffer.read_to_vec(), vec![1, 2, 3, 4, 0, 0]);
+
+    // the elements that aren't overwritten keep the previous upload
+    upload.map_write()[0] = 5;
+    upload.copy_into(&buffer);
+    assert_eq!(buffer.read_to_vec(), vec![5, 2, 3, 4, 0, 0]);
+}
+
+#[test]
+#[should_panic(expected = "can't be copied into a buffer of 2 elements")]
+fn upload_into_a_shorter_buffer() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let mut upload = context.upload_buffer::<u32>(4);
+    upload.copy_into(&context.buffer::<u32>(2));
+}
@@ -0,0 +1,63 @@
+const COPY: &str = r#"
+@group(0) @binding(0)
+var<storage, read> input: array<f32>;
+@group(0) @binding(1)
+var<storage, read_write> output: array<f32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    output[id.x] = input[id.x];
+}
+"#;
+
+#[test]
+fn validate_dispatches_without_running_them() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let input = context.buffer::<f32>(16);
+    let output = context.buffer::<f32>(16);
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&input, hac::BufferAccess::ReadOnly)
+        .push_buffer(&output, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(COPY);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    assert_eq!(kernel.validate(&[&bind_group], 16), Ok(()));
+
+    assert_eq!(
+        kernel.validate(&[], 16),
+        Err(hac::ValidationError::BindGroupCount {
+            expected: 1,
+            found: 0
+        })
+    );
+
+    let read_write = context
+        .bind_group_descriptor()
+        .push_buffer(&input, hac::BufferAccess::ReadWrite)
+        .push_buffer(&output, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    assert_eq!(
+        kernel.validate(&[&read_write], 16),
+        Err(hac::ValidationError::BindGroupLayout { index: 0 })
+    );
+
+    let max_per_dimension = hac::Limits::default().max_compute_workgroups_per_dimension;
+    assert!(matches!(
+        kernel.validate(&[&bind_group], max_per_dimension + 1),
+        Err(hac::ValidationError::Workgroups { .. })
+    ));
+
+    // nothing was dispatched
+    assert_eq!(output.read_to_vec(), vec![0.0; 16]);
+}
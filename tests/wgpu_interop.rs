@@ -0,0 +1,69 @@
+const DOUBLE: &str = r#"
+@group(0) @binding(0)
+var<storage, read_write> data: array<u32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    data[id.x] = data[id.x] * 2u;
+}
+"#;
+
+#[test]
+fn kernels_write_wgpu_buffers() {
+    use wgpu::util::DeviceExt as _;
+
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let handle = context
+        .wgpu_device()
+        .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::cast_slice(&[1u32, 2, 3, 4]),
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::VERTEX
+                | wgpu::BufferUsages::COPY_SRC,
+        });
+
+    let buffer = context.buffer_from_wgpu::<u32>(handle);
+    assert_eq!(buffer.len(), 4);
+    assert!(buffer.usage().copy_src);
+    assert!(!buffer.usage().copy_dst);
+
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(DOUBLE);
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    kernel.dispatch(4);
+
+    assert_eq!(buffer.read_to_vec(), vec![2, 4, 6, 8]);
+    assert!(buffer
+        .as_wgpu()
+        .usage()
+        .contains(wgpu::BufferUsages::VERTEX));
+}
+
+#[test]
+#[should_panic(expected = "storage usage")]
+fn wgpu_buffers_need_storage_usage() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let handle = context
+        .wgpu_device()
+        .create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 16,
+            usage: wgpu::BufferUsages::VERTEX,
+            mapped_at_creation: false,
+        });
+
+    context.buffer_from_wgpu::<u32>(handle);
+}
@@ -0,0 +1,120 @@
+fn kernel_source(len: u32) -> String {
+    format!(
+        r#"
+        @group(0) @binding(0)
+        var<storage, read_write> data: array<f32>;
+
+        var<workgroup> shared_data: array<f32, {len}>;
+        var<workgroup> count: u32;
+
+        @compute @workgroup_size(64)
+        fn main(@builtin(local_invocation_index) i: u32) {{
+            shared_data[i] = data[i];
+            count = 1u;
+            workgroupBarrier();
+            data[i] = shared_data[63u - i] + f32(count);
+        }}
+        "#
+    )
+}
+
+#[test]
+fn reflect_the_workgroup_storage_size() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+
+    let buffer = context.buffer_from_slice(&[0.0f32; 64]);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(&kernel_source(64));
+    let kernel = context.kernel(&hac::KernelInfo {
+        program: &program,
+        entry_point: "main",
+        bind_groups: &[&bind_group],
+        push_constants_range: None,
+    });
+
+    // the u32 is rounded up to 16 bytes
+    assert_eq!(kernel.workgroup_storage_size(), Some(64 * 4 + 16));
+    assert!(context.limits().max_compute_workgroup_storage_size >= 64 * 4 + 16);
+}
+
+#[test]
+fn reject_kernels_over_the_workgroup_storage_limit() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let limit = context.limits().max_compute_workgroup_storage_size;
+
+    let buffer = context.buffer_from_slice(&[0.0f32; 64]);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    let program = context.program_from_wgsl(&kernel_source(limit / 4));
+    let result = hac::Kernel::try_new(
+        &context,
+        &hac::KernelInfo {
+            program: &program,
+            entry_point: "main",
+            bind_groups: &[&bind_group],
+            push_constants_range: None,
+        },
+    );
+
+    let error = result.unwrap_err().to_string();
+    assert!(
+        error.contains("max_compute_workgroup_storage_size"),
+        "{error}"
+    );
+}
+
+#[test]
+fn reject_kernels_over_the_workgroup_size_limits() {
+    let context = hac::Context::new(&hac::ContextInfo::default());
+    let limits = context.limits();
+
+    let buffer = context.buffer_from_slice(&[0.0f32; 64]);
+    let bind_group = context
+        .bind_group_descriptor()
+        .push_buffer(&buffer, hac::BufferAccess::ReadWrite)
+        .into_bind_group();
+
+    // every dimension is within its limit but the invocations aren't
+    let side = limits
+        .max_compute_workgroup_size_x
+        .min(limits.max_compute_workgroup_size_y);
+    assert!(side * side > limits.max_compute_invocations_per_workgroup);
+
+    let program = context.program_from_wgsl(&format!(
+        r#"
+        @group(0) @binding(0)
+        var<storage, read_write> data: array<f32>;
+
+        @compute @workgroup_size({side}, {side})
+        fn main(@builtin(local_invocation_index) i: u32) {{
+            data[i] = 1.0;
+        }}
+        "#
+    ));
+    let result = hac::Kernel::try_new(
+        &context,
+        &hac::KernelInfo {
+            program: &program,
+            entry_point: "main",
+            bind_groups: &[&bind_group],
+            push_constants_range: None,
+        },
+    );
+
+    let error = result.unwrap_err().to_string();
+    assert!(
+        error.contains(&format!("{side}x{side}x1 ({} invocations)", side * side)),
+        "{error}"
+    );
+    assert!(
+        error.contains("max_compute_invocations_per_workgroup"),
+        "{error}"
+    );
+}